@@ -1247,7 +1247,7 @@ export class Compute_ExecutionMetrics extends $.Struct {
   static readonly _capnp = {
     displayName: "ExecutionMetrics",
     id: "e415891f6bc6c98c",
-    size: new $.ObjectSize(40, 0),
+    size: new $.ObjectSize(48, 2),
   };
   /**
 * CPU time used
@@ -1299,6 +1299,36 @@ export class Compute_ExecutionMetrics extends $.Struct {
   set outputBytes(value: bigint) {
     $.utils.setUint64(32, value, this);
   }
+  /**
+* Wall-clock duration (monotonic clock)
+*
+*/
+  get wallTimeNs(): bigint {
+    return $.utils.getUint64(40, this);
+  }
+  set wallTimeNs(value: bigint) {
+    $.utils.setUint64(40, value, this);
+  }
+  /**
+* Unit that ran the job
+*
+*/
+  get service(): string {
+    return $.utils.getText(0, this);
+  }
+  set service(value: string) {
+    $.utils.setText(0, value, this);
+  }
+  /**
+* Action invoked on the unit
+*
+*/
+  get action(): string {
+    return $.utils.getText(1, this);
+  }
+  set action(value: string) {
+    $.utils.setText(1, value, this);
+  }
   toString(): string { return "Compute_ExecutionMetrics_" + super.toString(); }
 }
 export class Compute$Client {
@@ -149,6 +149,10 @@ export const OFFSET_PINGPONG_CONTROL: number = 1445888;
 
 export const SIZE_PINGPONG_CONTROL: number = 64;
 
+export const OFFSET_DRONE_TELEMETRY: number = 1445952;
+
+export const SIZE_DRONE_TELEMETRY: number = 64;
+
 export const OFFSET_BIRD_BUFFER_A: number = 1449984;
 
 export const OFFSET_BIRD_BUFFER_B: number = 3940352;
@@ -231,6 +231,12 @@ export const OFFSET_PINGPONG_CONTROL = 0x161000 as const;
 /** 64 bytes */
 export const SIZE_PINGPONG_CONTROL = 64 as const;
 
+/** Latest vehicle state */
+export const OFFSET_DRONE_TELEMETRY = 0x161040 as const;
+
+/** 64 bytes */
+export const SIZE_DRONE_TELEMETRY = 64 as const;
+
 /** offsetBirdBufferA */
 export const OFFSET_BIRD_BUFFER_A = 0x162000 as const;
 
@@ -470,6 +476,8 @@ export const CONSTS = {
   SIZE_BIRD_STATE,
   OFFSET_PINGPONG_CONTROL,
   SIZE_PINGPONG_CONTROL,
+  OFFSET_DRONE_TELEMETRY,
+  SIZE_DRONE_TELEMETRY,
   OFFSET_BIRD_BUFFER_A,
   OFFSET_BIRD_BUFFER_B,
   SIZE_BIRD_BUFFER,
@@ -2750,12 +2750,12 @@ type Compute_ExecutionMetrics struct{ capnp.Struct }
 const Compute_ExecutionMetrics_TypeID = 0xe415891f6bc6c98c
 
 func NewCompute_ExecutionMetrics(s *capnp.Segment) (Compute_ExecutionMetrics, error) {
-	st, err := capnp.NewStruct(s, capnp.ObjectSize{DataSize: 40, PointerCount: 0})
+	st, err := capnp.NewStruct(s, capnp.ObjectSize{DataSize: 48, PointerCount: 2})
 	return Compute_ExecutionMetrics{st}, err
 }
 
 func NewRootCompute_ExecutionMetrics(s *capnp.Segment) (Compute_ExecutionMetrics, error) {
-	st, err := capnp.NewRootStruct(s, capnp.ObjectSize{DataSize: 40, PointerCount: 0})
+	st, err := capnp.NewRootStruct(s, capnp.ObjectSize{DataSize: 48, PointerCount: 2})
 	return Compute_ExecutionMetrics{st}, err
 }
 
@@ -2809,12 +2809,58 @@ func (s Compute_ExecutionMetrics) SetOutputBytes(v uint64) {
 	s.Struct.SetUint64(32, v)
 }
 
+func (s Compute_ExecutionMetrics) WallTimeNs() uint64 {
+	return s.Struct.Uint64(40)
+}
+
+func (s Compute_ExecutionMetrics) SetWallTimeNs(v uint64) {
+	s.Struct.SetUint64(40, v)
+}
+
+func (s Compute_ExecutionMetrics) Service() (string, error) {
+	p, err := s.Struct.Ptr(0)
+	return p.Text(), err
+}
+
+func (s Compute_ExecutionMetrics) HasService() bool {
+	p, err := s.Struct.Ptr(0)
+	return p.IsValid() || err != nil
+}
+
+func (s Compute_ExecutionMetrics) ServiceBytes() ([]byte, error) {
+	p, err := s.Struct.Ptr(0)
+	return p.TextBytes(), err
+}
+
+func (s Compute_ExecutionMetrics) SetService(v string) error {
+	return s.Struct.SetText(0, v)
+}
+
+func (s Compute_ExecutionMetrics) Action() (string, error) {
+	p, err := s.Struct.Ptr(1)
+	return p.Text(), err
+}
+
+func (s Compute_ExecutionMetrics) HasAction() bool {
+	p, err := s.Struct.Ptr(1)
+	return p.IsValid() || err != nil
+}
+
+func (s Compute_ExecutionMetrics) ActionBytes() ([]byte, error) {
+	p, err := s.Struct.Ptr(1)
+	return p.TextBytes(), err
+}
+
+func (s Compute_ExecutionMetrics) SetAction(v string) error {
+	return s.Struct.SetText(1, v)
+}
+
 // Compute_ExecutionMetrics_List is a list of Compute_ExecutionMetrics.
 type Compute_ExecutionMetrics_List struct{ capnp.List }
 
 // NewCompute_ExecutionMetrics creates a new list of Compute_ExecutionMetrics.
 func NewCompute_ExecutionMetrics_List(s *capnp.Segment, sz int32) (Compute_ExecutionMetrics_List, error) {
-	l, err := capnp.NewCompositeList(s, capnp.ObjectSize{DataSize: 40, PointerCount: 0}, sz)
+	l, err := capnp.NewCompositeList(s, capnp.ObjectSize{DataSize: 48, PointerCount: 2}, sz)
 	return Compute_ExecutionMetrics_List{l}, err
 }
 
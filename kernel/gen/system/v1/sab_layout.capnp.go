@@ -82,6 +82,8 @@ const (
 	SizeBirdState            = uint32(4096)
 	OffsetPingpongControl    = uint32(1445888)
 	SizePingpongControl      = uint32(64)
+	OffsetDroneTelemetry     = uint32(1445952)
+	SizeDroneTelemetry       = uint32(64)
 	OffsetBirdBufferA        = uint32(1449984)
 	OffsetBirdBufferB        = uint32(3940352)
 	SizeBirdBuffer           = uint32(2360000)
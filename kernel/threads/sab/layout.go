@@ -141,6 +141,10 @@ const (
 	OFFSET_PINGPONG_CONTROL = system.OffsetPingpongControl
 	SIZE_PINGPONG_CONTROL   = system.SizePingpongControl
 
+	// Drone Telemetry (drivers MAVLink socket)
+	OFFSET_DRONE_TELEMETRY = system.OffsetDroneTelemetry
+	SIZE_DRONE_TELEMETRY   = system.SizeDroneTelemetry
+
 	// Bird Population Data (Dual Buffers)
 	OFFSET_BIRD_BUFFER_A = system.OffsetBirdBufferA
 	OFFSET_BIRD_BUFFER_B = system.OffsetBirdBufferB
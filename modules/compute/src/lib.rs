@@ -9,11 +9,13 @@ getrandom::register_custom_getrandom!(sdk::js_interop::getrandom_custom);
 pub mod benchmarks;
 
 use engine::ComputeEngine;
+use futures::StreamExt;
 use log::info;
+use sdk::credits::BudgetVerifier;
 use sdk::{Epoch, Reactor, IDX_SYSTEM_EPOCH};
 use units::{
     AudioUnit, BoidUnit, CryptoUnit, DataUnit, DroneUnit, GpuUnit, ImageUnit, MathUnit,
-    PhysicsEngine, VideoUnit,
+    PhysicsEngine, StorageUnit, VideoUnit,
 };
 
 // --- PERSISTENT SAB CACHE ---
@@ -44,7 +46,12 @@ fn initialize_engine() -> ComputeEngine {
     // Register Unit Proxies (Arc for thread-safety)
     engine.register(Arc::new(ImageUnit::new()));
     engine.register(Arc::new(CryptoUnit::new()));
-    engine.register(Arc::new(DataUnit::new()));
+    // Held data results spill to the encrypted host vault under memory pressure
+    let data_unit = match StorageUnit::new() {
+        Ok(vault) => DataUnit::new().with_spill_vault(Arc::new(vault), 256 * 1024 * 1024),
+        Err(_) => DataUnit::new(),
+    };
+    engine.register(Arc::new(data_unit));
     engine.register(Arc::new(AudioUnit::new()));
     engine.register(Arc::new(GpuUnit::new()));
     engine.register(Arc::new(PhysicsEngine::new()));
@@ -61,21 +68,19 @@ fn initialize_engine() -> ComputeEngine {
 }
 
 /// Standardized Memory Allocator for WebAssembly
+/// Buffers come from the scratch arena and are released when the next
+/// `compute_execute`/`compute_dispatch` call returns.
 #[no_mangle]
 pub extern "C" fn compute_alloc(size: usize) -> *mut u8 {
-    let mut buf = Vec::with_capacity(size);
-    let ptr = buf.as_mut_ptr();
-    std::mem::forget(buf);
-    ptr
+    sdk::arena::scratch_alloc(size)
 }
 
 /// Standardized Memory Deallocator for WebAssembly
+/// Scratch buffers from `compute_alloc` are left to the arena reset.
 #[no_mangle]
 pub extern "C" fn compute_free(ptr: *mut u8, size: usize) {
-    if !ptr.is_null() {
-        unsafe {
-            let _ = Vec::from_raw_parts(ptr, 0, size);
-        }
+    if !ptr.is_null() && !sdk::arena::scratch_owns(ptr) {
+        unsafe { sdk::arena::free_host_buffer(ptr, size) }
     }
 }
 
@@ -139,6 +144,9 @@ pub extern "C" fn compute_execute(
     params_ptr: *const u8,
     params_len: usize,
 ) -> *mut u8 {
+    // Job inputs live in the scratch arena until this call returns
+    let _scratch = sdk::arena::ScratchJob::begin();
+
     // 0. Context Validation
     if !sdk::is_context_valid() {
         sdk::js_interop::console_log(
@@ -258,19 +266,18 @@ pub extern "C" fn compute_execute(
 /// Standard usage: compute_dispatch(request_ptr, request_len)
 #[no_mangle]
 pub extern "C" fn compute_dispatch(request_ptr: *const u8, request_len: usize) -> *mut u8 {
+    let _scratch = sdk::arena::ScratchJob::begin();
     if !sdk::is_context_valid() || request_ptr.is_null() || request_len == 0 {
         return std::ptr::null_mut();
     }
 
     let request_bytes = unsafe { std::slice::from_raw_parts(request_ptr, request_len) };
-    let mut reader = std::io::Cursor::new(request_bytes);
 
-    // Read message from slice (zero-copy from WASM heap perspective)
-    let message_reader =
-        match capnp::serialize::read_message(&mut reader, capnp::message::ReaderOptions::new()) {
-            Ok(r) => r,
-            Err(_) => return std::ptr::null_mut(),
-        };
+    // Read message from slice using the shared job framing
+    let message_reader = match sdk::protocols::compute::read_job_message(request_bytes) {
+        Ok(r) => r,
+        Err(_) => return std::ptr::null_mut(),
+    };
 
     let job =
         match message_reader.get_root::<sdk::protocols::compute::compute::job_request::Reader>() {
@@ -360,6 +367,17 @@ pub struct ComputeKernel {
     epoch: Epoch,
 }
 
+/// Jobs from one inbox drain that may run at once
+const MAX_IN_FLIGHT_JOBS: usize = 8;
+
+/// Result of one inbox request, tagged with the request's job id
+struct JobOutcome {
+    job_id: String,
+    result: Result<Vec<u8>, engine::ComputeError>,
+    /// None when the request was malformed and never reached the engine
+    metrics: Option<engine::JobMetrics>,
+}
+
 // Helper to register capabilities (moved from ComputeKernel::new to be standalone)
 fn register_compute_capabilities(sab: &sdk::sab::SafeSAB) {
     use sdk::registry::*;
@@ -423,6 +441,10 @@ impl ComputeKernel {
     }
 
     /// Poll for new compute segments using Reactive Mutation
+    ///
+    /// Up to `MAX_IN_FLIGHT_JOBS` requests run concurrently and each result is
+    /// written as soon as its job finishes, so a long job doesn't hold back short
+    /// ones. Results can leave out of order; hosts match them by `jobId`.
     pub async fn poll(&mut self) -> bool {
         if !self.reactor.check_inbox() {
             return false;
@@ -430,61 +452,124 @@ impl ComputeKernel {
 
         self.reactor.ack_inbox();
 
-        // 1. Get Inbox data and copy to buffer
-        let data = match self.reactor.read_request() {
-            Some(d) => d,
-            None => return false,
-        };
+        // 1. Drain every queued request so bursts don't wait for the next dirty flag
+        let requests: Vec<Vec<u8>> = self.reactor.drain_requests().collect();
+        if requests.is_empty() {
+            return false;
+        }
 
-        // 2. Execute via Engine
-        // Use proper Cap'n Proto processing
-        let result = self.process_job(&data).await;
+        // 2. Execute via Engine, writing each result as its job completes
+        let Self {
+            reactor,
+            engine,
+            epoch,
+        } = self;
+        let engine = &*engine;
+        let mut outcomes = futures::stream::iter(&requests)
+            .map(|data| Self::process_job(engine, data))
+            .buffer_unordered(MAX_IN_FLIGHT_JOBS);
+
+        while let Some(outcome) = outcomes.next().await {
+            Self::write_outcome(reactor, outcome);
+            // 3. Signal completion via Epoch
+            epoch.increment();
+        }
+
+        true
+    }
+
+    /// Write one job's result to the Outbox
+    fn write_outcome(reactor: &Reactor, outcome: JobOutcome) {
+        let JobOutcome {
+            job_id,
+            result,
+            metrics,
+        } = outcome;
 
         match result {
             Ok(output) => {
                 // Return success result
-                if let Ok(serialized) = self.serialize_result(true, &output, "") {
-                    if !self.reactor.write_result(&serialized) {
-                        log::error!("Output too large for outbox: {} bytes", serialized.len());
+                if let Ok(serialized) =
+                    Self::serialize_result(&job_id, true, &output, "", metrics.as_ref())
+                {
+                    if let Err(e) = reactor.try_write_result(&serialized) {
+                        log::error!("Outbox rejected {} byte result: {}", serialized.len(), e);
                         // Write error result
-                        if let Ok(err_bytes) = self.serialize_result(false, &[], "Output too large")
+                        if let Ok(err_bytes) =
+                            Self::serialize_result(&job_id, false, &[], &e.to_string(), None)
                         {
-                            self.reactor.write_result(&err_bytes);
+                            reactor.write_result(&err_bytes);
                         }
                     }
                 }
             }
             Err(e) => {
-                log::error!("Compute job failed: {}", e);
+                log::error!("Compute job {} failed: {}", job_id, e);
                 // Write error result
-                if let Ok(err_bytes) = self.serialize_result(false, &[], &e.to_string()) {
-                    self.reactor.write_result(&err_bytes);
+                if let Ok(err_bytes) =
+                    Self::serialize_result(&job_id, false, &[], &e.to_string(), metrics.as_ref())
+                {
+                    reactor.write_result(&err_bytes);
                 }
             }
         }
-
-        // 3. Signal completion via Epoch
-        self.epoch.increment();
-
-        true
     }
 
     /// Process job using Cap'n Proto "Lens"
-    async fn process_job(&self, data: &[u8]) -> Result<Vec<u8>, engine::ComputeError> {
-        let mut reader = std::io::Cursor::new(data);
-        let message_reader =
-            capnp::serialize::read_message(&mut reader, capnp::message::ReaderOptions::new())
-                .map_err(|e| {
-                    engine::ComputeError::ExecutionFailed(format!("Capnp read error: {}", e))
-                })?;
+    async fn process_job(engine: &ComputeEngine, data: &[u8]) -> JobOutcome {
+        let malformed = |error| JobOutcome {
+            job_id: String::new(),
+            result: Err(error),
+            metrics: None,
+        };
+
+        let message_reader = match sdk::protocols::compute::read_job_message(data) {
+            Ok(reader) => reader,
+            Err(e) => {
+                return malformed(engine::ComputeError::ExecutionFailed(format!(
+                    "Capnp read error: {}",
+                    e
+                )))
+            }
+        };
 
         // Access the lens
-        let job = message_reader
+        let job = match message_reader
             .get_root::<sdk::protocols::compute::compute::job_request::Reader>()
-            .map_err(|e| {
-                engine::ComputeError::ExecutionFailed(format!("Capnp root error: {}", e))
-            })?;
+        {
+            Ok(job) => job,
+            Err(e) => return malformed(engine::ComputeError::capnp("Capnp root error")(e)),
+        };
+
+        // Read the id first so even a job with bad fields gets a matchable result
+        let job_id = job
+            .get_job_id()
+            .ok()
+            .and_then(|id| id.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        match Self::execute_job(engine, job).await {
+            Ok((result, metrics)) => JobOutcome {
+                job_id,
+                result,
+                metrics: Some(metrics),
+            },
+            Err(e) => JobOutcome {
+                job_id,
+                result: Err(e),
+                metrics: None,
+            },
+        }
+    }
 
+    /// Run a parsed job on the engine.
+    /// The outer error is a malformed field; otherwise the execution result and its metrics.
+    async fn execute_job(
+        engine: &ComputeEngine,
+        job: sdk::protocols::compute::compute::job_request::Reader<'_>,
+    ) -> Result<(Result<Vec<u8>, engine::ComputeError>, engine::JobMetrics), engine::ComputeError>
+    {
         // Zero-copy field access
         let library_reader = job
             .get_library()
@@ -537,37 +622,197 @@ impl ComputeKernel {
             input.len()
         );
 
-        self.engine.execute(library, method, input, params).await
+        // A zero budget means the host attached none; the job runs unmetered
+        let mut budget = match job.get_budget() {
+            0 => None,
+            credits => Some(BudgetVerifier::new(credits)),
+        };
+
+        Ok(engine
+            .execute_measured(library, method, input, params, budget.as_mut())
+            .await)
     }
 
     /// Helper to serialize JobResult
     fn serialize_result(
-        &self,
+        job_id: &str,
         success: bool,
         data: &[u8],
         error_msg: &str,
+        metrics: Option<&engine::JobMetrics>,
     ) -> Result<Vec<u8>, engine::ComputeError> {
-        let mut message = capnp::message::Builder::new_default();
-        let mut root = message.init_root::<sdk::protocols::compute::compute::job_result::Builder>();
+        use sdk::protocols::compute::{encode_job_result, OwnedExecutionMetrics, OwnedJobResult};
 
-        // Set status
-        if success {
-            root.set_status(sdk::protocols::compute::compute::Status::Success);
+        let mut result = if success {
+            OwnedJobResult {
+                error_message: error_msg.to_string(),
+                ..OwnedJobResult::success(data.to_vec())
+            }
         } else {
-            root.set_status(sdk::protocols::compute::compute::Status::Failed);
+            OwnedJobResult {
+                output: data.to_vec(),
+                ..OwnedJobResult::failure(error_msg)
+            }
+        };
+        result.job_id = job_id.to_string();
+        if let Some(metrics) = metrics {
+            result.execution_time_ns = metrics.duration_ns;
+            result.metrics = Some(OwnedExecutionMetrics {
+                input_bytes: metrics.input_bytes,
+                output_bytes: metrics.output_bytes,
+                wall_time_ns: metrics.duration_ns,
+                service: metrics.service.clone(),
+                action: metrics.action.clone(),
+                ..Default::default()
+            });
         }
 
-        // Set output
-        root.set_output(data);
+        encode_job_result(&result)
+            .map_err(|e| engine::ComputeError::ExecutionFailed(format!("Serialize error: {}", e)))
+    }
+}
 
-        // Set error message
-        root.set_error_message(error_msg);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use engine::{ComputeError, ResourceLimits, UnitProxy};
+    use sdk::protocols::compute::{decode_job_result, encode_owned_job_request, OwnedJobRequest};
+    use std::sync::Arc;
+
+    /// Sleeps for as many milliseconds as its first input byte, then echoes the input
+    struct NapUnit;
+
+    #[async_trait]
+    impl UnitProxy for NapUnit {
+        fn service_name(&self) -> &str {
+            "nap"
+        }
+
+        async fn execute(
+            &self,
+            _method: &str,
+            input: &[u8],
+            _params: &[u8],
+        ) -> Result<Vec<u8>, ComputeError> {
+            let ms = input.first().copied().unwrap_or(0) as u64;
+            tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+            Ok(input.to_vec())
+        }
+
+        fn actions(&self) -> Vec<&str> {
+            vec!["sleep"]
+        }
 
-        let mut output_bytes = Vec::new();
-        capnp::serialize::write_message(&mut output_bytes, &message).map_err(|e| {
-            engine::ComputeError::ExecutionFailed(format!("Serialize error: {}", e))
-        })?;
+        fn resource_limits(&self) -> ResourceLimits {
+            ResourceLimits::for_image()
+        }
+    }
+
+    fn nap_kernel() -> (ComputeKernel, sdk::sab::SafeSAB) {
+        let sab = sdk::sab::SafeSAB::with_size(16 * 1024 * 1024);
+        let mut engine = ComputeEngine::new();
+        engine.register(Arc::new(NapUnit));
+        let kernel = ComputeKernel {
+            reactor: Reactor::new(sab.clone()),
+            engine,
+            epoch: Epoch::new(sab.clone(), IDX_SYSTEM_EPOCH),
+        };
+        (kernel, sab)
+    }
+
+    fn submit(kernel: &ComputeKernel, job_id: &str, nap_ms: u8) {
+        submit_with_budget(kernel, job_id, nap_ms, 0);
+    }
+
+    fn submit_with_budget(kernel: &ComputeKernel, job_id: &str, nap_ms: u8, budget: u64) {
+        let request = OwnedJobRequest {
+            job_id: job_id.to_string(),
+            library: "nap".to_string(),
+            method: "sleep".to_string(),
+            input: vec![nap_ms],
+            params: b"{}".to_vec(),
+            budget,
+            ..Default::default()
+        };
+        let bytes = encode_owned_job_request(&request).unwrap();
+        kernel.reactor.inbox.write_message_legacy(&bytes).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_poll_emits_results_as_jobs_complete() {
+        let (mut kernel, sab) = nap_kernel();
+        submit(&kernel, "slow", 60);
+        submit(&kernel, "fast", 0);
+        sdk::js_interop::atomic_store(sab.barrier_view(), sdk::IDX_INBOX_DIRTY, 1);
+
+        assert!(kernel.poll().await);
+
+        let results: Vec<_> = kernel
+            .reactor
+            .outbox
+            .drain_legacy()
+            .map(|bytes| decode_job_result(&bytes).unwrap())
+            .collect();
+        assert_eq!(results.len(), 2);
+
+        // The short job is not held back behind the long one
+        assert_eq!(results[0].job_id, "fast");
+        assert_eq!(results[1].job_id, "slow");
+        assert!(results.iter().all(|r| r.is_success()));
+        assert_eq!(results[1].output, vec![60]);
+        assert_eq!(kernel.epoch.current(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_poll_failure_keeps_job_id() {
+        let (mut kernel, sab) = nap_kernel();
+        let request = OwnedJobRequest {
+            job_id: "job-9".to_string(),
+            library: "missing".to_string(),
+            method: "sleep".to_string(),
+            ..Default::default()
+        };
+        let bytes = encode_owned_job_request(&request).unwrap();
+        kernel.reactor.inbox.write_message_legacy(&bytes).unwrap();
+        sdk::js_interop::atomic_store(sab.barrier_view(), sdk::IDX_INBOX_DIRTY, 1);
+
+        assert!(kernel.poll().await);
+
+        let bytes = kernel
+            .reactor
+            .outbox
+            .read_message_legacy()
+            .unwrap()
+            .unwrap();
+        let result = decode_job_result(&bytes).unwrap();
+        assert!(!result.is_success());
+        assert_eq!(result.job_id, "job-9");
+    }
 
-        Ok(output_bytes)
+    #[tokio::test]
+    async fn test_poll_enforces_job_budget() {
+        let (mut kernel, sab) = nap_kernel();
+        let estimated = ResourceLimits::for_image().estimate_cost(1);
+        submit_with_budget(&kernel, "affordable", 0, estimated);
+        submit_with_budget(&kernel, "too-poor", 0, estimated - 1);
+        sdk::js_interop::atomic_store(sab.barrier_view(), sdk::IDX_INBOX_DIRTY, 1);
+
+        assert!(kernel.poll().await);
+
+        let mut results: Vec<_> = kernel
+            .reactor
+            .outbox
+            .drain_legacy()
+            .map(|bytes| decode_job_result(&bytes).unwrap())
+            .collect();
+        results.sort_by(|a, b| a.job_id.cmp(&b.job_id));
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].job_id, "affordable");
+        assert!(results[0].is_success());
+        assert_eq!(results[1].job_id, "too-poor");
+        assert!(!results[1].is_success());
+        assert!(results[1].error_message.starts_with("Budget exceeded"));
     }
 }
@@ -4,6 +4,7 @@ use base64::{engine::general_purpose, Engine as _};
 #[allow(unused_imports)]
 use ffmpreg;
 use hound::{WavReader, WavSpec, WavWriter};
+use sdk::sab::SafeSAB;
 use serde_json::Value as JsonValue;
 use std::io::Cursor; // Use ffmpreg as a complementary toolkit
 
@@ -18,6 +19,12 @@ pub struct AudioUnit {
     config: AudioConfig,
 }
 
+/// Samples staged per block when processing directly over the SAB
+const SAB_BLOCK_SAMPLES: usize = 1024;
+
+/// Largest STFT window the phase vocoder accepts (~0.37s at 44.1kHz)
+const MAX_STFT_WINDOW: usize = 16384;
+
 #[derive(Clone)]
 struct AudioConfig {
     max_input_size: usize,  // 100MB
@@ -397,6 +404,55 @@ impl AudioUnit {
             .collect()
     }
 
+    /// ADSR amplitude envelope; the note lasts the whole clip and release ends on its last frame
+    /// Attack and decay are cut short if release would otherwise overlap them.
+    pub(crate) fn adsr(
+        &self,
+        samples: &[f32],
+        spec: &WavSpec,
+        attack_secs: f32,
+        decay_secs: f32,
+        sustain_level: f32,
+        release_secs: f32,
+    ) -> Vec<f32> {
+        let channels = spec.channels.max(1) as usize;
+        let frames = samples.len().div_ceil(channels);
+        let to_frames = |secs: f32| (secs.max(0.0) * spec.sample_rate as f32) as usize;
+
+        let attack = to_frames(attack_secs);
+        let decay = to_frames(decay_secs);
+        let sustain = sustain_level.clamp(0.0, 1.0);
+        let release = to_frames(release_secs).min(frames);
+        let release_start = frames - release;
+
+        // Level while the note is held; reaches 1.0 on the last attack frame
+        let held = |frame: usize| {
+            if frame < attack {
+                (frame + 1) as f32 / attack as f32
+            } else if frame < attack + decay {
+                let t = (frame - attack + 1) as f32 / decay as f32;
+                1.0 + (sustain - 1.0) * t
+            } else {
+                sustain
+            }
+        };
+        let release_from = held(release_start);
+
+        samples
+            .chunks(channels)
+            .enumerate()
+            .flat_map(|(frame, chunk)| {
+                let level = if frame < release_start {
+                    held(frame)
+                } else {
+                    let t = (frame - release_start + 1) as f32 / release as f32;
+                    release_from * (1.0 - t)
+                };
+                chunk.iter().map(move |&s| s * level)
+            })
+            .collect()
+    }
+
     /// Reverse audio
     fn reverse(&self, samples: &[f32]) -> Vec<f32> {
         samples.iter().rev().copied().collect()
@@ -595,37 +651,198 @@ impl AudioUnit {
         self.compressor(&highpassed, 0.6, 3.0, 5.0, 50.0, sample_rate)
     }
 
-    /// Pitch shift using simple time-domain method
-    fn pitch_shift(&self, samples: &[f32], semitones: f32) -> Vec<f32> {
-        // Simple pitch shift by resampling
-        let ratio = 2.0f32.powf(semitones / 12.0);
-        let new_len = (samples.len() as f32 / ratio) as usize;
+    /// Pitch shift without changing duration: phase-vocoder stretch, then resample back
+    pub(crate) fn pitch_shift(
+        &self,
+        samples: &[f32],
+        channels: u16,
+        semitones: f32,
+        window_size: usize,
+        hop_size: usize,
+    ) -> Result<Vec<f32>, ComputeError> {
+        let factor = 2.0f32.powf(semitones / 12.0);
+        self.validate_stft(samples.len(), factor, window_size, hop_size)?;
+
+        Ok(Self::map_channels(samples, channels, |channel| {
+            let stretched = Self::phase_vocoder(channel, factor, window_size, hop_size);
+            Self::resample_linear(&stretched, channel.len())
+        }))
+    }
 
-        (0..new_len)
-            .map(|i| {
-                let src_idx = (i as f32 * ratio) as usize;
-                if src_idx < samples.len() {
-                    samples[src_idx]
+    /// Time stretch with an STFT phase vocoder: duration scales by `ratio`, pitch is kept
+    pub(crate) fn time_stretch(
+        &self,
+        samples: &[f32],
+        channels: u16,
+        ratio: f32,
+        window_size: usize,
+        hop_size: usize,
+    ) -> Result<Vec<f32>, ComputeError> {
+        self.validate_stft(samples.len(), ratio, window_size, hop_size)?;
+
+        Ok(Self::map_channels(samples, channels, |channel| {
+            Self::phase_vocoder(channel, ratio, window_size, hop_size)
+        }))
+    }
+
+    /// Checks STFT params and the vocoder's projected footprint before anything is allocated
+    fn validate_stft(
+        &self,
+        len: usize,
+        ratio: f32,
+        window_size: usize,
+        hop_size: usize,
+    ) -> Result<(), ComputeError> {
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return Err(ComputeError::InvalidParams(format!(
+                "Stretch ratio must be positive, got {}",
+                ratio
+            )));
+        }
+        if window_size < 2 || hop_size == 0 || hop_size > window_size {
+            return Err(ComputeError::InvalidParams(format!(
+                "hop_size must be in 1..={} for window_size {}",
+                window_size, window_size
+            )));
+        }
+        if window_size > MAX_STFT_WINDOW {
+            return Err(ComputeError::InvalidParams(format!(
+                "window_size {} exceeds maximum {}",
+                window_size, MAX_STFT_WINDOW
+            )));
+        }
+
+        // The vocoder writes `len * ratio` samples into an overlap-add buffer
+        // and a matching window-sum buffer, each padded by a hop and a window.
+        // Done in f64 so huge ratios can't overflow before the check.
+        let projected = (len as f64 * ratio as f64 + (hop_size + window_size) as f64) * 2.0;
+        let projected_bytes = projected * std::mem::size_of::<f32>() as f64;
+        if projected_bytes > self.config.max_output_size as f64 {
+            return Err(ComputeError::ExecutionFailed(format!(
+                "Output too large: ~{} > {}",
+                projected_bytes as u64, self.config.max_output_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Single-channel phase vocoder
+    ///
+    /// Frames are read every `hop_size / ratio` samples and written every
+    /// `hop_size` samples. Each bin's phase advances by its measured
+    /// instantaneous frequency times the synthesis hop, so partials stay
+    /// coherent across frames while the spacing changes.
+    fn phase_vocoder(signal: &[f32], ratio: f32, window_size: usize, hop_size: usize) -> Vec<f32> {
+        use rustfft::{num_complex::Complex, FftPlanner};
+        use std::f32::consts::PI;
+
+        let out_len = (signal.len() as f32 * ratio).round() as usize;
+        if signal.is_empty() || out_len == 0 {
+            return vec![0.0; out_len];
+        }
+
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(window_size);
+        let inverse = planner.plan_fft_inverse(window_size);
+
+        // Periodic Hann window, applied on analysis and synthesis
+        let window: Vec<f32> = (0..window_size)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / window_size as f32).cos())
+            .collect();
+
+        let analysis_hop = hop_size as f32 / ratio;
+        let frames = out_len.div_ceil(hop_size) + 1;
+        let mut output = vec![0.0f32; frames * hop_size + window_size];
+        let mut window_sum = vec![0.0f32; output.len()];
+
+        let mut buffer = vec![Complex::new(0.0f32, 0.0); window_size];
+        let mut last_phase = vec![0.0f32; window_size];
+        let mut synth_phase = vec![0.0f32; window_size];
+        let mut last_pos = 0usize;
+
+        for frame in 0..frames {
+            let pos = (frame as f32 * analysis_hop).round() as usize;
+            for (i, bin) in buffer.iter_mut().enumerate() {
+                let sample = signal.get(pos + i).copied().unwrap_or(0.0);
+                *bin = Complex::new(sample * window[i], 0.0);
+            }
+            forward.process(&mut buffer);
+
+            let advance = (pos - last_pos) as f32;
+            for (b, bin) in buffer.iter_mut().enumerate() {
+                let (magnitude, phase) = bin.to_polar();
+                if frame == 0 {
+                    synth_phase[b] = phase;
                 } else {
-                    0.0
+                    let omega = 2.0 * PI * b as f32 / window_size as f32;
+                    let true_freq = if advance > 0.0 {
+                        let deviation = phase - last_phase[b] - omega * advance;
+                        // Wrap into [-PI, PI] before converting to a frequency offset
+                        let deviation = deviation - 2.0 * PI * (deviation / (2.0 * PI)).round();
+                        omega + deviation / advance
+                    } else {
+                        omega
+                    };
+                    synth_phase[b] += true_freq * hop_size as f32;
                 }
-            })
+                last_phase[b] = phase;
+                *bin = Complex::from_polar(magnitude, synth_phase[b]);
+            }
+            inverse.process(&mut buffer);
+
+            // Overlap-add; rustfft's inverse is unnormalized
+            let start = frame * hop_size;
+            for (i, bin) in buffer.iter().enumerate() {
+                output[start + i] += bin.re / window_size as f32 * window[i];
+                window_sum[start + i] += window[i] * window[i];
+            }
+            last_pos = pos;
+        }
+
+        output.truncate(out_len);
+        output
+            .iter()
+            .zip(&window_sum)
+            .map(|(&s, &w)| if w > 1e-6 { s / w } else { 0.0 })
             .collect()
     }
 
-    /// Time stretch using simple overlap-add
-    fn time_stretch(&self, samples: &[f32], ratio: f32) -> Vec<f32> {
-        let new_len = (samples.len() as f32 * ratio) as usize;
+    /// Linear-interpolation resample of one channel to `len` samples
+    fn resample_linear(signal: &[f32], len: usize) -> Vec<f32> {
+        let Some(last) = signal.len().checked_sub(1) else {
+            return vec![0.0; len];
+        };
+        let step = signal.len() as f32 / len as f32;
 
-        (0..new_len)
+        (0..len)
             .map(|i| {
-                let src_idx = (i as f32 / ratio) as usize;
-                if src_idx < samples.len() {
-                    samples[src_idx]
-                } else {
-                    0.0
-                }
+                let pos = i as f32 * step;
+                let idx = pos as usize;
+                let frac = pos - idx as f32;
+                let a = signal[idx.min(last)];
+                let b = signal[(idx + 1).min(last)];
+                a + (b - a) * frac
+            })
+            .collect()
+    }
+
+    /// Applies `f` to each de-interleaved channel and re-interleaves the results
+    fn map_channels(samples: &[f32], channels: u16, f: impl Fn(&[f32]) -> Vec<f32>) -> Vec<f32> {
+        let channels = channels.max(1) as usize;
+        if channels == 1 {
+            return f(samples);
+        }
+
+        let processed: Vec<Vec<f32>> = (0..channels)
+            .map(|c| {
+                let channel: Vec<f32> = samples.iter().skip(c).step_by(channels).copied().collect();
+                f(&channel)
             })
+            .collect();
+        let frames = processed.iter().map(Vec::len).min().unwrap_or(0);
+
+        (0..frames)
+            .flat_map(|i| processed.iter().map(move |channel| channel[i]))
             .collect()
     }
 
@@ -743,45 +960,81 @@ impl AudioUnit {
         Ok(())
     }
 
-    // ===== SAB-NATIVE PROCESSING (Future Optimization) =====
+    // ===== SAB-NATIVE PROCESSING =====
 
     /// Execute audio operation directly on SharedArrayBuffer (zero-copy)
     ///
-    /// This is a future optimization for when the Kernel passes SAB pointers
-    /// instead of copying data. Currently unused but ready for integration.
-    #[allow(dead_code)]
-    pub unsafe fn execute_sab(
+    /// `input_ptr..input_ptr + input_len` holds little-endian f32 samples.
+    /// Results are written at `output_ptr`; passing the same pointer processes
+    /// in place. Returns the number of bytes written.
+    pub fn execute_sab(
         &self,
+        sab: &SafeSAB,
         method: &str,
-        _input_ptr: usize,
-        _input_len: usize,
-        _output_ptr: usize,
+        input_ptr: usize,
+        input_len: usize,
+        output_ptr: usize,
         params: &str,
     ) -> Result<usize, ComputeError> {
         // Parse params
         let _params: JsonValue =
             serde_json::from_str(params).unwrap_or(JsonValue::Object(serde_json::Map::new()));
 
-        // Access SAB directly (requires SAB to be accessible)
-        // For now, this is a placeholder for future SAB integration
-        // Real implementation would use: &SAB[input_ptr..input_ptr + input_len]
-
         match method {
-            "normalize" => {
-                // Future: Process directly in SAB
-                // let input = &SAB[input_ptr..input_ptr + input_len];
-                // let output = &mut SAB[output_ptr..];
-                // self.normalize_in_place(input, output)
-                Err(ComputeError::ExecutionFailed(
-                    "SAB-native processing not yet integrated".to_string(),
-                ))
-            }
+            "normalize" => self.normalize_sab(sab, input_ptr, input_len, output_ptr),
             _ => Err(ComputeError::UnknownAction {
                 service: "audio".to_string(),
                 action: method.to_string(),
             }),
         }
     }
+
+    /// Same result as `normalize`, streamed through a stack block instead of
+    /// Vecs: one pass finds the peak, a second scales and writes.
+    fn normalize_sab(
+        &self,
+        sab: &SafeSAB,
+        input_ptr: usize,
+        input_len: usize,
+        output_ptr: usize,
+    ) -> Result<usize, ComputeError> {
+        self.validate_input_size(input_len)?;
+        if !input_len.is_multiple_of(4) {
+            return Err(ComputeError::ExecutionFailed(format!(
+                "SAB input length {} is not a whole number of f32 samples",
+                input_len
+            )));
+        }
+        let sab_err =
+            |e: String| ComputeError::ExecutionFailed(format!("SAB access failed: {}", e));
+
+        let mut block = [0u8; SAB_BLOCK_SAMPLES * 4];
+
+        // Pass 1: find peak amplitude
+        let mut peak = 0.0f32;
+        for start in (0..input_len).step_by(block.len()) {
+            let chunk = &mut block[..(input_len - start).min(SAB_BLOCK_SAMPLES * 4)];
+            sab.read_raw(input_ptr + start, chunk).map_err(sab_err)?;
+            for bytes in chunk.chunks_exact(4) {
+                let sample = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                peak = peak.max(sample.abs());
+            }
+        }
+
+        // Pass 2: normalize to 0.95 to avoid clipping (silence is copied as-is)
+        let scale = if peak == 0.0 { 1.0 } else { 0.95 / peak };
+        for start in (0..input_len).step_by(block.len()) {
+            let chunk = &mut block[..(input_len - start).min(SAB_BLOCK_SAMPLES * 4)];
+            sab.read_raw(input_ptr + start, chunk).map_err(sab_err)?;
+            for bytes in chunk.chunks_exact_mut(4) {
+                let sample = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                bytes.copy_from_slice(&(sample * scale).to_le_bytes());
+            }
+            sab.write_raw(output_ptr + start, chunk).map_err(sab_err)?;
+        }
+
+        Ok(input_len)
+    }
 }
 
 impl Default for AudioUnit {
@@ -1013,6 +1266,16 @@ impl UnitProxy for AudioUnit {
                         self.crossfade(&samples1, &samples2, duration, spec1.sample_rate);
                     self.encode_wav(&crossfaded, &spec1)?
                 }
+                "adsr" => {
+                    let attack = params["attack_secs"].as_f64().unwrap_or(0.01) as f32;
+                    let decay = params["decay_secs"].as_f64().unwrap_or(0.1) as f32;
+                    let sustain = params["sustain_level"].as_f64().unwrap_or(0.7) as f32;
+                    let release = params["release_secs"].as_f64().unwrap_or(0.2) as f32;
+
+                    let (samples, spec) = self.decode_wav(input)?;
+                    let enveloped = self.adsr(&samples, &spec, attack, decay, sustain, release);
+                    self.encode_wav(&enveloped, &spec)?
+                }
                 "reverse" => {
                     let (samples, spec) = self.decode_wav(input)?;
                     let reversed = self.reverse(&samples);
@@ -1129,8 +1392,13 @@ impl UnitProxy for AudioUnit {
                     let semitones = params["semitones"].as_f64().ok_or_else(|| {
                         ComputeError::InvalidParams("Missing semitones".to_string())
                     })? as f32;
+                    let window_size = params["window_size"].as_u64().unwrap_or(2048) as usize;
+                    let hop = params["hop_size"]
+                        .as_u64()
+                        .map_or(window_size / 4, |h| h as usize);
                     let (samples, spec) = self.decode_wav(input)?;
-                    let shifted = self.pitch_shift(&samples, semitones);
+                    let shifted =
+                        self.pitch_shift(&samples, spec.channels, semitones, window_size, hop)?;
                     self.encode_wav(&shifted, &spec)?
                 }
                 "time_stretch" => {
@@ -1138,8 +1406,14 @@ impl UnitProxy for AudioUnit {
                         .as_f64()
                         .ok_or_else(|| ComputeError::InvalidParams("Missing ratio".to_string()))?
                         as f32;
+                    let window_size = params["window_size"].as_u64().unwrap_or(2048) as usize;
+                    let hop = params["hop_size"]
+                        .as_u64()
+                        .map_or(window_size / 4, |h| h as usize);
                     let (samples, spec) = self.decode_wav(input)?;
-                    let stretched = self.time_stretch(&samples, ratio);
+                    let stretched =
+                        self.time_stretch(&samples, spec.channels, ratio, window_size, hop)?;
+                    self.validate_output_size(stretched.len() * 4)?;
                     self.encode_wav(&stretched, &spec)?
                 }
                 "auto_tune" => {
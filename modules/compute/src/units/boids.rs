@@ -856,11 +856,11 @@ impl BoidUnit {
         let mut reader = std::io::Cursor::new(resource_data);
         let message_reader =
             capnp::serialize::read_message(&mut reader, capnp::message::ReaderOptions::new())
-                .map_err(|e| ComputeError::ExecutionFailed(format!("Capnp read error: {}", e)))?;
+                .map_err(ComputeError::capnp("Capnp read error"))?;
 
         let res_reader = message_reader
             .get_root::<resource::Reader>()
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Capnp root error: {}", e)))?;
+            .map_err(ComputeError::capnp("Capnp root error"))?;
 
         // 2. Extract Inline Data (Packed Parents)
         let inline_data = match res_reader.which() {
@@ -906,7 +906,7 @@ impl BoidUnit {
 
         let mut output_bytes = Vec::new();
         capnp::serialize::write_message(&mut output_bytes, &out_message)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Serialize error: {}", e)))?;
+            .map_err(ComputeError::capnp("Serialize error"))?;
 
         Ok(output_bytes)
     }
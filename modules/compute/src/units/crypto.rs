@@ -55,8 +55,71 @@ pub struct CryptoUnit {
     config: CryptoConfig,
     key_trackers: Arc<Mutex<HashMap<String, KeyUsageTracker>>>,
     rate_limiter: Arc<RateLimiter>,
+    /// Per-session Ed25519 key attributing this node's job results
+    session_key: SigningKey,
+    /// Open incremental hashes, fed chunk by chunk via `hash_stream_update`
+    hash_streams: Arc<Mutex<HashMap<u64, OpenHashStream>>>,
+    next_stream_id: AtomicU64,
 }
 
+/// Segment size `hash_stream` feeds the hasher with, unless overridden
+const HASH_STREAM_SEGMENT: usize = 1024 * 1024;
+/// Open incremental hashes allowed at once, so abandoned streams cannot pile up
+const MAX_HASH_STREAMS: usize = 64;
+/// Streams untouched for this long are dropped (ms)
+const HASH_STREAM_IDLE_MS: f64 = 60_000.0;
+
+/// An incremental hash awaiting its next chunk
+struct OpenHashStream {
+    hasher: StreamHasher,
+    last_used: f64, // Performance.now() (ms)
+}
+
+/// Incremental hash over input fed in segments
+/// Segmented and one-shot hashing of the same bytes give the same digest.
+pub enum StreamHasher {
+    Blake3(Box<blake3::Hasher>),
+    Sha256(Sha256),
+}
+
+impl StreamHasher {
+    /// `algorithm`: "blake3" or "sha256"
+    pub fn new(algorithm: &str) -> Result<Self, ComputeError> {
+        match algorithm {
+            "blake3" => Ok(StreamHasher::Blake3(Box::default())),
+            "sha256" => Ok(StreamHasher::Sha256(Sha256::new())),
+            other => Err(ComputeError::InvalidParams(format!(
+                "Unsupported stream hash algorithm: {}",
+                other
+            ))),
+        }
+    }
+
+    pub fn update(&mut self, segment: &[u8]) {
+        match self {
+            StreamHasher::Blake3(hasher) => {
+                hasher.update(segment);
+            }
+            StreamHasher::Sha256(hasher) => hasher.update(segment),
+        }
+    }
+
+    pub fn finalize_hex(self) -> String {
+        match self {
+            StreamHasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+            StreamHasher::Sha256(hasher) => hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        }
+    }
+}
+
+/// Domain separator for job result signatures, so they can never be
+/// replayed as signatures over arbitrary messages
+const JOB_RESULT_DOMAIN: &[u8] = b"inos:job-result:v1:";
+
 /// Configuration with secure defaults
 #[derive(Clone)]
 struct CryptoConfig {
@@ -207,6 +270,13 @@ impl CryptoUnit {
             config: CryptoConfig::default(),
             key_trackers: Arc::new(Mutex::new(HashMap::new())),
             rate_limiter: Arc::new(RateLimiter::new()),
+            session_key: {
+                let mut seed = Zeroizing::new([0u8; 32]);
+                sdk::js_interop::fill_random(&mut seed[..]);
+                SigningKey::from_bytes(&seed)
+            },
+            hash_streams: Arc::new(Mutex::new(HashMap::new())),
+            next_stream_id: AtomicU64::new(1),
         }
     }
 
@@ -325,6 +395,139 @@ impl CryptoUnit {
         }
     }
 
+    /// Hash `input` in bounded segments; returns the hex digest as bytes
+    /// Params: `algorithm` ("blake3" default, "sha256"), `segment_size`.
+    fn hash_stream(
+        &self,
+        input: &[u8],
+        params: &serde_json::Value,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        let mut hasher = StreamHasher::new(params["algorithm"].as_str().unwrap_or("blake3"))?;
+        let segment_size = params["segment_size"]
+            .as_u64()
+            .map(|s| s as usize)
+            .unwrap_or(HASH_STREAM_SEGMENT)
+            .clamp(1, HASH_STREAM_SEGMENT);
+        for segment in input.chunks(segment_size) {
+            hasher.update(segment);
+        }
+        Ok(Zeroizing::new(hasher.finalize_hex().into_bytes()))
+    }
+
+    /// Open an incremental hash; returns its stream id as JSON `{"stream_id": n}`
+    /// Inputs larger than `max_input_size` are fed as several updates.
+    /// Idle streams expire; when all slots are live the least recently used
+    /// stream is dropped.
+    pub(crate) fn hash_stream_begin_at(
+        &self,
+        params: &serde_json::Value,
+        now: f64,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        let hasher = StreamHasher::new(params["algorithm"].as_str().unwrap_or("blake3"))?;
+        let mut streams = self.hash_streams.lock().unwrap();
+        streams.retain(|_, stream| now - stream.last_used < HASH_STREAM_IDLE_MS);
+        if streams.len() >= MAX_HASH_STREAMS {
+            let lru = streams
+                .iter()
+                .min_by(|a, b| a.1.last_used.total_cmp(&b.1.last_used))
+                .map(|(&id, _)| id);
+            if let Some(id) = lru {
+                streams.remove(&id);
+            }
+        }
+        let stream_id = self.next_stream_id.fetch_add(1, Ordering::SeqCst);
+        streams.insert(
+            stream_id,
+            OpenHashStream {
+                hasher,
+                last_used: now,
+            },
+        );
+        Ok(Zeroizing::new(
+            serde_json::json!({ "stream_id": stream_id })
+                .to_string()
+                .into_bytes(),
+        ))
+    }
+
+    /// Feed the next chunk (`input`) into stream `stream_id`
+    pub(crate) fn hash_stream_update_at(
+        &self,
+        input: &[u8],
+        params: &serde_json::Value,
+        now: f64,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        let stream_id = Self::stream_id(params)?;
+        let mut streams = self.hash_streams.lock().unwrap();
+        let stream = Self::live_stream(&mut streams, stream_id, now)?;
+        for segment in input.chunks(HASH_STREAM_SEGMENT) {
+            stream.hasher.update(segment);
+        }
+        stream.last_used = now;
+        Ok(Zeroizing::new(Vec::new()))
+    }
+
+    /// Close stream `stream_id` and return its hex digest as bytes
+    pub(crate) fn hash_stream_finish_at(
+        &self,
+        params: &serde_json::Value,
+        now: f64,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        let stream_id = Self::stream_id(params)?;
+        let mut streams = self.hash_streams.lock().unwrap();
+        Self::live_stream(&mut streams, stream_id, now)?;
+        let stream = streams.remove(&stream_id).unwrap();
+        Ok(Zeroizing::new(stream.hasher.finalize_hex().into_bytes()))
+    }
+
+    fn hash_stream_begin(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        self.hash_stream_begin_at(params, sdk::js_interop::get_performance_now())
+    }
+
+    fn hash_stream_update(
+        &self,
+        input: &[u8],
+        params: &serde_json::Value,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        self.hash_stream_update_at(input, params, sdk::js_interop::get_performance_now())
+    }
+
+    fn hash_stream_finish(
+        &self,
+        params: &serde_json::Value,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        self.hash_stream_finish_at(params, sdk::js_interop::get_performance_now())
+    }
+
+    /// Stream `stream_id`, unless it is unknown or has sat idle too long
+    fn live_stream(
+        streams: &mut HashMap<u64, OpenHashStream>,
+        stream_id: u64,
+        now: f64,
+    ) -> Result<&mut OpenHashStream, ComputeError> {
+        let idle = match streams.get(&stream_id) {
+            Some(stream) => now - stream.last_used >= HASH_STREAM_IDLE_MS,
+            None => true,
+        };
+        if idle {
+            streams.remove(&stream_id);
+            return Err(ComputeError::InvalidParams(format!(
+                "Unknown hash stream: {}",
+                stream_id
+            )));
+        }
+        Ok(streams.get_mut(&stream_id).unwrap())
+    }
+
+    fn stream_id(params: &serde_json::Value) -> Result<u64, ComputeError> {
+        params["stream_id"]
+            .as_u64()
+            .ok_or_else(|| ComputeError::InvalidParams("Missing stream_id".to_string()))
+    }
+
     /// HMAC-SHA256 (constant-time verification)
     fn hmac_sha256(
         &self,
@@ -614,6 +817,61 @@ impl CryptoUnit {
         Ok(Zeroizing::new(vec![if is_valid { 1 } else { 0 }]))
     }
 
+    /// Message signed for a job result: domain || BLAKE3(output)
+    fn job_result_message(output: &[u8]) -> (blake3::Hash, Vec<u8>) {
+        let hash = blake3::hash(output);
+        let mut message = Vec::with_capacity(JOB_RESULT_DOMAIN.len() + 32);
+        message.extend_from_slice(JOB_RESULT_DOMAIN);
+        message.extend_from_slice(hash.as_bytes());
+        (hash, message)
+    }
+
+    /// Public half of the session key, for peers verifying this node's results
+    pub fn session_public_key(&self) -> [u8; 32] {
+        self.session_key.verifying_key().to_bytes()
+    }
+
+    /// Sign a job result's output hash with the session key (or `private_key`)
+    /// Returns JSON: {hash (hex), signature, public_key (base64)}
+    fn sign_result(
+        &self,
+        output: &[u8],
+        params: &serde_json::Value,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        let signing_key = match params.get("private_key").and_then(|v| v.as_str()) {
+            Some(private_key_b64) => {
+                let private_key = self.decode_key_secure(private_key_b64, 32)?;
+                let key_array: [u8; 32] = private_key[..32].try_into().map_err(|_| {
+                    ComputeError::ExecutionFailed("Key conversion failed".to_string())
+                })?;
+                SigningKey::from_bytes(&key_array)
+            }
+            None => self.session_key.clone(),
+        };
+
+        let (hash, message) = Self::job_result_message(output);
+        let signature = signing_key.sign(&message);
+
+        let response = serde_json::json!({
+            "hash": hash.to_hex().to_string(),
+            "signature": general_purpose::STANDARD.encode(signature.to_bytes()),
+            "public_key": general_purpose::STANDARD.encode(signing_key.verifying_key().as_bytes()),
+        });
+        serde_json::to_vec(&response)
+            .map(Zeroizing::new)
+            .map_err(|e| ComputeError::ExecutionFailed(format!("Serialization failed: {}", e)))
+    }
+
+    /// Verify a detached job result signature against the output it claims
+    fn verify_result(
+        &self,
+        output: &[u8],
+        params: &serde_json::Value,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        let (_, message) = Self::job_result_message(output);
+        self.ed25519_verify_secure(&message, params)
+    }
+
     /// Ed25519 keypair generation
     fn ed25519_keygen(&self) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
         let mut seed = [0u8; 32];
@@ -659,8 +917,6 @@ impl CryptoUnit {
 
     /// HKDF (RFC 5869)
     fn hkdf(&self, params: &serde_json::Value) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
-        use hkdf::Hkdf;
-
         let ikm_b64 = params["ikm"]
             .as_str()
             .ok_or_else(|| ComputeError::InvalidParams("Missing ikm".to_string()))?;
@@ -682,10 +938,58 @@ impl CryptoUnit {
 
         let length = params.get("length").and_then(|v| v.as_u64()).unwrap_or(32) as usize;
 
-        let hkdf = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+        Self::hkdf_sha256(&ikm, &salt, &info, length)
+    }
+
+    /// Derive a 32-byte per-purpose subkey (e.g. for `StorageEngine::new`)
+    /// from a master secret: HKDF-SHA256 with a mandatory `info` label, so
+    /// every purpose gets an independent key. Params are base64; unlike
+    /// `hkdf`, malformed salt or info is rejected rather than ignored.
+    fn derive_key(&self, params: &serde_json::Value) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        let decode = |name: &str| -> Result<Option<Zeroizing<Vec<u8>>>, ComputeError> {
+            params
+                .get(name)
+                .and_then(|v| v.as_str())
+                .map(|s| {
+                    general_purpose::STANDARD
+                        .decode(s)
+                        .map(Zeroizing::new)
+                        .map_err(|_| {
+                            ComputeError::InvalidParams(format!("Invalid {} encoding", name))
+                        })
+                })
+                .transpose()
+        };
+
+        let master = decode("master")?
+            .ok_or_else(|| ComputeError::InvalidParams("Missing master".to_string()))?;
+        if master.len() < 16 {
+            return Err(ComputeError::InvalidParams(
+                "Master secret must be at least 16 bytes".to_string(),
+            ));
+        }
+        let info = decode("info")?
+            .filter(|info| !info.is_empty())
+            .ok_or_else(|| {
+                ComputeError::InvalidParams("Missing info (key purpose label)".to_string())
+            })?;
+        let salt = decode("salt")?.unwrap_or_default();
+
+        Self::hkdf_sha256(&master, &salt, &info, 32)
+    }
+
+    fn hkdf_sha256(
+        ikm: &[u8],
+        salt: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> Result<Zeroizing<Vec<u8>>, ComputeError> {
+        use hkdf::Hkdf;
+
+        let hkdf = Hkdf::<Sha256>::new(Some(salt), ikm);
         let mut okm = Zeroizing::new(vec![0u8; length]);
 
-        hkdf.expand(&info, &mut okm)
+        hkdf.expand(info, &mut okm)
             .map_err(|e| ComputeError::ExecutionFailed(e.to_string()))?;
 
         Ok(okm)
@@ -741,6 +1045,10 @@ impl UnitProxy for CryptoUnit {
             "sha256",
             "sha512",
             "blake3",
+            "hash_stream",
+            "hash_stream_begin",
+            "hash_stream_update",
+            "hash_stream_finish",
             "hmac_sha256",
             "hmac_sha256_verify",
             "hmac_sha512",
@@ -753,7 +1061,10 @@ impl UnitProxy for CryptoUnit {
             "ed25519_sign",
             "ed25519_verify",
             "x25519_key_exchange",
+            "sign_result",
+            "verify_result",
             "hkdf",
+            "derive_key",
             "argon2id",
         ]
     }
@@ -778,8 +1089,8 @@ impl UnitProxy for CryptoUnit {
         // Determine operation type for rate limiting
         let operation = match action {
             // Changed from method
-            "ed25519_sign" => Operation::Sign,
-            "ed25519_verify" => Operation::Verify,
+            "ed25519_sign" | "sign_result" => Operation::Sign,
+            "ed25519_verify" | "verify_result" => Operation::Verify,
             "aes256_gcm_encrypt" | "chacha20_encrypt" => Operation::Encrypt,
             "aes256_gcm_decrypt" | "chacha20_decrypt" => Operation::Decrypt,
             _ => Operation::Hash,
@@ -795,6 +1106,10 @@ impl UnitProxy for CryptoUnit {
             "sha256" => Ok(self.sha256_secure(input)),
             "sha512" => Ok(self.sha512_secure(input)),
             "blake3" => Ok(self.blake3_secure(input)),
+            "hash_stream" => self.hash_stream(input, &params),
+            "hash_stream_begin" => self.hash_stream_begin(&params),
+            "hash_stream_update" => self.hash_stream_update(input, &params),
+            "hash_stream_finish" => self.hash_stream_finish(&params),
             "hmac_sha256" => self.hmac_sha256(input, &params),
             "hmac_sha256_verify" => self.hmac_sha256_verify(input, &params),
             "hmac_sha512" => self.hmac_sha512(input, &params),
@@ -812,8 +1127,13 @@ impl UnitProxy for CryptoUnit {
             "ed25519_verify" => self.ed25519_verify_secure(input, &params),
             "x25519_key_exchange" => self.x25519_key_exchange(&params),
 
+            // Job result provenance
+            "sign_result" => self.sign_result(input, &params),
+            "verify_result" => self.verify_result(input, &params),
+
             // Key derivation
             "hkdf" => self.hkdf(&params),
+            "derive_key" => self.derive_key(&params),
             "argon2id" => self.argon2id(&params),
 
             _ => Err(ComputeError::UnknownAction {
@@ -1,4 +1,4 @@
-use crate::engine::{ComputeError, ResourceLimits, UnitProxy};
+use crate::engine::{check_deadline, ComputeError, ResourceLimits, UnitProxy};
 use arrow::array::*;
 use arrow::compute;
 use arrow::csv;
@@ -6,12 +6,18 @@ use arrow::datatypes::*;
 use arrow::ipc;
 use arrow::json;
 use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
 use async_trait::async_trait;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::ArrowWriter;
 use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Cursor;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::storage::StorageUnit;
 
 /// Production-grade data processing library using Apache Arrow
 ///
@@ -24,6 +30,100 @@ use std::sync::Arc;
 /// - Parquet, CSV, JSON, Arrow IPC support
 pub struct DataUnit {
     config: DataConfig,
+    /// Results held by handle for chunked `fetch` (Arrow Flight-style pull protocol)
+    pool: Mutex<BatchPool>,
+    next_handle: AtomicU64,
+    /// Where cold held batches go when the pool exceeds its memory budget
+    vault: Option<Arc<dyn SpillVault>>,
+    /// Random per-unit prefix of vault keys, so units sharing a vault never collide
+    spill_namespace: u64,
+    /// Bumped for every spill, so no two spills ever share a vault key
+    next_spill: AtomicU64,
+}
+
+/// Encrypted backing store for batches evicted from the result pool
+///
+/// Spilled batches are written as Arrow IPC under a key unique to that spill.
+/// Their BLAKE3 digest is kept in the pool, so a reload can verify it got
+/// back exactly what was evicted.
+#[async_trait]
+pub trait SpillVault: Send + Sync {
+    async fn store(&self, key: &str, data: &[u8]) -> Result<(), String>;
+    async fn load(&self, key: &str) -> Result<Vec<u8>, String>;
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+#[async_trait]
+impl SpillVault for StorageUnit {
+    async fn store(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        let params = serde_json::json!({ "content_hash": key, "priority": "low" }).to_string();
+        self.execute("store_chunk", data, &params)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn load(&self, key: &str) -> Result<Vec<u8>, String> {
+        let params = serde_json::json!({ "content_hash": key }).to_string();
+        self.execute("load_chunk", &[], &params)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let params = serde_json::json!({ "content_hash": key }).to_string();
+        self.execute("delete_chunk", &[], &params)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Held result batches, resident in linear memory or spilled to the vault
+#[derive(Default)]
+struct BatchPool {
+    resident: HashMap<u64, (RecordBatch, usize)>,
+    spilled: HashMap<u64, SpilledBatch>,
+    recency: VecDeque<u64>, // coldest first
+    resident_bytes: usize,
+}
+
+/// Where a spilled batch lives in the vault and what it must hash to
+#[derive(Clone, PartialEq)]
+struct SpilledBatch {
+    key: String,
+    digest: String,
+}
+
+impl BatchPool {
+    fn len(&self) -> usize {
+        self.resident.len() + self.spilled.len()
+    }
+
+    fn touch(&mut self, handle: u64) {
+        self.recency.retain(|&h| h != handle);
+        self.recency.push_back(handle);
+    }
+
+    fn insert(&mut self, handle: u64, batch: RecordBatch) {
+        let size = batch.get_array_memory_size();
+        self.resident_bytes += size;
+        self.resident.insert(handle, (batch, size));
+        self.touch(handle);
+    }
+
+    fn take_resident(&mut self, handle: u64) -> Option<RecordBatch> {
+        let (batch, size) = self.resident.remove(&handle)?;
+        self.resident_bytes -= size;
+        Some(batch)
+    }
+
+    /// Forget `handle` entirely, returning its spill if it had one
+    fn remove(&mut self, handle: u64) -> Option<SpilledBatch> {
+        self.recency.retain(|&h| h != handle);
+        self.take_resident(handle);
+        self.spilled.remove(&handle)
+    }
 }
 
 #[derive(Clone)]
@@ -33,10 +133,19 @@ struct DataConfig {
     max_rows: usize,        // 100M rows
     #[allow(dead_code)]
     streaming_threshold: usize, // 100MB - use streaming above this (future)
-    #[allow(dead_code)]
-    chunk_size: usize, // 10k rows per chunk for streaming (future)
+    chunk_size: usize,      // 10k rows per chunk for fetch
+    max_held_results: usize, // Held result handles before fetch refuses new ones
+    memory_budget: usize,   // Resident bytes of held results before spilling
+    csv_infer_rows: usize,  // Data rows sampled for CSV type inference
 }
 
+/// Leading bytes of the Arrow IPC file format; streams start with a continuation marker instead
+const ARROW_FILE_MAGIC: &[u8] = b"ARROW1";
+/// Rows processed between `check_deadline` calls in per-row loops
+const DEADLINE_CHECK_ROWS: usize = 4096;
+/// 2^63: whole floats in `-I64_BOUND..I64_BOUND` convert to i64 exactly
+const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
 impl Default for DataConfig {
     fn default() -> Self {
         Self {
@@ -45,6 +154,160 @@ impl Default for DataConfig {
             max_rows: 100_000_000,                   // 100M rows
             streaming_threshold: 100 * 1024 * 1024,  // 100MB
             chunk_size: 10_000,                      // 10k rows per chunk
+            max_held_results: 64,
+            memory_budget: 256 * 1024 * 1024, // 256MB
+            csv_infer_rows: 100,
+        }
+    }
+}
+
+/// Field separator, quote and escape bytes shared by CSV inference and the arrow reader,
+/// so both split a file into the same columns
+#[derive(Debug, Clone, Copy)]
+struct CsvDialect {
+    delimiter: u8,
+    quote: u8,
+    escape: Option<u8>,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// `delimiter`, `quote` and `escape` params, each a single ASCII character
+    fn from_params(params: &JsonValue) -> Result<Self, ComputeError> {
+        let byte_param = |key: &str| -> Result<Option<u8>, ComputeError> {
+            match params.get(key).and_then(|v| v.as_str()) {
+                None => Ok(None),
+                Some(s) if s.len() == 1 && s.is_ascii() => Ok(Some(s.as_bytes()[0])),
+                Some(s) => Err(ComputeError::InvalidParams(format!(
+                    "CSV {} must be a single ASCII character, got {:?}",
+                    key, s
+                ))),
+            }
+        };
+
+        let default = Self::default();
+        Ok(Self {
+            delimiter: byte_param("delimiter")?.unwrap_or(default.delimiter),
+            quote: byte_param("quote")?.unwrap_or(default.quote),
+            escape: byte_param("escape")?,
+        })
+    }
+
+    /// Split up to `max_records` records into fields.
+    ///
+    /// Quoted fields may contain delimiters, newlines and doubled quotes (`""`);
+    /// the escape byte, if set, makes the following byte literal inside quotes.
+    /// Blank lines are skipped, as the arrow reader does.
+    fn records(&self, input: &[u8], max_records: usize) -> Vec<Vec<String>> {
+        let mut records = Vec::new();
+        let mut record: Vec<String> = Vec::new();
+        let mut field: Vec<u8> = Vec::new();
+        let mut in_quotes = false;
+        let mut bytes = input.iter().copied().peekable();
+
+        while records.len() < max_records {
+            let Some(b) = bytes.next() else { break };
+
+            if in_quotes {
+                if Some(b) == self.escape {
+                    if let Some(next) = bytes.next() {
+                        field.push(next);
+                    }
+                } else if b == self.quote {
+                    if bytes.peek() == Some(&self.quote) {
+                        field.push(self.quote);
+                        bytes.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    field.push(b);
+                }
+            } else if b == self.quote && field.is_empty() {
+                in_quotes = true;
+            } else if b == self.delimiter {
+                record.push(String::from_utf8_lossy(&field).into_owned());
+                field.clear();
+            } else if b == b'\n' || b == b'\r' {
+                if b == b'\r' && bytes.peek() == Some(&b'\n') {
+                    bytes.next();
+                }
+                if !record.is_empty() || !field.is_empty() {
+                    record.push(String::from_utf8_lossy(&field).into_owned());
+                    records.push(std::mem::take(&mut record));
+                }
+                field.clear();
+            } else {
+                field.push(b);
+            }
+        }
+
+        if records.len() < max_records && (!record.is_empty() || !field.is_empty()) {
+            record.push(String::from_utf8_lossy(&field).into_owned());
+            records.push(record);
+        }
+        records
+    }
+}
+
+/// How `fillna` replaces the nulls of a column
+#[derive(Debug, Clone)]
+enum FillStrategy {
+    /// Constant, cast to the column's type
+    Value(JsonValue),
+    /// Mean of the column's non-null values (numeric columns, result is Float64)
+    Mean,
+    /// Last non-null value above, in row order
+    Forward,
+    /// Next non-null value below, in row order
+    Backward,
+}
+
+impl FillStrategy {
+    fn parse(strategy: &str) -> Result<Self, ComputeError> {
+        match strategy {
+            "mean" => Ok(Self::Mean),
+            "ffill" | "forward" => Ok(Self::Forward),
+            "bfill" | "backward" => Ok(Self::Backward),
+            _ => Err(ComputeError::InvalidParams(format!(
+                "Unknown fill strategy: {}",
+                strategy
+            ))),
+        }
+    }
+}
+
+/// Output layout of `json_write` and `json_write_stream`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JsonLayout {
+    /// One object per line (NDJSON)
+    Lines,
+    /// A single JSON array of row objects
+    Array,
+    /// A JSON array indented for reading; built from the whole output
+    Pretty,
+}
+
+impl JsonLayout {
+    /// `format` param: "lines" (default), "array" or "pretty"
+    fn from_params(params: &JsonValue) -> Result<Self, ComputeError> {
+        match params.get("format").and_then(|v| v.as_str()) {
+            None | Some("lines") | Some("ndjson") => Ok(Self::Lines),
+            Some("array") => Ok(Self::Array),
+            Some("pretty") => Ok(Self::Pretty),
+            Some(other) => Err(ComputeError::InvalidParams(format!(
+                "Unknown JSON format: {}",
+                other
+            ))),
         }
     }
 }
@@ -53,9 +316,25 @@ impl DataUnit {
     pub fn new() -> Self {
         Self {
             config: DataConfig::default(),
+            pool: Mutex::new(BatchPool::default()),
+            next_handle: AtomicU64::new(1),
+            vault: None,
+            spill_namespace: {
+                let mut bytes = [0u8; 8];
+                sdk::js_interop::fill_random(&mut bytes);
+                u64::from_le_bytes(bytes)
+            },
+            next_spill: AtomicU64::new(1),
         }
     }
 
+    /// Spill cold held results to `vault` once they exceed `memory_budget` bytes
+    pub fn with_spill_vault(mut self, vault: Arc<dyn SpillVault>, memory_budget: usize) -> Self {
+        self.vault = Some(vault);
+        self.config.memory_budget = memory_budget;
+        self
+    }
+
     // ===== PHASE 1: CORE I/O OPERATIONS =====
 
     /// Read Parquet file from bytes
@@ -65,19 +344,17 @@ impl DataUnit {
         let bytes = Bytes::copy_from_slice(input);
 
         let builder = ParquetRecordBatchReaderBuilder::try_new(bytes)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Parquet read failed: {}", e)))?;
+            .map_err(ComputeError::parquet("Parquet read failed"))?;
 
-        let mut reader = builder.build().map_err(|e| {
-            ComputeError::ExecutionFailed(format!("Parquet reader build failed: {}", e))
-        })?;
+        let mut reader = builder
+            .build()
+            .map_err(ComputeError::parquet("Parquet reader build failed"))?;
 
         // Read first batch (for now, we'll handle multiple batches later)
         let batch = reader
             .next()
             .ok_or_else(|| ComputeError::ExecutionFailed("No data in Parquet file".to_string()))?
-            .map_err(|e| {
-                ComputeError::ExecutionFailed(format!("Parquet batch read failed: {}", e))
-            })?;
+            .map_err(ComputeError::arrow("Parquet batch read failed"))?;
 
         Ok(batch)
     }
@@ -91,39 +368,49 @@ impl DataUnit {
             .set_compression(parquet::basic::Compression::SNAPPY)
             .build();
 
-        let mut writer =
-            ArrowWriter::try_new(cursor, batch.schema(), Some(props)).map_err(|e| {
-                ComputeError::ExecutionFailed(format!("Parquet writer creation failed: {}", e))
-            })?;
+        let mut writer = ArrowWriter::try_new(cursor, batch.schema(), Some(props))
+            .map_err(ComputeError::parquet("Parquet writer creation failed"))?;
 
         writer
             .write(batch)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Parquet write failed: {}", e)))?;
+            .map_err(ComputeError::parquet("Parquet write failed"))?;
 
         writer
             .close()
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Parquet close failed: {}", e)))?;
+            .map_err(ComputeError::parquet("Parquet close failed"))?;
 
         Ok(buffer)
     }
 
     /// Read CSV from bytes with automatic schema inference
-    fn csv_read(&self, input: &[u8], has_header: bool) -> Result<RecordBatch, ComputeError> {
-        // Infer schema from data
-        let schema = self.infer_csv_schema(input, has_header)?;
+    fn csv_read(
+        &self,
+        input: &[u8],
+        has_header: bool,
+        infer_rows: usize,
+        dialect: &CsvDialect,
+    ) -> Result<RecordBatch, ComputeError> {
+        // Infer schema from a sample of the data
+        let schema = self.infer_csv_schema(input, has_header, infer_rows, dialect)?;
 
         let cursor = Cursor::new(input);
-        let reader = csv::ReaderBuilder::new(schema)
+        let mut builder = csv::ReaderBuilder::new(schema)
             .with_header(has_header)
+            .with_delimiter(dialect.delimiter)
+            .with_quote(dialect.quote);
+        if let Some(escape) = dialect.escape {
+            builder = builder.with_escape(escape);
+        }
+        let reader = builder
             .build(cursor)
-            .map_err(|e| {
-                ComputeError::ExecutionFailed(format!("CSV reader creation failed: {}", e))
-            })?;
+            .map_err(ComputeError::arrow("CSV reader creation failed"))?;
 
         // Read all batches and combine
-        let batches: Result<Vec<_>, _> = reader.collect();
-        let batches = batches
-            .map_err(|e| ComputeError::ExecutionFailed(format!("CSV read failed: {}", e)))?;
+        let mut batches = Vec::new();
+        for batch in reader {
+            check_deadline()?;
+            batches.push(batch.map_err(ComputeError::arrow("CSV read failed"))?);
+        }
 
         if batches.is_empty() {
             return Err(ComputeError::ExecutionFailed(
@@ -136,17 +423,27 @@ impl DataUnit {
     }
 
     /// Write RecordBatch to CSV format
-    fn csv_write(&self, batch: &RecordBatch, has_header: bool) -> Result<Vec<u8>, ComputeError> {
+    fn csv_write(
+        &self,
+        batch: &RecordBatch,
+        has_header: bool,
+        dialect: &CsvDialect,
+    ) -> Result<Vec<u8>, ComputeError> {
         let mut buffer = Vec::new();
         let cursor = Cursor::new(&mut buffer);
 
-        let mut writer = csv::WriterBuilder::new()
+        let mut builder = csv::WriterBuilder::new()
             .with_header(has_header)
-            .build(cursor);
+            .with_delimiter(dialect.delimiter)
+            .with_quote(dialect.quote);
+        if let Some(escape) = dialect.escape {
+            builder = builder.with_escape(escape).with_double_quote(false);
+        }
+        let mut writer = builder.build(cursor);
 
         writer
             .write(batch)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("CSV write failed: {}", e)))?;
+            .map_err(ComputeError::arrow("CSV write failed"))?;
 
         drop(writer);
 
@@ -205,71 +502,90 @@ impl DataUnit {
         Ok(Arc::new(Schema::new(fields)))
     }
 
-    /// Infer Arrow schema from CSV headers and first data row
+    /// Infer Arrow schema from CSV headers and the first `sample_rows` data rows
+    ///
+    /// Each column starts at the narrowest type its first value fits and is widened
+    /// (Int64 -> Float64 -> Utf8) as later rows demand. Empty fields are nulls and
+    /// never affect the type; a column with no values at all defaults to Utf8.
     fn infer_csv_schema(
         &self,
         input: &[u8],
         has_header: bool,
+        sample_rows: usize,
+        dialect: &CsvDialect,
     ) -> Result<Arc<Schema>, ComputeError> {
-        use std::io::BufRead;
+        let max_records = sample_rows.max(1) + usize::from(has_header);
+        let mut records = dialect.records(input, max_records).into_iter();
+
+        let header_names: Option<Vec<String>> = if has_header {
+            let headers = records
+                .next()
+                .ok_or_else(|| ComputeError::ExecutionFailed("No header row in CSV".to_string()))?;
+            Some(headers.iter().map(|s| s.trim().to_string()).collect())
+        } else {
+            None
+        };
 
-        let cursor = Cursor::new(input);
-        let mut lines = cursor.lines();
-
-        if !has_header {
-            // Without headers, peek at first line to count columns
-            if let Some(Ok(first_line)) = lines.next() {
-                let col_count = first_line.split(',').count();
-                let fields: Vec<Field> = (0..col_count)
-                    .map(|i| Field::new(format!("column_{}", i), DataType::Utf8, true))
-                    .collect();
-                return Ok(Arc::new(Schema::new(fields)));
+        let mut column_types: Vec<Option<DataType>> = match &header_names {
+            Some(names) => vec![None; names.len()],
+            None => Vec::new(),
+        };
+
+        for values in records {
+            if header_names.is_none() && values.len() > column_types.len() {
+                column_types.resize(values.len(), None);
             }
-            return Ok(Arc::new(Schema::new(vec![Field::new(
-                "column_0",
-                DataType::Utf8,
-                true,
-            )])));
-        }
 
-        // Read headers
-        let headers = lines
-            .next()
-            .ok_or_else(|| ComputeError::ExecutionFailed("No header row in CSV".to_string()))?
-            .map_err(|e| ComputeError::ExecutionFailed(format!("CSV header read failed: {}", e)))?;
+            for (slot, value) in column_types.iter_mut().zip(&values) {
+                let value = value.trim();
+                if value.is_empty() {
+                    continue;
+                }
+                let seen = Self::csv_value_type(value);
+                *slot = Some(match slot.take() {
+                    Some(current) => Self::widen_csv_type(current, seen),
+                    None => seen,
+                });
+            }
+        }
 
-        let header_names: Vec<&str> = headers.split(',').map(|s| s.trim()).collect();
+        let names = header_names.unwrap_or_else(|| {
+            (0..column_types.len().max(1))
+                .map(|i| format!("column_{}", i))
+                .collect()
+        });
+        column_types.resize(names.len(), None);
 
-        // Try to read first data row for type inference
-        if let Some(Ok(first_row)) = lines.next() {
-            let values: Vec<&str> = first_row.split(',').map(|s| s.trim()).collect();
+        let fields: Vec<Field> = names
+            .into_iter()
+            .zip(column_types)
+            .map(|(name, data_type)| Field::new(name, data_type.unwrap_or(DataType::Utf8), true))
+            .collect();
 
-            let fields: Vec<Field> = header_names
-                .iter()
-                .zip(values.iter())
-                .map(|(name, value)| {
-                    // Infer type from value
-                    let data_type = if value.parse::<i64>().is_ok() {
-                        DataType::Int64
-                    } else if value.parse::<f64>().is_ok() {
-                        DataType::Float64
-                    } else if value.parse::<bool>().is_ok() {
-                        DataType::Boolean
-                    } else {
-                        DataType::Utf8
-                    };
-                    Field::new(*name, data_type, true)
-                })
-                .collect();
+        Ok(Arc::new(Schema::new(fields)))
+    }
 
-            Ok(Arc::new(Schema::new(fields)))
+    /// Narrowest Arrow type a single non-empty CSV field parses as
+    fn csv_value_type(value: &str) -> DataType {
+        if value.parse::<i64>().is_ok() {
+            DataType::Int64
+        } else if value.parse::<f64>().is_ok() {
+            DataType::Float64
+        } else if value.parse::<bool>().is_ok() {
+            DataType::Boolean
         } else {
-            // No data rows, default to Utf8 for all columns
-            let fields: Vec<Field> = header_names
-                .iter()
-                .map(|name| Field::new(*name, DataType::Utf8, true))
-                .collect();
-            Ok(Arc::new(Schema::new(fields)))
+            DataType::Utf8
+        }
+    }
+
+    /// Most permissive of two inferred CSV column types
+    fn widen_csv_type(current: DataType, seen: DataType) -> DataType {
+        match (current, seen) {
+            (a, b) if a == b => a,
+            (DataType::Int64, DataType::Float64) | (DataType::Float64, DataType::Int64) => {
+                DataType::Float64
+            }
+            _ => DataType::Utf8,
         }
     }
 
@@ -296,9 +612,8 @@ impl DataUnit {
             // Return empty RecordBatch with minimal schema
             let schema = Arc::new(Schema::new(vec![Field::new("empty", DataType::Utf8, true)]));
             let empty_array: ArrayRef = Arc::new(StringArray::from(Vec::<Option<&str>>::new()));
-            return RecordBatch::try_new(schema, vec![empty_array]).map_err(|e| {
-                ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
-            });
+            return RecordBatch::try_new(schema, vec![empty_array])
+                .map_err(ComputeError::arrow("RecordBatch creation failed"));
         }
 
         // Infer schema from first object
@@ -416,64 +731,162 @@ impl DataUnit {
         }
 
         // Create RecordBatch
-        RecordBatch::try_new(schema, arrays).map_err(|e| {
-            ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
-        })
+        RecordBatch::try_new(schema, arrays)
+            .map_err(ComputeError::arrow("RecordBatch creation failed"))
     }
 
     /// Write RecordBatch to JSON format
-    fn json_write(&self, batch: &RecordBatch) -> Result<Vec<u8>, ComputeError> {
+    fn json_write(&self, batch: &RecordBatch, layout: JsonLayout) -> Result<Vec<u8>, ComputeError> {
+        self.json_write_batches(std::iter::once(Ok(batch.clone())), layout)
+    }
+
+    /// Write every batch of an Arrow IPC stream as one JSON document.
+    /// Batches are decoded and written one at a time, never concatenated.
+    fn json_write_stream(&self, input: &[u8], layout: JsonLayout) -> Result<Vec<u8>, ComputeError> {
+        let cursor = Cursor::new(input);
+
+        let reader = ipc::reader::StreamReader::try_new(cursor, None)
+            .map_err(ComputeError::arrow("Arrow IPC read failed"))?;
+
+        let batches = reader.map(|batch| {
+            let batch = batch.map_err(ComputeError::arrow("Arrow IPC batch read failed"))?;
+            self.validate_size(&batch)?;
+            Ok(batch)
+        });
+        self.json_write_batches(batches, layout)
+    }
+
+    fn json_write_batches(
+        &self,
+        batches: impl Iterator<Item = Result<RecordBatch, ComputeError>>,
+        layout: JsonLayout,
+    ) -> Result<Vec<u8>, ComputeError> {
+        match layout {
+            JsonLayout::Lines => Self::write_json_rows::<json::writer::LineDelimited>(batches),
+            JsonLayout::Array => Self::write_json_rows::<json::writer::JsonArray>(batches),
+            JsonLayout::Pretty => {
+                let array = Self::write_json_rows::<json::writer::JsonArray>(batches)?;
+                let rows: JsonValue = serde_json::from_slice(&array).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON row parse failed: {}", e))
+                })?;
+                serde_json::to_vec_pretty(&rows).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON pretty print failed: {}", e))
+                })
+            }
+        }
+    }
+
+    /// Feed batches through one arrow JSON writer so the output is a single document
+    fn write_json_rows<F: json::writer::JsonFormat>(
+        batches: impl Iterator<Item = Result<RecordBatch, ComputeError>>,
+    ) -> Result<Vec<u8>, ComputeError> {
+        let mut buffer = Vec::new();
+        let mut writer = json::writer::Writer::<_, F>::new(&mut buffer);
+
+        for batch in batches {
+            check_deadline()?;
+            writer
+                .write(&batch?)
+                .map_err(ComputeError::arrow("JSON write failed"))?;
+        }
+
+        writer
+            .finish()
+            .map_err(ComputeError::arrow("JSON finish failed"))?;
+
+        drop(writer);
+
+        Ok(buffer)
+    }
+
+    /// Read Arrow IPC format (zero-copy), concatenating all batches of the stream
+    ///
+    /// Input starting with the IPC file magic (`.arrow` / Feather v2) goes to
+    /// `arrow_file_read`; anything else is read as an IPC stream.
+    fn arrow_read(&self, input: &[u8]) -> Result<RecordBatch, ComputeError> {
+        let batch = if input.starts_with(ARROW_FILE_MAGIC) {
+            self.arrow_file_read(input)?
+        } else {
+            let cursor = Cursor::new(input);
+
+            let reader = ipc::reader::StreamReader::try_new(cursor, None)
+                .map_err(ComputeError::arrow("Arrow IPC read failed"))?;
+            let schema = reader.schema();
+
+            let batches = reader
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ComputeError::arrow("Arrow IPC batch read failed"))?;
+            if batches.is_empty() {
+                return Err(ComputeError::ExecutionFailed(
+                    "No data in Arrow IPC stream".to_string(),
+                ));
+            }
+
+            compute::concat_batches(&schema, &batches)
+                .map_err(ComputeError::arrow("Concat failed"))?
+        };
+
+        // Every action decodes its input here, so the row cap bounds CPU-heavy
+        // operations (sort, hashing, distinct) before they start
+        self.validate_size(&batch)?;
+        Ok(batch)
+    }
+
+    /// Write RecordBatch to Arrow IPC format (zero-copy)
+    fn arrow_write(&self, batch: &RecordBatch) -> Result<Vec<u8>, ComputeError> {
         let mut buffer = Vec::new();
         let cursor = Cursor::new(&mut buffer);
 
-        let mut writer = json::LineDelimitedWriter::new(cursor);
+        let mut writer = ipc::writer::StreamWriter::try_new(cursor, &batch.schema())
+            .map_err(ComputeError::arrow("Arrow IPC writer creation failed"))?;
 
         writer
             .write(batch)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("JSON write failed: {}", e)))?;
+            .map_err(ComputeError::arrow("Arrow IPC write failed"))?;
 
         writer
             .finish()
-            .map_err(|e| ComputeError::ExecutionFailed(format!("JSON finish failed: {}", e)))?;
+            .map_err(ComputeError::arrow("Arrow IPC finish failed"))?;
+
+        drop(writer);
 
         Ok(buffer)
     }
 
-    /// Read Arrow IPC format (zero-copy)
-    fn arrow_read(&self, input: &[u8]) -> Result<RecordBatch, ComputeError> {
+    /// Read Arrow IPC file format (footer + random access), concatenating all batches
+    fn arrow_file_read(&self, input: &[u8]) -> Result<RecordBatch, ComputeError> {
         let cursor = Cursor::new(input);
 
-        let reader = ipc::reader::StreamReader::try_new(cursor, None)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Arrow IPC read failed: {}", e)))?;
+        let reader = ipc::reader::FileReader::try_new(cursor, None)
+            .map_err(ComputeError::arrow("Arrow IPC file read failed"))?;
+        let schema = reader.schema();
 
-        let batch = reader
-            .into_iter()
-            .next()
-            .ok_or_else(|| ComputeError::ExecutionFailed("No data in Arrow IPC file".to_string()))?
-            .map_err(|e| {
-                ComputeError::ExecutionFailed(format!("Arrow IPC batch read failed: {}", e))
-            })?;
+        let batches = reader
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ComputeError::arrow("Arrow IPC file batch read failed"))?;
+        if batches.is_empty() {
+            return Err(ComputeError::ExecutionFailed(
+                "No data in Arrow IPC file".to_string(),
+            ));
+        }
 
-        Ok(batch)
+        compute::concat_batches(&schema, &batches).map_err(ComputeError::arrow("Concat failed"))
     }
 
-    /// Write RecordBatch to Arrow IPC format (zero-copy)
-    fn arrow_write(&self, batch: &RecordBatch) -> Result<Vec<u8>, ComputeError> {
+    /// Write RecordBatch to Arrow IPC file format, as read by pyarrow `.arrow`/`.feather`
+    fn arrow_file_write(&self, batch: &RecordBatch) -> Result<Vec<u8>, ComputeError> {
         let mut buffer = Vec::new();
-        let cursor = Cursor::new(&mut buffer);
 
-        let mut writer =
-            ipc::writer::StreamWriter::try_new(cursor, &batch.schema()).map_err(|e| {
-                ComputeError::ExecutionFailed(format!("Arrow IPC writer creation failed: {}", e))
-            })?;
+        let mut writer = ipc::writer::FileWriter::try_new(&mut buffer, &batch.schema())
+            .map_err(ComputeError::arrow("Arrow IPC file writer creation failed"))?;
 
         writer
             .write(batch)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Arrow IPC write failed: {}", e)))?;
+            .map_err(ComputeError::arrow("Arrow IPC file write failed"))?;
 
-        writer.finish().map_err(|e| {
-            ComputeError::ExecutionFailed(format!("Arrow IPC finish failed: {}", e))
-        })?;
+        writer
+            .finish()
+            .map_err(ComputeError::arrow("Arrow IPC file finish failed"))?;
 
         drop(writer);
 
@@ -499,7 +912,7 @@ impl DataUnit {
 
         let new_schema = Arc::new(Schema::new(selected_fields));
         RecordBatch::try_new(new_schema, selected_columns)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Select failed: {}", e)))
+            .map_err(ComputeError::arrow("Select failed"))
     }
 
     /// Filter rows by boolean mask
@@ -508,8 +921,122 @@ impl DataUnit {
         batch: &RecordBatch,
         mask: &BooleanArray,
     ) -> Result<RecordBatch, ComputeError> {
-        compute::filter_record_batch(batch, mask)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Filter failed: {}", e)))
+        compute::filter_record_batch(batch, mask).map_err(ComputeError::arrow("Filter failed"))
+    }
+
+    /// Filter rows by a `query` predicate string, e.g. `age > 30 AND city == 'NYC'`
+    fn query(&self, batch: &RecordBatch, predicate: &str) -> Result<RecordBatch, ComputeError> {
+        let predicate = Predicate::parse(predicate)?;
+        let mask = self.evaluate_predicate(batch, &predicate)?;
+        self.filter(batch, &mask)
+    }
+
+    /// Boolean mask for a parsed predicate. Comparisons against null are null,
+    /// and AND/OR follow SQL three-valued logic, so `filter` drops those rows.
+    fn evaluate_predicate(
+        &self,
+        batch: &RecordBatch,
+        predicate: &Predicate,
+    ) -> Result<BooleanArray, ComputeError> {
+        let boolean_err = ComputeError::arrow("Predicate evaluation failed");
+
+        match predicate {
+            Predicate::Compare { column, op, value } => {
+                self.compare_column(batch, column, *op, value)
+            }
+            Predicate::In { column, values } => {
+                let mut mask: Option<BooleanArray> = None;
+                for value in values {
+                    let eq = self.compare_column(batch, column, CmpOp::Eq, value)?;
+                    mask = Some(match mask {
+                        None => eq,
+                        Some(existing) => {
+                            compute::or_kleene(&existing, &eq).map_err(&boolean_err)?
+                        }
+                    });
+                }
+                Ok(mask.unwrap_or_else(|| BooleanArray::from(vec![false; batch.num_rows()])))
+            }
+            Predicate::And(left, right) => compute::and_kleene(
+                &self.evaluate_predicate(batch, left)?,
+                &self.evaluate_predicate(batch, right)?,
+            )
+            .map_err(&boolean_err),
+            Predicate::Or(left, right) => compute::or_kleene(
+                &self.evaluate_predicate(batch, left)?,
+                &self.evaluate_predicate(batch, right)?,
+            )
+            .map_err(&boolean_err),
+            Predicate::Not(inner) => {
+                compute::not(&self.evaluate_predicate(batch, inner)?).map_err(&boolean_err)
+            }
+        }
+    }
+
+    /// Compare a column against a literal of a matching kind (numeric, string or bool)
+    fn compare_column(
+        &self,
+        batch: &RecordBatch,
+        column: &str,
+        op: CmpOp,
+        value: &Literal,
+    ) -> Result<BooleanArray, ComputeError> {
+        let array = batch
+            .column_by_name(column)
+            .ok_or_else(|| ComputeError::InvalidParams(format!("Column '{}' not found", column)))?;
+        let data_type = array.data_type();
+
+        // Integer columns past 2^53 lose precision as Float64, so a whole-number
+        // literal compares in the column's own integer domain instead
+        let whole = match value {
+            Literal::Int(n) => Some(*n),
+            Literal::Number(n) if n.fract() == 0.0 && (-I64_BOUND..I64_BOUND).contains(n) => {
+                Some(*n as i64)
+            }
+            _ => None,
+        };
+
+        let (target, scalar): (DataType, ArrayRef) = match (value, whole) {
+            (_, Some(n)) if data_type.is_signed_integer() => {
+                (DataType::Int64, Arc::new(Int64Array::from(vec![n])))
+            }
+            (_, Some(n)) if data_type.is_unsigned_integer() && n >= 0 => (
+                DataType::UInt64,
+                Arc::new(UInt64Array::from(vec![n as u64])),
+            ),
+            (Literal::Int(n), _) if data_type.is_numeric() => (
+                DataType::Float64,
+                Arc::new(Float64Array::from(vec![*n as f64])),
+            ),
+            (Literal::Number(n), _) if data_type.is_numeric() => {
+                (DataType::Float64, Arc::new(Float64Array::from(vec![*n])))
+            }
+            (Literal::Str(s), _) if matches!(data_type, DataType::Utf8 | DataType::LargeUtf8) => (
+                DataType::Utf8,
+                Arc::new(StringArray::from(vec![s.as_str()])),
+            ),
+            (Literal::Bool(b), _) if *data_type == DataType::Boolean => {
+                (DataType::Boolean, Arc::new(BooleanArray::from(vec![*b])))
+            }
+            _ => {
+                return Err(ComputeError::InvalidParams(format!(
+                    "Type mismatch: column '{}' is {:?} but is compared with {}",
+                    column, data_type, value
+                )))
+            }
+        };
+
+        let lhs = compute::cast(array, &target).map_err(ComputeError::arrow("Cast failed"))?;
+        let rhs = Scalar::new(scalar);
+        let result = match op {
+            CmpOp::Eq => compute::kernels::cmp::eq(&lhs, &rhs),
+            CmpOp::NotEq => compute::kernels::cmp::neq(&lhs, &rhs),
+            CmpOp::Lt => compute::kernels::cmp::lt(&lhs, &rhs),
+            CmpOp::LtEq => compute::kernels::cmp::lt_eq(&lhs, &rhs),
+            CmpOp::Gt => compute::kernels::cmp::gt(&lhs, &rhs),
+            CmpOp::GtEq => compute::kernels::cmp::gt_eq(&lhs, &rhs),
+        };
+        result.map_err(ComputeError::arrow("Comparison failed"))
     }
 
     /// Get first N rows
@@ -554,10 +1081,47 @@ impl DataUnit {
         };
 
         let indices = compute::sort_to_indices(batch.column(index), Some(options), None)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Sort failed: {}", e)))?;
+            .map_err(ComputeError::arrow("Sort failed"))?;
+
+        compute::take_record_batch(batch, &indices)
+            .map_err(ComputeError::arrow("Take after sort failed"))
+    }
+
+    /// Sort by several columns in order of precedence, each with its own direction;
+    /// later keys only break ties in the earlier ones
+    fn sort_by(
+        &self,
+        batch: &RecordBatch,
+        keys: &[(String, bool)],
+    ) -> Result<RecordBatch, ComputeError> {
+        if keys.is_empty() {
+            return Err(ComputeError::InvalidParams(
+                "Sort needs at least one key".to_string(),
+            ));
+        }
+
+        let schema = batch.schema();
+        let columns = keys
+            .iter()
+            .map(|(column, descending)| {
+                let index = schema.index_of(column).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("Column '{}' not found: {}", column, e))
+                })?;
+                Ok(compute::SortColumn {
+                    values: batch.column(index).clone(),
+                    options: Some(arrow::compute::SortOptions {
+                        descending: *descending,
+                        nulls_first: false,
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>, ComputeError>>()?;
+
+        let indices = compute::lexsort_to_indices(&columns, None)
+            .map_err(ComputeError::arrow("Sort failed"))?;
 
         compute::take_record_batch(batch, &indices)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Take after sort failed: {}", e)))
+            .map_err(ComputeError::arrow("Take after sort failed"))
     }
 
     // ===== PHASE 3: AGGREGATIONS =====
@@ -663,8 +1227,7 @@ impl DataUnit {
 
         let schema = batches[0].schema();
 
-        compute::concat_batches(&schema, &batches)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Concat failed: {}", e)))
+        compute::concat_batches(&schema, &batches).map_err(ComputeError::arrow("Concat failed"))
     }
 
     // ===== PHASE 5: TRANSFORMATIONS =====
@@ -699,8 +1262,8 @@ impl DataUnit {
             }
         };
 
-        let casted = compute::cast(array, &data_type)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Cast failed: {}", e)))?;
+        let casted =
+            compute::cast(array, &data_type).map_err(ComputeError::arrow("Cast failed"))?;
 
         // Create new batch with casted column
         let mut columns = Vec::new();
@@ -721,9 +1284,8 @@ impl DataUnit {
         }
 
         let new_schema = Arc::new(Schema::new(fields));
-        RecordBatch::try_new(new_schema, columns).map_err(|e| {
-            ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
-        })
+        RecordBatch::try_new(new_schema, columns)
+            .map_err(ComputeError::arrow("RecordBatch creation failed"))
     }
 
     /// Drop rows with null values
@@ -732,14 +1294,14 @@ impl DataUnit {
         let mut mask: Option<BooleanArray> = None;
 
         for column in batch.columns() {
-            let is_not_null = compute::is_not_null(column)
-                .map_err(|e| ComputeError::ExecutionFailed(format!("is_not_null failed: {}", e)))?;
+            let is_not_null =
+                compute::is_not_null(column).map_err(ComputeError::arrow("is_not_null failed"))?;
 
             mask = match mask {
                 None => Some(is_not_null),
                 Some(existing) => Some(
                     compute::and(&existing, &is_not_null)
-                        .map_err(|e| ComputeError::ExecutionFailed(format!("and failed: {}", e)))?,
+                        .map_err(ComputeError::arrow("and failed"))?,
                 ),
             };
         }
@@ -750,30 +1312,384 @@ impl DataUnit {
         }
     }
 
-    // ===== PHASE 6: WINDOW FUNCTIONS =====
+    /// Replace nulls column by column
+    ///
+    /// `per_column` strategies are applied as given and fail on unsuitable columns;
+    /// `default` covers every other column and skips those it cannot fill (a mean
+    /// over a string column, a constant that does not cast).
+    fn fillna(
+        &self,
+        batch: &RecordBatch,
+        per_column: &HashMap<String, FillStrategy>,
+        default: Option<&FillStrategy>,
+    ) -> Result<RecordBatch, ComputeError> {
+        let schema = batch.schema();
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        let mut fields = Vec::with_capacity(batch.num_columns());
+
+        for (field, array) in schema.fields().iter().zip(batch.columns()) {
+            let explicit = per_column.get(field.name());
+            let filled = match explicit.or(default) {
+                Some(strategy) if array.null_count() > 0 => {
+                    match self.fill_column(array, strategy) {
+                        Ok(filled) => Some(filled),
+                        Err(e) if explicit.is_some() => {
+                            return Err(ComputeError::ExecutionFailed(format!(
+                                "fillna on column '{}' failed: {}",
+                                field.name(),
+                                e
+                            )))
+                        }
+                        Err(_) => None,
+                    }
+                }
+                _ => None,
+            };
 
-    /// Row number (sequential numbering)
-    fn row_number(&self, batch: &RecordBatch) -> Result<Int64Array, ComputeError> {
-        let num_rows = batch.num_rows();
-        let row_numbers: Vec<i64> = (0..num_rows as i64).collect();
-        Ok(Int64Array::from(row_numbers))
-    }
+            match filled {
+                Some(filled) => {
+                    fields.push(Field::new(
+                        field.name(),
+                        filled.data_type().clone(),
+                        field.is_nullable(),
+                    ));
+                    columns.push(filled);
+                }
+                None => {
+                    fields.push((**field).clone());
+                    columns.push(array.clone());
+                }
+            }
+        }
 
-    /// Rank with gaps (SQL RANK function)
-    fn rank(&self, batch: &RecordBatch, column: &str) -> Result<Int64Array, ComputeError> {
-        let schema = batch.schema();
-        let index = schema.index_of(column).map_err(|e| {
-            ComputeError::ExecutionFailed(format!("Column '{}' not found: {}", column, e))
-        })?;
+        let new_schema = Arc::new(Schema::new(fields));
+        RecordBatch::try_new(new_schema, columns)
+            .map_err(ComputeError::arrow("RecordBatch creation failed"))
+    }
 
-        // Sort indices
+    /// Fill the nulls of a single column
+    fn fill_column(
+        &self,
+        array: &ArrayRef,
+        strategy: &FillStrategy,
+    ) -> Result<ArrayRef, ComputeError> {
+        match strategy {
+            FillStrategy::Value(value) => {
+                let fill = Self::json_scalar(value, array.data_type())?;
+                Self::fill_nulls_with(array, &fill)
+            }
+            FillStrategy::Mean => {
+                if !array.data_type().is_numeric() {
+                    return Err(ComputeError::ExecutionFailed(format!(
+                        "Mean fill needs a numeric column, got {:?}",
+                        array.data_type()
+                    )));
+                }
+                let floats = compute::cast(array, &DataType::Float64)
+                    .map_err(ComputeError::arrow("Cast failed"))?;
+                let values = floats
+                    .as_any()
+                    .downcast_ref::<Float64Array>()
+                    .ok_or_else(|| {
+                        ComputeError::ExecutionFailed("Float64 downcast failed".to_string())
+                    })?;
+
+                let present = values.len() - values.null_count();
+                if present == 0 {
+                    // Nothing to average; leave the column all-null
+                    return Ok(floats);
+                }
+                let mean = compute::sum(values).unwrap_or(0.0) / present as f64;
+                let fill: ArrayRef = Arc::new(Float64Array::from(vec![mean]));
+                Self::fill_nulls_with(&floats, &fill)
+            }
+            FillStrategy::Forward | FillStrategy::Backward => {
+                // Each row takes the index of the nearest non-null row in scan order
+                let len = array.len();
+                let mut indices: Vec<Option<u32>> = vec![None; len];
+                let mut last = None;
+                let order: Box<dyn Iterator<Item = usize>> =
+                    if matches!(strategy, FillStrategy::Forward) {
+                        Box::new(0..len)
+                    } else {
+                        Box::new((0..len).rev())
+                    };
+                for row in order {
+                    if array.is_valid(row) {
+                        last = Some(row as u32);
+                    }
+                    indices[row] = last;
+                }
+
+                compute::take(array.as_ref(), &UInt32Array::from(indices), None)
+                    .map_err(ComputeError::arrow("Fill take failed"))
+            }
+        }
+    }
+
+    /// Replace the nulls of `array` with the single value in `fill`
+    fn fill_nulls_with(array: &ArrayRef, fill: &ArrayRef) -> Result<ArrayRef, ComputeError> {
+        let present =
+            compute::is_not_null(array).map_err(ComputeError::arrow("is_not_null failed"))?;
+        compute::kernels::zip::zip(&present, array, &Scalar::new(fill.clone()))
+            .map_err(ComputeError::arrow("Fill failed"))
+    }
+
+    /// One-element array holding a JSON constant cast to `data_type`
+    fn json_scalar(value: &JsonValue, data_type: &DataType) -> Result<ArrayRef, ComputeError> {
+        let raw: ArrayRef = match value {
+            JsonValue::Number(n) if n.is_i64() => Arc::new(Int64Array::from(vec![n.as_i64()])),
+            JsonValue::Number(n) => Arc::new(Float64Array::from(vec![n.as_f64()])),
+            JsonValue::String(s) => Arc::new(StringArray::from(vec![s.as_str()])),
+            JsonValue::Bool(b) => Arc::new(BooleanArray::from(vec![*b])),
+            _ => {
+                return Err(ComputeError::InvalidParams(format!(
+                    "Fill value must be a number, string or bool, got {}",
+                    value
+                )))
+            }
+        };
+
+        let options = compute::CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        compute::cast_with_options(&raw, data_type, &options).map_err(|e| {
+            ComputeError::ExecutionFailed(format!(
+                "Fill value {} does not fit {:?}: {}",
+                value, data_type, e
+            ))
+        })
+    }
+
+    /// First non-null value across `columns`, row by row, cast to the first column's type
+    fn coalesce(&self, batch: &RecordBatch, columns: &[&str]) -> Result<ArrayRef, ComputeError> {
+        let selected = self.select(batch, columns)?;
+        let mut arrays = selected.columns().iter();
+        let mut result = arrays.next().cloned().ok_or_else(|| {
+            ComputeError::InvalidParams("coalesce needs at least one column".to_string())
+        })?;
+
+        for next in arrays {
+            if result.null_count() == 0 {
+                break;
+            }
+            let next = compute::cast(next, result.data_type())
+                .map_err(ComputeError::arrow("Coalesce cast failed"))?;
+            let present =
+                compute::is_not_null(&result).map_err(ComputeError::arrow("is_not_null failed"))?;
+            result = compute::kernels::zip::zip(&present, &result, &next)
+                .map_err(ComputeError::arrow("Coalesce failed"))?;
+        }
+
+        Ok(result)
+    }
+
+    /// Evaluate an arithmetic expression over numeric columns, e.g. `price * qty - 1.5`
+    ///
+    /// Integer-only `+ - *` stays Int64 (overflow is an error); anything involving a
+    /// float, and every division, is Float64. A zero divisor gives null when
+    /// `div_zero_null` is set, otherwise IEEE inf/NaN.
+    fn with_column(
+        &self,
+        batch: &RecordBatch,
+        expr: &str,
+        div_zero_null: bool,
+    ) -> Result<ArrayRef, ComputeError> {
+        let expr = ArithExpr::parse(expr)?;
+        let value = self.evaluate_arith(batch, &expr, div_zero_null)?;
+        if !value.scalar {
+            return Ok(value.array);
+        }
+
+        // Constant expression: repeat the single value for every row
+        let indices = UInt32Array::from(vec![0u32; batch.num_rows()]);
+        compute::take(value.array.as_ref(), &indices, None)
+            .map_err(ComputeError::arrow("Broadcast failed"))
+    }
+
+    fn evaluate_arith(
+        &self,
+        batch: &RecordBatch,
+        expr: &ArithExpr,
+        div_zero_null: bool,
+    ) -> Result<ArithValue, ComputeError> {
+        let arith_err = ComputeError::arrow("Arithmetic failed");
+
+        match expr {
+            ArithExpr::Column(name) => {
+                let array = batch.column_by_name(name).ok_or_else(|| {
+                    ComputeError::InvalidParams(format!("Column '{}' not found", name))
+                })?;
+                let data_type = array.data_type();
+                if !data_type.is_numeric() {
+                    return Err(ComputeError::InvalidParams(format!(
+                        "Column '{}' is {:?}, not numeric",
+                        name, data_type
+                    )));
+                }
+                let target = if data_type.is_integer() {
+                    DataType::Int64
+                } else {
+                    DataType::Float64
+                };
+                let array =
+                    compute::cast(array, &target).map_err(ComputeError::arrow("Cast failed"))?;
+                Ok(ArithValue {
+                    array,
+                    scalar: false,
+                })
+            }
+            ArithExpr::Int(n) => Ok(ArithValue {
+                array: Arc::new(Int64Array::from(vec![*n])),
+                scalar: true,
+            }),
+            ArithExpr::Float(n) => Ok(ArithValue {
+                array: Arc::new(Float64Array::from(vec![*n])),
+                scalar: true,
+            }),
+            ArithExpr::Neg(inner) => {
+                let value = self.evaluate_arith(batch, inner, div_zero_null)?;
+                Ok(ArithValue {
+                    array: compute::kernels::numeric::neg(value.array.as_ref())
+                        .map_err(&arith_err)?,
+                    scalar: value.scalar,
+                })
+            }
+            ArithExpr::Binary { op, left, right } => {
+                let left = self.evaluate_arith(batch, left, div_zero_null)?;
+                let right = self.evaluate_arith(batch, right, div_zero_null)?;
+
+                let integral = *op != ArithOp::Div
+                    && *left.array.data_type() == DataType::Int64
+                    && *right.array.data_type() == DataType::Int64;
+                let target = if integral {
+                    DataType::Int64
+                } else {
+                    DataType::Float64
+                };
+                let cast = |value: &ArithValue| {
+                    compute::cast(&value.array, &target).map_err(ComputeError::arrow("Cast failed"))
+                };
+                let (lhs, rhs) = (cast(&left)?, cast(&right)?);
+
+                let lhs_scalar;
+                let rhs_scalar;
+                let lhs_datum: &dyn Datum = if left.scalar {
+                    lhs_scalar = Scalar::new(lhs.clone());
+                    &lhs_scalar
+                } else {
+                    &lhs
+                };
+                let rhs_datum: &dyn Datum = if right.scalar {
+                    rhs_scalar = Scalar::new(rhs.clone());
+                    &rhs_scalar
+                } else {
+                    &rhs
+                };
+
+                let mut result = match op {
+                    ArithOp::Add => compute::kernels::numeric::add(lhs_datum, rhs_datum),
+                    ArithOp::Sub => compute::kernels::numeric::sub(lhs_datum, rhs_datum),
+                    ArithOp::Mul => compute::kernels::numeric::mul(lhs_datum, rhs_datum),
+                    ArithOp::Div => compute::kernels::numeric::div(lhs_datum, rhs_datum),
+                }
+                .map_err(&arith_err)?;
+
+                if *op == ArithOp::Div && div_zero_null {
+                    let zero = Scalar::new(Float64Array::from(vec![0.0]));
+                    let is_zero = compute::kernels::cmp::eq(&rhs, &zero).map_err(&arith_err)?;
+                    result = if right.scalar {
+                        if is_zero.value(0) {
+                            new_null_array(&DataType::Float64, result.len())
+                        } else {
+                            result
+                        }
+                    } else {
+                        compute::kernels::nullif::nullif(&result, &is_zero).map_err(&arith_err)?
+                    };
+                }
+
+                Ok(ArithValue {
+                    array: result,
+                    scalar: left.scalar && right.scalar,
+                })
+            }
+        }
+    }
+
+    /// Keep the first row of each set of rows equal over `columns` (all columns if empty)
+    ///
+    /// Rows are keyed by their arrow row encoding, so nulls are equal to each other
+    /// and distinct from every value.
+    fn distinct(&self, batch: &RecordBatch, columns: &[&str]) -> Result<RecordBatch, ComputeError> {
+        let key_columns = if columns.is_empty() {
+            batch.columns().to_vec()
+        } else {
+            self.select(batch, columns)?.columns().to_vec()
+        };
+
+        let sort_fields = key_columns
+            .iter()
+            .map(|c| SortField::new(c.data_type().clone()))
+            .collect();
+        let converter = RowConverter::new(sort_fields)
+            .map_err(ComputeError::arrow("Row converter creation failed"))?;
+        let rows = converter
+            .convert_columns(&key_columns)
+            .map_err(ComputeError::arrow("Row conversion failed"))?;
+
+        let mut seen = HashSet::with_capacity(rows.num_rows());
+        let mask: BooleanArray = rows.iter().map(|row| Some(seen.insert(row))).collect();
+        self.filter(batch, &mask)
+    }
+
+    /// Distinct values of one column in first-seen order, nulls as JSON null
+    fn unique_values(
+        &self,
+        batch: &RecordBatch,
+        column: &str,
+    ) -> Result<Vec<JsonValue>, ComputeError> {
+        let unique = self.distinct(&self.select(batch, &[column])?, &[])?;
+        let lines = self.json_write(&unique, JsonLayout::Lines)?;
+
+        // The line-delimited writer emits one object per row and omits null fields
+        lines
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let row: JsonValue = serde_json::from_slice(line).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON row parse failed: {}", e))
+                })?;
+                Ok(row.get(column).cloned().unwrap_or(JsonValue::Null))
+            })
+            .collect()
+    }
+
+    // ===== PHASE 6: WINDOW FUNCTIONS =====
+
+    /// Row number (sequential numbering)
+    fn row_number(&self, batch: &RecordBatch) -> Result<Int64Array, ComputeError> {
+        let num_rows = batch.num_rows();
+        let row_numbers: Vec<i64> = (0..num_rows as i64).collect();
+        Ok(Int64Array::from(row_numbers))
+    }
+
+    /// Rank with gaps (SQL RANK function)
+    fn rank(&self, batch: &RecordBatch, column: &str) -> Result<Int64Array, ComputeError> {
+        let schema = batch.schema();
+        let index = schema.index_of(column).map_err(|e| {
+            ComputeError::ExecutionFailed(format!("Column '{}' not found: {}", column, e))
+        })?;
+
+        // Sort indices
         let sort_options = compute::SortOptions {
             descending: false,
             nulls_first: false,
         };
 
         let indices = compute::sort_to_indices(batch.column(index), Some(sort_options), None)
-            .map_err(|e| ComputeError::ExecutionFailed(format!("Sort failed: {}", e)))?;
+            .map_err(ComputeError::arrow("Sort failed"))?;
 
         // Assign ranks
         let mut ranks = vec![0i64; batch.num_rows()];
@@ -859,6 +1775,170 @@ impl DataUnit {
         ))
     }
 
+    /// Rolling aggregate over a trailing window of `window` rows
+    ///
+    /// Nulls inside the window are skipped. Rows whose window holds fewer than
+    /// `min_periods` non-null values (e.g. the partial windows at the start) are null.
+    fn rolling(
+        &self,
+        batch: &RecordBatch,
+        column: &str,
+        window: usize,
+        agg: &str,
+        min_periods: usize,
+    ) -> Result<Float64Array, ComputeError> {
+        if window == 0 {
+            return Err(ComputeError::InvalidParams(
+                "window must be greater than 0".to_string(),
+            ));
+        }
+        if !matches!(agg, "sum" | "mean" | "min" | "max" | "count") {
+            return Err(ComputeError::InvalidParams(format!(
+                "Unknown rolling aggregation: {}",
+                agg
+            )));
+        }
+
+        let schema = batch.schema();
+        let index = schema.index_of(column).map_err(|e| {
+            ComputeError::ExecutionFailed(format!("Column '{}' not found: {}", column, e))
+        })?;
+
+        let array = batch.column(index);
+        if !array.data_type().is_numeric() {
+            return Err(ComputeError::ExecutionFailed(format!(
+                "Column '{}' is not numeric",
+                column
+            )));
+        }
+
+        let casted =
+            compute::cast(array, &DataType::Float64).map_err(ComputeError::arrow("Cast failed"))?;
+        let values = casted
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .ok_or_else(|| ComputeError::ExecutionFailed("Float64 cast failed".to_string()))?;
+
+        /// Compensated running sum; non-finite values are counted so they
+        /// leave the window as cleanly as they entered
+        #[derive(Default)]
+        struct WindowSum {
+            sum: f64,
+            compensation: f64,
+            nan: usize,
+            pos_inf: usize,
+            neg_inf: usize,
+        }
+
+        impl WindowSum {
+            fn add(&mut self, v: f64) {
+                self.apply(v, true);
+            }
+
+            fn remove(&mut self, v: f64) {
+                self.apply(v, false);
+            }
+
+            fn apply(&mut self, v: f64, entering: bool) {
+                let counter = if v.is_nan() {
+                    &mut self.nan
+                } else if v == f64::INFINITY {
+                    &mut self.pos_inf
+                } else if v == f64::NEG_INFINITY {
+                    &mut self.neg_inf
+                } else {
+                    let v = if entering { v } else { -v };
+                    let t = self.sum + v;
+                    self.compensation += if self.sum.abs() >= v.abs() {
+                        (self.sum - t) + v
+                    } else {
+                        (v - t) + self.sum
+                    };
+                    self.sum = t;
+                    return;
+                };
+                if entering {
+                    *counter += 1;
+                } else {
+                    *counter -= 1;
+                }
+            }
+
+            fn value(&self) -> f64 {
+                if self.nan > 0 || (self.pos_inf > 0 && self.neg_inf > 0) {
+                    f64::NAN
+                } else if self.pos_inf > 0 {
+                    f64::INFINITY
+                } else if self.neg_inf > 0 {
+                    f64::NEG_INFINITY
+                } else {
+                    self.sum + self.compensation
+                }
+            }
+        }
+
+        // Windows never reach past the first row, so longer ones change nothing
+        let window = window.min(values.len().max(1));
+        let mut sum = WindowSum::default();
+        let mut count = 0usize;
+        // Row indices whose values are monotonic, so the front is the extreme
+        let mut mins: VecDeque<usize> = VecDeque::new();
+        let mut maxs: VecDeque<usize> = VecDeque::new();
+
+        let mut builder = Float64Builder::with_capacity(values.len());
+        for i in 0..values.len() {
+            if i % DEADLINE_CHECK_ROWS == 0 {
+                check_deadline()?;
+            }
+
+            if values.is_valid(i) {
+                let v = values.value(i);
+                sum.add(v);
+                count += 1;
+                // NaN never wins min/max, as with f64::min/max
+                if !v.is_nan() {
+                    while matches!(mins.back(), Some(&j) if values.value(j) >= v) {
+                        mins.pop_back();
+                    }
+                    mins.push_back(i);
+                    while matches!(maxs.back(), Some(&j) if values.value(j) <= v) {
+                        maxs.pop_back();
+                    }
+                    maxs.push_back(i);
+                }
+            }
+            if i >= window {
+                let out = i - window;
+                if values.is_valid(out) {
+                    sum.remove(values.value(out));
+                    count -= 1;
+                }
+                if mins.front() == Some(&out) {
+                    mins.pop_front();
+                }
+                if maxs.front() == Some(&out) {
+                    maxs.pop_front();
+                }
+            }
+
+            if count == 0 || count < min_periods {
+                builder.append_null();
+                continue;
+            }
+
+            let value = match agg {
+                "sum" => sum.value(),
+                "mean" => sum.value() / count as f64,
+                "min" => mins.front().map_or(f64::INFINITY, |&j| values.value(j)),
+                "max" => maxs.front().map_or(f64::NEG_INFINITY, |&j| values.value(j)),
+                _ => count as f64, // count
+            };
+            builder.append_value(value);
+        }
+
+        Ok(builder.finish())
+    }
+
     // ===== PHASE 7: STRING OPERATIONS =====
 
     /// Check if string contains pattern
@@ -1000,6 +2080,339 @@ impl DataUnit {
         )))
     }
 
+    // ===== PHASE 8: FINGERPRINTING =====
+
+    /// Deterministic BLAKE3 hash per row over type-tagged, null-aware canonical bytes
+    ///
+    /// Integer widths are widened to 64 bits and floats to f64 (with -0.0 and NaN
+    /// canonicalized), so the same logical row hashes identically whether it was
+    /// loaded as Int32 from CSV or Int64 from JSON.
+    fn row_hashes(&self, batch: &RecordBatch) -> Result<Vec<blake3::Hash>, ComputeError> {
+        const TAG_INT: u8 = 1;
+        const TAG_UINT: u8 = 2;
+        const TAG_FLOAT: u8 = 3;
+        const TAG_BOOL: u8 = 4;
+        const TAG_UTF8: u8 = 5;
+        const TAG_BINARY: u8 = 6;
+        const TAG_OTHER: u8 = 7;
+
+        /// A canonically cast column, downcast once for the row loop
+        enum Cells<'a> {
+            Int(&'a Int64Array),
+            UInt(&'a UInt64Array),
+            Float(&'a Float64Array),
+            Bool(&'a BooleanArray),
+            Binary(&'a LargeBinaryArray),
+            Utf8(&'a LargeStringArray),
+        }
+
+        // Cast every column once; rows are then hashed one at a time
+        let mut canonical = Vec::with_capacity(batch.num_columns());
+        for column in batch.columns() {
+            check_deadline()?;
+            let data_type = column.data_type();
+            let (tag, target) = if matches!(
+                data_type,
+                DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64
+            ) {
+                (TAG_INT, DataType::Int64)
+            } else if matches!(
+                data_type,
+                DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64
+            ) {
+                (TAG_UINT, DataType::UInt64)
+            } else if data_type.is_floating() {
+                (TAG_FLOAT, DataType::Float64)
+            } else if *data_type == DataType::Boolean {
+                (TAG_BOOL, DataType::Boolean)
+            } else if matches!(data_type, DataType::Binary | DataType::LargeBinary) {
+                (TAG_BINARY, DataType::LargeBinary)
+            } else if matches!(data_type, DataType::Utf8 | DataType::LargeUtf8) {
+                (TAG_UTF8, DataType::LargeUtf8)
+            } else {
+                (TAG_OTHER, DataType::LargeUtf8)
+            };
+
+            let array = compute::cast(column, &target)
+                .map_err(ComputeError::arrow("Canonical cast failed"))?;
+            canonical.push((tag, array));
+        }
+
+        let columns = canonical
+            .iter()
+            .map(|(tag, array)| {
+                let any = array.as_any();
+                let cells = match array.data_type() {
+                    DataType::Int64 => any.downcast_ref().map(Cells::Int),
+                    DataType::UInt64 => any.downcast_ref().map(Cells::UInt),
+                    DataType::Float64 => any.downcast_ref().map(Cells::Float),
+                    DataType::Boolean => any.downcast_ref().map(Cells::Bool),
+                    DataType::LargeBinary => any.downcast_ref().map(Cells::Binary),
+                    _ => any.downcast_ref().map(Cells::Utf8),
+                };
+                cells.map(|cells| (*tag, array, cells)).ok_or_else(|| {
+                    ComputeError::ExecutionFailed("Canonical downcast failed".to_string())
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Length prefix keeps adjacent variable-width values unambiguous
+        fn payload(hasher: &mut blake3::Hasher, bytes: &[u8]) {
+            hasher.update(&(bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        let mut hashes = Vec::with_capacity(batch.num_rows());
+        for row in 0..batch.num_rows() {
+            if row % DEADLINE_CHECK_ROWS == 0 {
+                check_deadline()?;
+            }
+            hasher.reset();
+            for (tag, array, cells) in &columns {
+                hasher.update(&[*tag]);
+                if array.is_null(row) {
+                    hasher.update(&[0]);
+                    continue;
+                }
+                hasher.update(&[1]);
+
+                match cells {
+                    Cells::Int(a) => payload(&mut hasher, &a.value(row).to_le_bytes()),
+                    Cells::UInt(a) => payload(&mut hasher, &a.value(row).to_le_bytes()),
+                    Cells::Float(a) => {
+                        let v = a.value(row);
+                        let v = if v == 0.0 {
+                            0.0
+                        } else if v.is_nan() {
+                            f64::NAN
+                        } else {
+                            v
+                        };
+                        payload(&mut hasher, &v.to_bits().to_le_bytes())
+                    }
+                    Cells::Bool(a) => payload(&mut hasher, &[a.value(row) as u8]),
+                    Cells::Binary(a) => payload(&mut hasher, a.value(row)),
+                    Cells::Utf8(a) => payload(&mut hasher, a.value(row).as_bytes()),
+                }
+            }
+            hashes.push(hasher.finalize());
+        }
+
+        Ok(hashes)
+    }
+
+    /// Single fingerprint for a whole batch: column names, row count, then row hashes in order
+    fn batch_hash(&self, batch: &RecordBatch) -> Result<blake3::Hash, ComputeError> {
+        let mut hasher = blake3::Hasher::new();
+        for field in batch.schema().fields() {
+            hasher.update(&(field.name().len() as u64).to_le_bytes());
+            hasher.update(field.name().as_bytes());
+        }
+        hasher.update(&(batch.num_rows() as u64).to_le_bytes());
+        for row_hash in self.row_hashes(batch)? {
+            hasher.update(row_hash.as_bytes());
+        }
+        Ok(hasher.finalize())
+    }
+
+    // ===== PHASE 9: CHUNKED RESULT PROTOCOL =====
+
+    /// Keep a result batch in the unit and return a descriptor with its handle
+    ///
+    /// Callers page through the rows with `fetch` instead of receiving one
+    /// IPC payload that may not fit in the outbox.
+    async fn hold_result(&self, batch: RecordBatch) -> Result<JsonValue, ComputeError> {
+        let handle = self.next_handle.fetch_add(1, Ordering::Relaxed);
+        let descriptor = serde_json::json!({
+            "handle": handle,
+            "num_rows": batch.num_rows(),
+            "chunk_size": self.config.chunk_size,
+            "schema": self.get_schema(&batch)?,
+        });
+
+        {
+            let mut pool = self.pool.lock().await;
+            if pool.len() >= self.config.max_held_results {
+                return Err(ComputeError::ExecutionFailed(format!(
+                    "Too many held results (max: {}), release a handle first",
+                    self.config.max_held_results
+                )));
+            }
+            pool.insert(handle, batch);
+        }
+
+        // The caller never learns a handle whose hold failed, so don't keep it
+        if let Err(e) = self.relieve_pressure(handle).await {
+            self.pool.lock().await.remove(handle);
+            return Err(e);
+        }
+
+        Ok(descriptor)
+    }
+
+    /// Get a held batch, reloading it from the vault if it was spilled
+    async fn checkout_result(&self, handle: u64) -> Result<RecordBatch, ComputeError> {
+        let spill = {
+            let mut pool = self.pool.lock().await;
+            if let Some((batch, _)) = pool.resident.get(&handle) {
+                let batch = batch.clone();
+                pool.touch(handle);
+                return Ok(batch);
+            }
+            pool.spilled.get(&handle).cloned().ok_or_else(|| {
+                ComputeError::InvalidParams(format!("Unknown result handle: {}", handle))
+            })?
+        };
+        let vault = self.vault.as_ref().ok_or_else(|| {
+            ComputeError::ExecutionFailed("Spilled result without a vault".to_string())
+        })?;
+
+        // Vault I/O runs without the pool lock so other handles stay usable
+        let bytes = vault
+            .load(&spill.key)
+            .await
+            .map_err(|e| ComputeError::ExecutionFailed(format!("Spill reload failed: {}", e)))?;
+        if blake3::hash(&bytes).to_hex().as_str() != spill.digest {
+            return Err(ComputeError::ExecutionFailed(format!(
+                "Spilled result {} failed integrity check",
+                handle
+            )));
+        }
+        let batch = self.arrow_read(&bytes)?;
+
+        {
+            let mut pool = self.pool.lock().await;
+            match pool.spilled.get(&handle).cloned() {
+                Some(current) if current == spill => {
+                    pool.spilled.remove(&handle);
+                    pool.insert(handle, batch.clone());
+                }
+                // A concurrent checkout got there first and owns the spill now
+                Some(_) => return Ok(batch),
+                None if pool.resident.contains_key(&handle) => return Ok(batch),
+                // Released meanwhile; the release already deleted the spill
+                None => {
+                    return Err(ComputeError::InvalidParams(format!(
+                        "Unknown result handle: {}",
+                        handle
+                    )))
+                }
+            }
+        }
+
+        if let Err(e) = vault.delete(&spill.key).await {
+            log::warn!("Failed to delete spilled result {}: {}", handle, e);
+        }
+        self.relieve_pressure(handle).await?;
+
+        Ok(batch)
+    }
+
+    /// Evict the coldest resident batches to the vault until under budget
+    /// `pinned` is the batch currently in use and is never evicted.
+    async fn relieve_pressure(&self, pinned: u64) -> Result<(), ComputeError> {
+        let Some(vault) = self.vault.as_ref() else {
+            return Ok(());
+        };
+
+        loop {
+            let (victim, bytes) = {
+                let pool = self.pool.lock().await;
+                if pool.resident_bytes <= self.config.memory_budget {
+                    return Ok(());
+                }
+                let victim = pool
+                    .recency
+                    .iter()
+                    .copied()
+                    .find(|h| *h != pinned && pool.resident.contains_key(h));
+                let Some(victim) = victim else {
+                    return Ok(());
+                };
+                match pool.resident.get(&victim) {
+                    Some((batch, _)) => (victim, self.arrow_write(batch)?),
+                    None => return Ok(()),
+                }
+            };
+
+            // The victim stays resident (and readable) while it is written out
+            let spill = SpilledBatch {
+                key: format!(
+                    "data-spill-{:016x}-{}-{}",
+                    self.spill_namespace,
+                    victim,
+                    self.next_spill.fetch_add(1, Ordering::Relaxed)
+                ),
+                digest: blake3::hash(&bytes).to_hex().to_string(),
+            };
+            vault
+                .store(&spill.key, &bytes)
+                .await
+                .map_err(|e| ComputeError::ExecutionFailed(format!("Spill failed: {}", e)))?;
+
+            let mut pool = self.pool.lock().await;
+            if pool.take_resident(victim).is_some() {
+                pool.recency.retain(|&h| h != victim);
+                pool.spilled.insert(victim, spill);
+            } else {
+                // Released or spilled by another caller while we were writing
+                drop(pool);
+                if let Err(e) = vault.delete(&spill.key).await {
+                    log::warn!("Failed to delete orphaned spill {}: {}", spill.key, e);
+                }
+            }
+        }
+    }
+
+    /// Slice `limit` rows starting at `offset` out of a held result
+    /// An empty batch means the result is exhausted.
+    async fn fetch_result(
+        &self,
+        handle: u64,
+        offset: usize,
+        limit: usize,
+    ) -> Result<RecordBatch, ComputeError> {
+        let batch = self.checkout_result(handle).await?;
+
+        let offset = offset.min(batch.num_rows());
+        let length = limit.min(batch.num_rows() - offset);
+        Ok(batch.slice(offset, length))
+    }
+
+    /// Drop a held result, returning whether the handle existed
+    async fn release_result(&self, handle: u64) -> Result<bool, ComputeError> {
+        let (existed, spill) = {
+            let mut pool = self.pool.lock().await;
+            let resident = pool.resident.contains_key(&handle);
+            let spill = pool.remove(handle);
+            (resident || spill.is_some(), spill)
+        };
+
+        match spill {
+            Some(spill) => {
+                if let Some(vault) = self.vault.as_ref() {
+                    vault.delete(&spill.key).await.map_err(|e| {
+                        ComputeError::ExecutionFailed(format!("Spill delete failed: {}", e))
+                    })?;
+                }
+                Ok(true)
+            }
+            None => Ok(existed),
+        }
+    }
+
+    /// Resident vs spilled footprint of held results
+    async fn pool_stats(&self) -> JsonValue {
+        let pool = self.pool.lock().await;
+        serde_json::json!({
+            "resident": pool.resident.len(),
+            "spilled": pool.spilled.len(),
+            "resident_bytes": pool.resident_bytes,
+            "memory_budget": self.config.memory_budget,
+        })
+    }
+
     // ===== HELPER FUNCTIONS =====
 
     /// Get schema as JSON
@@ -1017,6 +2430,28 @@ impl DataUnit {
         Ok(JsonValue::Object(schema_map))
     }
 
+    /// Append a derived column to the end of a batch
+    fn append_column(
+        &self,
+        batch: &RecordBatch,
+        field: Field,
+        array: ArrayRef,
+    ) -> Result<RecordBatch, ComputeError> {
+        let mut columns = batch.columns().to_vec();
+        columns.push(array);
+        let mut fields: Vec<Field> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| (**f).clone())
+            .collect();
+        fields.push(field);
+
+        let new_schema = Arc::new(Schema::new(fields));
+        RecordBatch::try_new(new_schema, columns)
+            .map_err(ComputeError::arrow("RecordBatch creation failed"))
+    }
+
     /// Validate batch size
     fn validate_size(&self, batch: &RecordBatch) -> Result<(), ComputeError> {
         if batch.num_rows() > self.config.max_rows {
@@ -1056,7 +2491,11 @@ impl UnitProxy for DataUnit {
             "csv_write",
             "json_read",
             "json_write",
+            "json_write_stream",
+            "arrow_file_read",
+            "arrow_file_write",
             "select",
+            "query",
             "head",
             "tail",
             "slice",
@@ -1069,15 +2508,26 @@ impl UnitProxy for DataUnit {
             "count",
             "cast",
             "drop_nulls",
+            "fillna",
+            "coalesce",
+            "with_column",
+            "distinct",
+            "unique_values",
             "row_number",
             "rank",
             "lag",
             "lead",
+            "rolling",
             "str_contains",
             "str_replace",
             "str_length",
             "str_to_lowercase",
             "str_to_uppercase",
+            "row_hash",
+            "batch_hash",
+            "fetch",
+            "release",
+            "pool_stats",
         ]
     }
 
@@ -1099,6 +2549,22 @@ impl UnitProxy for DataUnit {
         let params: serde_json::Value = serde_json::from_slice(params)
             .map_err(|e| ComputeError::InvalidParams(format!("Invalid JSON: {}", e)))?;
 
+        // Any Arrow-producing action can keep its result server-side for chunked fetch
+        let hold_result = params
+            .get("hold_result")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // Multi-step pipelines can read a held result instead of shipping it back in
+        let held_input;
+        let input = match params.get("input_handle").and_then(|v| v.as_u64()) {
+            Some(handle) => {
+                held_input = self.arrow_write(&self.checkout_result(handle).await?)?;
+                &held_input[..]
+            }
+            None => input,
+        };
+
         // Validate input size
         if input.len() > self.config.max_input_size {
             return Err(ComputeError::InputTooLarge {
@@ -1107,6 +2573,13 @@ impl UnitProxy for DataUnit {
             });
         }
 
+        // Arrow results stay batches until the end, so a held result goes
+        // straight into the pool instead of being serialized and parsed back
+        enum Output {
+            Arrow(RecordBatch),
+            Bytes(Vec<u8>),
+        }
+
         // Execute method
         let result = match action {
             // Changed from method
@@ -1114,20 +2587,26 @@ impl UnitProxy for DataUnit {
             "parquet_read" => {
                 let batch = self.parquet_read(input)?;
                 self.validate_size(&batch)?;
-                self.arrow_write(&batch)?
+                Output::Arrow(batch)
             }
             "parquet_write" => {
                 let batch = self.arrow_read(input)?;
-                self.parquet_write(&batch)?
+                Output::Bytes(self.parquet_write(&batch)?)
             }
             "csv_read" => {
                 let has_header = params
                     .get("has_header")
                     .and_then(|v| v.as_bool())
                     .unwrap_or(true);
-                let batch = self.csv_read(input, has_header)?;
+                let infer_rows = params
+                    .get("infer_rows")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as usize)
+                    .unwrap_or(self.config.csv_infer_rows);
+                let dialect = CsvDialect::from_params(&params)?;
+                let batch = self.csv_read(input, has_header, infer_rows, &dialect)?;
                 self.validate_size(&batch)?;
-                self.arrow_write(&batch)?
+                Output::Arrow(batch)
             }
             "csv_write" => {
                 let batch = self.arrow_read(input)?;
@@ -1135,16 +2614,29 @@ impl UnitProxy for DataUnit {
                     .get("has_header")
                     .and_then(|v| v.as_bool())
                     .unwrap_or(true);
-                self.csv_write(&batch, has_header)?
+                let dialect = CsvDialect::from_params(&params)?;
+                Output::Bytes(self.csv_write(&batch, has_header, &dialect)?)
             }
             "json_read" => {
                 let batch = self.json_read(input)?;
                 self.validate_size(&batch)?;
-                self.arrow_write(&batch)?
+                Output::Arrow(batch)
             }
             "json_write" => {
                 let batch = self.arrow_read(input)?;
-                self.json_write(&batch)?
+                Output::Bytes(self.json_write(&batch, JsonLayout::from_params(&params)?)?)
+            }
+            "json_write_stream" => {
+                Output::Bytes(self.json_write_stream(input, JsonLayout::from_params(&params)?)?)
+            }
+            "arrow_file_read" => {
+                let batch = self.arrow_file_read(input)?;
+                self.validate_size(&batch)?;
+                Output::Arrow(batch)
+            }
+            "arrow_file_write" => {
+                let batch = self.arrow_read(input)?;
+                Output::Bytes(self.arrow_file_write(&batch)?)
             }
 
             // Selection & Filtering
@@ -1160,45 +2652,76 @@ impl UnitProxy for DataUnit {
                     .collect();
                 let col_refs: Vec<&str> = columns.iter().map(|s| s.as_str()).collect();
                 let result = self.select(&batch, &col_refs)?;
-                self.arrow_write(&result)?
+                Output::Arrow(result)
+            }
+            "query" => {
+                let batch = self.arrow_read(input)?;
+                let predicate = params["predicate"].as_str().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing predicate parameter".to_string())
+                })?;
+                let result = self.query(&batch, predicate)?;
+                Output::Arrow(result)
             }
             "head" => {
                 let batch = self.arrow_read(input)?;
                 let n = params.get("n").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
                 let result = self.head(&batch, n)?;
-                self.arrow_write(&result)?
+                Output::Arrow(result)
             }
             "tail" => {
                 let batch = self.arrow_read(input)?;
                 let n = params.get("n").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
                 let result = self.tail(&batch, n)?;
-                self.arrow_write(&result)?
+                Output::Arrow(result)
             }
             "slice" => {
                 let batch = self.arrow_read(input)?;
                 let offset = params.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
                 let length = params.get("length").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
                 let result = self.slice(&batch, offset, length)?;
-                self.arrow_write(&result)?
+                Output::Arrow(result)
             }
             "sort" => {
                 let batch = self.arrow_read(input)?;
-                let column = params["column"].as_str().ok_or_else(|| {
-                    ComputeError::InvalidParams("Missing column parameter".to_string())
-                })?;
-                let descending = params
-                    .get("descending")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-                let result = self.sort(&batch, column, descending)?;
-                self.arrow_write(&result)?
+                let result = match params.get("by").and_then(|v| v.as_array()) {
+                    // Multi-key: by: [{column, descending}, ...]
+                    Some(by) => {
+                        let keys = by
+                            .iter()
+                            .map(|key| {
+                                let column = key["column"].as_str().ok_or_else(|| {
+                                    ComputeError::InvalidParams(
+                                        "Each sort key needs a column".to_string(),
+                                    )
+                                })?;
+                                let descending = key
+                                    .get("descending")
+                                    .and_then(|v| v.as_bool())
+                                    .unwrap_or(false);
+                                Ok((column.to_string(), descending))
+                            })
+                            .collect::<Result<Vec<_>, ComputeError>>()?;
+                        self.sort_by(&batch, &keys)?
+                    }
+                    None => {
+                        let column = params["column"].as_str().ok_or_else(|| {
+                            ComputeError::InvalidParams("Missing column parameter".to_string())
+                        })?;
+                        let descending = params
+                            .get("descending")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false);
+                        self.sort(&batch, column, descending)?
+                    }
+                };
+                Output::Arrow(result)
             }
             "schema" => {
                 let batch = self.arrow_read(input)?;
                 let schema = self.get_schema(&batch)?;
-                serde_json::to_vec(&schema).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&schema).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("Schema serialization failed: {}", e))
-                })?
+                })?)
             }
 
             // Aggregations
@@ -1208,9 +2731,9 @@ impl UnitProxy for DataUnit {
                     ComputeError::InvalidParams("Missing column parameter".to_string())
                 })?;
                 let result = self.sum(&batch, column)?;
-                serde_json::to_vec(&result).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&result).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "mean" => {
                 let batch = self.arrow_read(input)?;
@@ -1218,9 +2741,9 @@ impl UnitProxy for DataUnit {
                     ComputeError::InvalidParams("Missing column parameter".to_string())
                 })?;
                 let result = self.mean(&batch, column)?;
-                serde_json::to_vec(&result).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&result).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "min" => {
                 let batch = self.arrow_read(input)?;
@@ -1228,9 +2751,9 @@ impl UnitProxy for DataUnit {
                     ComputeError::InvalidParams("Missing column parameter".to_string())
                 })?;
                 let result = self.min(&batch, column)?;
-                serde_json::to_vec(&result).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&result).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "max" => {
                 let batch = self.arrow_read(input)?;
@@ -1238,16 +2761,16 @@ impl UnitProxy for DataUnit {
                     ComputeError::InvalidParams("Missing column parameter".to_string())
                 })?;
                 let result = self.max(&batch, column)?;
-                serde_json::to_vec(&result).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&result).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "count" => {
                 let batch = self.arrow_read(input)?;
                 let result = self.count(&batch)?;
-                serde_json::to_vec(&result).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&result).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
 
             // Transformations
@@ -1260,12 +2783,124 @@ impl UnitProxy for DataUnit {
                     ComputeError::InvalidParams("Missing type parameter".to_string())
                 })?;
                 let result = self.cast(&batch, column, target_type)?;
-                self.arrow_write(&result)?
+                Output::Arrow(result)
             }
             "drop_nulls" => {
                 let batch = self.arrow_read(input)?;
                 let result = self.drop_nulls(&batch)?;
-                self.arrow_write(&result)?
+                Output::Arrow(result)
+            }
+            "fillna" => {
+                let batch = self.arrow_read(input)?;
+
+                // Per-column constants, then a strategy or constant for the rest
+                let mut per_column: HashMap<String, FillStrategy> = params
+                    .get("values")
+                    .and_then(|v| v.as_object())
+                    .map(|values| {
+                        values
+                            .iter()
+                            .map(|(col, v)| (col.clone(), FillStrategy::Value(v.clone())))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let mut default = match (params.get("strategy"), params.get("value")) {
+                    (Some(strategy), _) => {
+                        let strategy = strategy.as_str().ok_or_else(|| {
+                            ComputeError::InvalidParams("strategy must be a string".to_string())
+                        })?;
+                        Some(FillStrategy::parse(strategy)?)
+                    }
+                    (None, Some(value)) => Some(FillStrategy::Value(value.clone())),
+                    (None, None) => None,
+                };
+                if per_column.is_empty() && default.is_none() {
+                    return Err(ComputeError::InvalidParams(
+                        "fillna needs values, value or strategy".to_string(),
+                    ));
+                }
+
+                // An explicit column list narrows the default to those columns
+                if let Some(cols) = params.get("columns").and_then(|v| v.as_array()) {
+                    if let Some(strategy) = default.take() {
+                        for col in cols.iter().filter_map(|v| v.as_str()) {
+                            per_column
+                                .entry(col.to_string())
+                                .or_insert_with(|| strategy.clone());
+                        }
+                    }
+                }
+
+                let result = self.fillna(&batch, &per_column, default.as_ref())?;
+                Output::Arrow(result)
+            }
+            "coalesce" => {
+                let batch = self.arrow_read(input)?;
+                let columns: Vec<String> = params["columns"]
+                    .as_array()
+                    .ok_or_else(|| {
+                        ComputeError::InvalidParams("Missing columns parameter".to_string())
+                    })?
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect();
+                let col_refs: Vec<&str> = columns.iter().map(|s| s.as_str()).collect();
+                let name = params
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("coalesce");
+                let result = self.coalesce(&batch, &col_refs)?;
+                let field = Field::new(name, result.data_type().clone(), true);
+                let new_batch = self.append_column(&batch, field, result)?;
+                Output::Arrow(new_batch)
+            }
+            "with_column" => {
+                let batch = self.arrow_read(input)?;
+                let name = params["name"].as_str().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing name parameter".to_string())
+                })?;
+                let expr = params["expr"].as_str().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing expr parameter".to_string())
+                })?;
+                let div_zero_null = match params.get("div_by_zero").and_then(|v| v.as_str()) {
+                    None | Some("null") => true,
+                    Some("inf") => false,
+                    Some(other) => {
+                        return Err(ComputeError::InvalidParams(format!(
+                            "div_by_zero must be 'null' or 'inf', got '{}'",
+                            other
+                        )))
+                    }
+                };
+                let result = self.with_column(&batch, expr, div_zero_null)?;
+                let field = Field::new(name, result.data_type().clone(), true);
+                let new_batch = self.append_column(&batch, field, result)?;
+                Output::Arrow(new_batch)
+            }
+            "distinct" => {
+                let batch = self.arrow_read(input)?;
+                let columns: Vec<String> = params
+                    .get("columns")
+                    .and_then(|v| v.as_array())
+                    .map(|cols| {
+                        cols.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let col_refs: Vec<&str> = columns.iter().map(|s| s.as_str()).collect();
+                let result = self.distinct(&batch, &col_refs)?;
+                Output::Arrow(result)
+            }
+            "unique_values" => {
+                let batch = self.arrow_read(input)?;
+                let column = params["column"].as_str().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing column parameter".to_string())
+                })?;
+                let values = self.unique_values(&batch, column)?;
+                Output::Bytes(serde_json::to_vec(&values).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
+                })?)
             }
 
             // Window Functions
@@ -1273,9 +2908,9 @@ impl UnitProxy for DataUnit {
                 let batch = self.arrow_read(input)?;
                 let result = self.row_number(&batch)?;
                 let values: Vec<i64> = result.values().iter().copied().collect();
-                serde_json::to_vec(&values).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&values).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "rank" => {
                 let batch = self.arrow_read(input)?;
@@ -1284,9 +2919,9 @@ impl UnitProxy for DataUnit {
                 })?;
                 let result = self.rank(&batch, column)?;
                 let values: Vec<i64> = result.values().iter().copied().collect();
-                serde_json::to_vec(&values).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&values).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "lag" => {
                 let batch = self.arrow_read(input)?;
@@ -1312,10 +2947,9 @@ impl UnitProxy for DataUnit {
                 ));
 
                 let new_schema = Arc::new(Schema::new(fields));
-                let new_batch = RecordBatch::try_new(new_schema, columns).map_err(|e| {
-                    ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
-                })?;
-                self.arrow_write(&new_batch)?
+                let new_batch = RecordBatch::try_new(new_schema, columns)
+                    .map_err(ComputeError::arrow("RecordBatch creation failed"))?;
+                Output::Arrow(new_batch)
             }
             "lead" => {
                 let batch = self.arrow_read(input)?;
@@ -1341,10 +2975,34 @@ impl UnitProxy for DataUnit {
                 ));
 
                 let new_schema = Arc::new(Schema::new(fields));
-                let new_batch = RecordBatch::try_new(new_schema, columns).map_err(|e| {
-                    ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
+                let new_batch = RecordBatch::try_new(new_schema, columns)
+                    .map_err(ComputeError::arrow("RecordBatch creation failed"))?;
+                Output::Arrow(new_batch)
+            }
+
+            "rolling" => {
+                let batch = self.arrow_read(input)?;
+                let column = params["column"].as_str().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing column parameter".to_string())
                 })?;
-                self.arrow_write(&new_batch)?
+                let window = params["window"].as_u64().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing window parameter".to_string())
+                })? as usize;
+                let agg = params.get("agg").and_then(|v| v.as_str()).unwrap_or("mean");
+                let min_periods = params
+                    .get("min_periods")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as usize)
+                    .unwrap_or(window);
+                let result = self.rolling(&batch, column, window, agg, min_periods)?;
+
+                let field = Field::new(
+                    format!("{}_rolling_{}_{}", column, agg, window),
+                    DataType::Float64,
+                    true,
+                );
+                let new_batch = self.append_column(&batch, field, Arc::new(result))?;
+                Output::Arrow(new_batch)
             }
 
             // String Operations
@@ -1358,9 +3016,9 @@ impl UnitProxy for DataUnit {
                 })?;
                 let result = self.str_contains(&batch, column, pattern)?;
                 let values: Vec<bool> = (0..result.len()).map(|i| result.value(i)).collect();
-                serde_json::to_vec(&values).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&values).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "str_replace" => {
                 let batch = self.arrow_read(input)?;
@@ -1384,10 +3042,9 @@ impl UnitProxy for DataUnit {
                 let mut columns = batch.columns().to_vec();
                 columns[index] = Arc::new(result);
 
-                let new_batch = RecordBatch::try_new(schema, columns).map_err(|e| {
-                    ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
-                })?;
-                self.arrow_write(&new_batch)?
+                let new_batch = RecordBatch::try_new(schema, columns)
+                    .map_err(ComputeError::arrow("RecordBatch creation failed"))?;
+                Output::Arrow(new_batch)
             }
             "str_length" => {
                 let batch = self.arrow_read(input)?;
@@ -1396,9 +3053,9 @@ impl UnitProxy for DataUnit {
                 })?;
                 let result = self.str_length(&batch, column)?;
                 let values: Vec<i32> = result.values().iter().copied().collect();
-                serde_json::to_vec(&values).map_err(|e| {
+                Output::Bytes(serde_json::to_vec(&values).map_err(|e| {
                     ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
-                })?
+                })?)
             }
             "str_to_lowercase" => {
                 let batch = self.arrow_read(input)?;
@@ -1416,10 +3073,9 @@ impl UnitProxy for DataUnit {
                 let mut columns = batch.columns().to_vec();
                 columns[index] = Arc::new(result);
 
-                let new_batch = RecordBatch::try_new(schema, columns).map_err(|e| {
-                    ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
-                })?;
-                self.arrow_write(&new_batch)?
+                let new_batch = RecordBatch::try_new(schema, columns)
+                    .map_err(ComputeError::arrow("RecordBatch creation failed"))?;
+                Output::Arrow(new_batch)
             }
             "str_to_uppercase" => {
                 let batch = self.arrow_read(input)?;
@@ -1437,10 +3093,63 @@ impl UnitProxy for DataUnit {
                 let mut columns = batch.columns().to_vec();
                 columns[index] = Arc::new(result);
 
-                let new_batch = RecordBatch::try_new(schema, columns).map_err(|e| {
-                    ComputeError::ExecutionFailed(format!("RecordBatch creation failed: {}", e))
+                let new_batch = RecordBatch::try_new(schema, columns)
+                    .map_err(ComputeError::arrow("RecordBatch creation failed"))?;
+                Output::Arrow(new_batch)
+            }
+
+            // Fingerprinting
+            "row_hash" => {
+                let batch = self.arrow_read(input)?;
+                let hashes: StringArray = self
+                    .row_hashes(&batch)?
+                    .iter()
+                    .map(|h| Some(h.to_hex().to_string()))
+                    .collect();
+                let field = Field::new("row_hash", DataType::Utf8, false);
+                let new_batch = self.append_column(&batch, field, Arc::new(hashes))?;
+                Output::Arrow(new_batch)
+            }
+            "batch_hash" => {
+                let batch = self.arrow_read(input)?;
+                let hash = self.batch_hash(&batch)?;
+                let summary = serde_json::json!({
+                    "hash": hash.to_hex().to_string(),
+                    "num_rows": batch.num_rows(),
+                });
+                Output::Bytes(serde_json::to_vec(&summary).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
+                })?)
+            }
+
+            // Chunked Result Protocol
+            "fetch" => {
+                let handle = params["handle"].as_u64().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing handle parameter".to_string())
+                })?;
+                let offset = params.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                let limit = params
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as usize)
+                    .unwrap_or(self.config.chunk_size);
+                let chunk = self.fetch_result(handle, offset, limit).await?;
+                Output::Arrow(chunk)
+            }
+            "release" => {
+                let handle = params["handle"].as_u64().ok_or_else(|| {
+                    ComputeError::InvalidParams("Missing handle parameter".to_string())
                 })?;
-                self.arrow_write(&new_batch)?
+                let released = self.release_result(handle).await?;
+                let summary = serde_json::json!({ "released": released });
+                Output::Bytes(serde_json::to_vec(&summary).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
+                })?)
+            }
+            "pool_stats" => {
+                Output::Bytes(serde_json::to_vec(&self.pool_stats().await).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
+                })?)
             }
 
             _ => {
@@ -1451,6 +3160,492 @@ impl UnitProxy for DataUnit {
             }
         };
 
-        Ok(result)
+        match result {
+            Output::Arrow(batch) if hold_result && action != "fetch" => {
+                let descriptor = self.hold_result(batch).await?;
+                serde_json::to_vec(&descriptor).map_err(|e| {
+                    ComputeError::ExecutionFailed(format!("JSON serialization failed: {}", e))
+                })
+            }
+            Output::Arrow(batch) => self.arrow_write(&batch),
+            Output::Bytes(_) if hold_result && !matches!(action, "release" | "pool_stats") => {
+                Err(ComputeError::InvalidParams(format!(
+                    "Action '{}' does not produce an Arrow result and cannot be held",
+                    action
+                )))
+            }
+            Output::Bytes(bytes) => Ok(bytes),
+        }
+    }
+}
+
+// ===== EXPRESSION TOKENS =====
+//
+// One tokenizer serves both the `query` predicates and `with_column`
+// expressions; each parser rejects the tokens its grammar has no use for.
+
+/// Deepest expression tree, or parenthesis nesting, the parsers accept, so
+/// hostile input cannot overflow the stack while parsing or evaluating
+const MAX_EXPR_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+enum ExprToken {
+    Ident(String),
+    Int(i64),
+    Float(f64),
+    /// String and boolean literals
+    Literal(Literal),
+    Cmp(CmpOp),
+    Op(ArithOp),
+    And,
+    Or,
+    Not,
+    In,
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// Split `source` into tokens, reporting problems through `syntax_error`
+fn tokenize_expr(
+    source: &str,
+    syntax_error: fn(&str) -> ComputeError,
+) -> Result<Vec<ExprToken>, ComputeError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' | ')' | ',' | '+' | '-' | '*' | '/' => {
+                tokens.push(match c {
+                    '(' => ExprToken::LParen,
+                    ')' => ExprToken::RParen,
+                    ',' => ExprToken::Comma,
+                    '+' => ExprToken::Op(ArithOp::Add),
+                    '-' => ExprToken::Op(ArithOp::Sub),
+                    '*' => ExprToken::Op(ArithOp::Mul),
+                    _ => ExprToken::Op(ArithOp::Div),
+                });
+                i += 1;
+            }
+            '=' | '!' | '<' | '>' => {
+                let (op, width) = match (c, next) {
+                    ('=', Some('=')) => (CmpOp::Eq, 2),
+                    ('=', _) => (CmpOp::Eq, 1),
+                    ('!', Some('=')) => (CmpOp::NotEq, 2),
+                    ('<', Some('>')) => (CmpOp::NotEq, 2),
+                    ('<', Some('=')) => (CmpOp::LtEq, 2),
+                    ('<', _) => (CmpOp::Lt, 1),
+                    ('>', Some('=')) => (CmpOp::GtEq, 2),
+                    ('>', _) => (CmpOp::Gt, 1),
+                    _ => return Err(syntax_error("'!' must be followed by '='")),
+                };
+                tokens.push(ExprToken::Cmp(op));
+                i += width;
+            }
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .map(|p| i + 1 + p)
+                    .ok_or_else(|| syntax_error("unterminated string literal"))?;
+                let text: String = chars[i + 1..end].iter().collect();
+                tokens.push(ExprToken::Literal(Literal::Str(text)));
+                i = end + 1;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let bad_number = || syntax_error(&format!("bad number '{}'", text));
+                tokens.push(match text.parse::<i64>() {
+                    Ok(n) if !text.contains('.') => ExprToken::Int(n),
+                    _ => ExprToken::Float(text.parse().map_err(|_| bad_number())?),
+                });
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.to_ascii_uppercase().as_str() {
+                    "AND" => ExprToken::And,
+                    "OR" => ExprToken::Or,
+                    "NOT" => ExprToken::Not,
+                    "IN" => ExprToken::In,
+                    "TRUE" => ExprToken::Literal(Literal::Bool(true)),
+                    "FALSE" => ExprToken::Literal(Literal::Bool(false)),
+                    _ => ExprToken::Ident(word),
+                });
+            }
+            _ => return Err(syntax_error(&format!("unexpected character '{}'", c))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// `depth` if it is within `MAX_EXPR_DEPTH`
+fn nest(depth: usize, syntax_error: fn(&str) -> ComputeError) -> Result<usize, ComputeError> {
+    if depth > MAX_EXPR_DEPTH {
+        return Err(syntax_error(&format!(
+            "nested deeper than {} levels",
+            MAX_EXPR_DEPTH
+        )));
+    }
+    Ok(depth)
+}
+
+// ===== QUERY PREDICATES =====
+//
+// Grammar for the `query` action (keywords are case-insensitive):
+//
+//   expr    := and (OR and)*
+//   and     := unary (AND unary)*
+//   unary   := NOT unary | '(' expr ')' | column cmp literal | column [NOT] IN '(' literal, ... ')'
+//   cmp     := == | = | != | <> | < | <= | > | >=
+//   literal := number | 'string' | "string" | true | false
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    /// Whole-number literal, kept exact so integer columns compare without rounding
+    Int(i64),
+    Number(f64),
+    Str(String),
+    Bool(bool),
+}
+
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Int(n) => write!(f, "{}", n),
+            Literal::Number(n) => write!(f, "{}", n),
+            Literal::Str(s) => write!(f, "'{}'", s),
+            Literal::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Parsed `query` predicate
+#[derive(Debug, Clone, PartialEq)]
+enum Predicate {
+    Compare {
+        column: String,
+        op: CmpOp,
+        value: Literal,
+    },
+    In {
+        column: String,
+        values: Vec<Literal>,
+    },
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    fn parse(source: &str) -> Result<Self, ComputeError> {
+        let tokens = tokenize_expr(source, Self::syntax_error)?;
+        let mut pos = 0;
+        let (predicate, _) = Self::parse_or(&tokens, &mut pos, 0)?;
+        if pos < tokens.len() {
+            return Err(Self::syntax_error(&format!(
+                "unexpected {:?} at token {}",
+                tokens[pos], pos
+            )));
+        }
+        Ok(predicate)
+    }
+
+    fn syntax_error(detail: &str) -> ComputeError {
+        ComputeError::InvalidParams(format!("Invalid query: {}", detail))
+    }
+
+    // `nesting` counts the enclosing parentheses and NOTs; each parser also
+    // returns the depth of the tree it built.
+
+    fn parse_or(
+        tokens: &[ExprToken],
+        pos: &mut usize,
+        nesting: usize,
+    ) -> Result<(Self, usize), ComputeError> {
+        let (mut left, mut depth) = Self::parse_and(tokens, pos, nesting)?;
+        while tokens.get(*pos) == Some(&ExprToken::Or) {
+            *pos += 1;
+            let (right, right_depth) = Self::parse_and(tokens, pos, nesting)?;
+            depth = nest(depth.max(right_depth) + 1, Self::syntax_error)?;
+            left = Predicate::Or(Box::new(left), Box::new(right));
+        }
+        Ok((left, depth))
+    }
+
+    fn parse_and(
+        tokens: &[ExprToken],
+        pos: &mut usize,
+        nesting: usize,
+    ) -> Result<(Self, usize), ComputeError> {
+        let (mut left, mut depth) = Self::parse_unary(tokens, pos, nesting)?;
+        while tokens.get(*pos) == Some(&ExprToken::And) {
+            *pos += 1;
+            let (right, right_depth) = Self::parse_unary(tokens, pos, nesting)?;
+            depth = nest(depth.max(right_depth) + 1, Self::syntax_error)?;
+            left = Predicate::And(Box::new(left), Box::new(right));
+        }
+        Ok((left, depth))
+    }
+
+    fn parse_unary(
+        tokens: &[ExprToken],
+        pos: &mut usize,
+        nesting: usize,
+    ) -> Result<(Self, usize), ComputeError> {
+        match tokens.get(*pos) {
+            Some(ExprToken::Not) => {
+                *pos += 1;
+                let nesting = nest(nesting + 1, Self::syntax_error)?;
+                let (inner, depth) = Self::parse_unary(tokens, pos, nesting)?;
+                Ok((
+                    Predicate::Not(Box::new(inner)),
+                    nest(depth + 1, Self::syntax_error)?,
+                ))
+            }
+            Some(ExprToken::LParen) => {
+                *pos += 1;
+                let inner = Self::parse_or(tokens, pos, nest(nesting + 1, Self::syntax_error)?)?;
+                Self::expect(tokens, pos, &ExprToken::RParen)?;
+                Ok(inner)
+            }
+            Some(ExprToken::Ident(column)) => {
+                let column = column.clone();
+                *pos += 1;
+                match tokens.get(*pos) {
+                    Some(ExprToken::Cmp(op)) => {
+                        let op = *op;
+                        *pos += 1;
+                        let value = Self::parse_literal(tokens, pos)?;
+                        Ok((Predicate::Compare { column, op, value }, 1))
+                    }
+                    Some(ExprToken::In) => {
+                        *pos += 1;
+                        Ok((Self::parse_in(column, tokens, pos)?, 1))
+                    }
+                    Some(ExprToken::Not) if tokens.get(*pos + 1) == Some(&ExprToken::In) => {
+                        *pos += 2;
+                        let values = Self::parse_in(column, tokens, pos)?;
+                        Ok((Predicate::Not(Box::new(values)), 2))
+                    }
+                    other => Err(Self::syntax_error(&format!(
+                        "expected comparison or IN after '{}', found {:?}",
+                        column, other
+                    ))),
+                }
+            }
+            other => Err(Self::syntax_error(&format!(
+                "expected column, NOT or '(', found {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn parse_in(
+        column: String,
+        tokens: &[ExprToken],
+        pos: &mut usize,
+    ) -> Result<Self, ComputeError> {
+        Self::expect(tokens, pos, &ExprToken::LParen)?;
+        let mut values = vec![Self::parse_literal(tokens, pos)?];
+        while tokens.get(*pos) == Some(&ExprToken::Comma) {
+            *pos += 1;
+            values.push(Self::parse_literal(tokens, pos)?);
+        }
+        Self::expect(tokens, pos, &ExprToken::RParen)?;
+        Ok(Predicate::In { column, values })
+    }
+
+    fn parse_literal(tokens: &[ExprToken], pos: &mut usize) -> Result<Literal, ComputeError> {
+        let negate = tokens.get(*pos) == Some(&ExprToken::Op(ArithOp::Sub));
+        let at = *pos + negate as usize;
+        let value = match tokens.get(at) {
+            Some(ExprToken::Int(n)) => Literal::Int(*n),
+            Some(ExprToken::Float(n)) => Literal::Number(*n),
+            Some(ExprToken::Literal(value)) if !negate => value.clone(),
+            other => {
+                return Err(Self::syntax_error(&format!(
+                    "expected a literal, found {:?}",
+                    other
+                )))
+            }
+        };
+        *pos = at + 1;
+        Ok(match value {
+            Literal::Int(n) if negate => Literal::Int(-n),
+            Literal::Number(n) if negate => Literal::Number(-n),
+            value => value,
+        })
+    }
+
+    fn expect(
+        tokens: &[ExprToken],
+        pos: &mut usize,
+        expected: &ExprToken,
+    ) -> Result<(), ComputeError> {
+        if tokens.get(*pos) == Some(expected) {
+            *pos += 1;
+            Ok(())
+        } else {
+            Err(Self::syntax_error(&format!(
+                "expected {:?}, found {:?}",
+                expected,
+                tokens.get(*pos)
+            )))
+        }
+    }
+}
+
+// ===== COLUMN EXPRESSIONS =====
+//
+// Grammar for the `with_column` action:
+//
+//   expr   := term (('+' | '-') term)*
+//   term   := factor (('*' | '/') factor)*
+//   factor := '-' factor | '(' expr ')' | number | column
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Parsed `with_column` expression
+#[derive(Debug, Clone, PartialEq)]
+enum ArithExpr {
+    Column(String),
+    Int(i64),
+    Float(f64),
+    Neg(Box<ArithExpr>),
+    Binary {
+        op: ArithOp,
+        left: Box<ArithExpr>,
+        right: Box<ArithExpr>,
+    },
+}
+
+/// Intermediate result: a column-length array, or a one-element scalar
+struct ArithValue {
+    array: ArrayRef,
+    scalar: bool,
+}
+
+impl ArithExpr {
+    fn parse(source: &str) -> Result<Self, ComputeError> {
+        let tokens = tokenize_expr(source, Self::syntax_error)?;
+        let mut pos = 0;
+        let (expr, _) = Self::parse_sum(&tokens, &mut pos, 0)?;
+        if pos < tokens.len() {
+            return Err(Self::syntax_error(&format!(
+                "unexpected {:?} at token {}",
+                tokens[pos], pos
+            )));
+        }
+        Ok(expr)
+    }
+
+    fn syntax_error(detail: &str) -> ComputeError {
+        ComputeError::InvalidParams(format!("Invalid expression: {}", detail))
+    }
+
+    // `nesting` counts the enclosing parentheses and negations; each parser
+    // also returns the depth of the tree it built.
+
+    fn parse_sum(
+        tokens: &[ExprToken],
+        pos: &mut usize,
+        nesting: usize,
+    ) -> Result<(Self, usize), ComputeError> {
+        let (mut left, mut depth) = Self::parse_product(tokens, pos, nesting)?;
+        while let Some(ExprToken::Op(op @ (ArithOp::Add | ArithOp::Sub))) = tokens.get(*pos) {
+            *pos += 1;
+            let (right, right_depth) = Self::parse_product(tokens, pos, nesting)?;
+            depth = nest(depth.max(right_depth) + 1, Self::syntax_error)?;
+            left = ArithExpr::Binary {
+                op: *op,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+        Ok((left, depth))
+    }
+
+    fn parse_product(
+        tokens: &[ExprToken],
+        pos: &mut usize,
+        nesting: usize,
+    ) -> Result<(Self, usize), ComputeError> {
+        let (mut left, mut depth) = Self::parse_factor(tokens, pos, nesting)?;
+        while let Some(ExprToken::Op(op @ (ArithOp::Mul | ArithOp::Div))) = tokens.get(*pos) {
+            *pos += 1;
+            let (right, right_depth) = Self::parse_factor(tokens, pos, nesting)?;
+            depth = nest(depth.max(right_depth) + 1, Self::syntax_error)?;
+            left = ArithExpr::Binary {
+                op: *op,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+        Ok((left, depth))
+    }
+
+    fn parse_factor(
+        tokens: &[ExprToken],
+        pos: &mut usize,
+        nesting: usize,
+    ) -> Result<(Self, usize), ComputeError> {
+        let token = tokens
+            .get(*pos)
+            .ok_or_else(|| Self::syntax_error("unexpected end of expression"))?;
+        *pos += 1;
+        match token {
+            ExprToken::Op(ArithOp::Sub) => {
+                let nesting = nest(nesting + 1, Self::syntax_error)?;
+                let (inner, depth) = Self::parse_factor(tokens, pos, nesting)?;
+                Ok((
+                    ArithExpr::Neg(Box::new(inner)),
+                    nest(depth + 1, Self::syntax_error)?,
+                ))
+            }
+            ExprToken::LParen => {
+                let inner = Self::parse_sum(tokens, pos, nest(nesting + 1, Self::syntax_error)?)?;
+                if tokens.get(*pos) != Some(&ExprToken::RParen) {
+                    return Err(Self::syntax_error("missing ')'"));
+                }
+                *pos += 1;
+                Ok(inner)
+            }
+            ExprToken::Int(n) => Ok((ArithExpr::Int(*n), 1)),
+            ExprToken::Float(n) => Ok((ArithExpr::Float(*n), 1)),
+            ExprToken::Ident(column) => Ok((ArithExpr::Column(column.clone()), 1)),
+            other => Err(Self::syntax_error(&format!("unexpected {:?}", other))),
+        }
     }
 }
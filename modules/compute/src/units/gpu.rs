@@ -32,12 +32,10 @@ pub struct GpuUnit {
 
 #[derive(Clone)]
 struct GpuConfig {
-    max_shader_size: usize, // 1MB max shader code
-    #[allow(dead_code)] // Future: buffer size validation
-    max_buffer_size: usize, // 100MB max buffer
+    max_shader_size: usize,       // 1MB max shader code
+    max_buffer_size: usize,       // 100MB max buffer
     max_workgroup_size: [u32; 3], // [256, 256, 64]
-    #[allow(dead_code)] // Future: dispatch size validation
-    max_dispatch_size: [u32; 3], // [65535, 65535, 65535]
+    max_dispatch_size: [u32; 3],  // [65535, 65535, 65535]
 }
 
 impl Default for GpuConfig {
@@ -51,6 +49,17 @@ impl Default for GpuConfig {
     }
 }
 
+/// Block edge of the CPU matmul, sized for cache reuse
+const MATMUL_TILE: usize = 32;
+/// Largest tile edge offered to the matmul shader
+const MATMUL_MAX_GPU_TILE: u32 = 32;
+/// Tile declaration in `gpu_shaders/matmul.wgsl`, rewritten per adapter
+const MATMUL_TILE_DECL: &str = "const TILE: u32 = 16u;";
+/// WebGPU default limits, assumed when the host reports none
+const WEBGPU_DEFAULT_INVOCATIONS: u64 = 256;
+const WEBGPU_DEFAULT_WORKGROUP_SIZE_XY: u64 = 256;
+const WEBGPU_DEFAULT_WORKGROUP_STORAGE: u64 = 16384;
+
 /// Shader security validator
 struct ShaderValidator {
     max_workgroup_size: u32,
@@ -195,6 +204,27 @@ struct WebGpuRequest {
     dispatch: [u32; 3],
 }
 
+/// What the scheduler may route here: the host adapter's limits clamped to
+/// this unit's validation policy, or the policy alone when no adapter exists
+#[derive(Serialize)]
+struct GpuCapabilities {
+    is_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    adapter: Option<String>,
+    limits: GpuLimits,
+    features: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct GpuLimits {
+    max_buffer_size: u64,
+    max_workgroup_size: [u32; 3],
+    max_workgroup_invocations: u32,
+    max_dispatch_size: [u32; 3],
+    max_bindings: usize,
+    max_shader_size: usize,
+}
+
 #[derive(Serialize)]
 struct BufferDesc {
     id: String,
@@ -210,6 +240,7 @@ impl GpuUnit {
         let mut prebuilt_shaders = HashMap::new();
 
         // Load pre-built WGSL shaders
+        // matmul's TILE is rewritten per adapter, see `matmul_request`
         prebuilt_shaders.insert("matmul", include_str!("gpu_shaders/matmul.wgsl"));
         prebuilt_shaders.insert("fft", include_str!("gpu_shaders/fft.wgsl"));
         prebuilt_shaders.insert(
@@ -313,6 +344,221 @@ impl GpuUnit {
         Ok(())
     }
 
+    /// Report GPU availability and limits as JSON
+    ///
+    /// Rust never touches the device, so the host passes what its WebGPU
+    /// adapter reported as `adapter` (`name`, `limits` keyed by WebGPU limit
+    /// names, `features`). Without one, the unit reports itself unavailable.
+    fn capabilities(&self, params: &JsonValue) -> Result<Vec<u8>, ComputeError> {
+        let adapter = params.get("adapter").filter(|a| a.is_object());
+        let limit = |key: &str, policy: u64| -> u64 {
+            adapter
+                .and_then(|a| a.get("limits"))
+                .and_then(|l| l.get(key))
+                .and_then(|v| v.as_u64())
+                .map_or(policy, |reported| reported.min(policy))
+        };
+
+        let [wx, wy, wz] = self.config.max_workgroup_size;
+        let [dx, dy, dz] = self.config.max_dispatch_size;
+        let dispatch = limit(
+            "maxComputeWorkgroupsPerDimension",
+            dx.max(dy).max(dz) as u64,
+        );
+        // Buffers are bound as storage, so both limits apply
+        let buffer_policy = self.config.max_buffer_size as u64;
+        let max_buffer_size = limit("maxBufferSize", buffer_policy)
+            .min(limit("maxStorageBufferBindingSize", buffer_policy));
+        let capabilities = GpuCapabilities {
+            is_available: adapter.is_some(),
+            adapter: adapter
+                .and_then(|a| a.get("name"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            limits: GpuLimits {
+                max_buffer_size,
+                max_workgroup_size: [
+                    limit("maxComputeWorkgroupSizeX", wx as u64) as u32,
+                    limit("maxComputeWorkgroupSizeY", wy as u64) as u32,
+                    limit("maxComputeWorkgroupSizeZ", wz as u64) as u32,
+                ],
+                max_workgroup_invocations: limit(
+                    "maxComputeInvocationsPerWorkgroup",
+                    self.validator.max_total_invocations as u64,
+                ) as u32,
+                max_dispatch_size: [
+                    dispatch.min(dx as u64) as u32,
+                    dispatch.min(dy as u64) as u32,
+                    dispatch.min(dz as u64) as u32,
+                ],
+                max_bindings: limit(
+                    "maxBindingsPerBindGroup",
+                    self.validator.max_bindings as u64,
+                ) as usize,
+                max_shader_size: self.config.max_shader_size,
+            },
+            features: adapter
+                .and_then(|a| a.get("features"))
+                .and_then(|f| f.as_array())
+                .map(|f| {
+                    f.iter()
+                        .filter_map(|v| v.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
+        serde_json::to_vec(&capabilities)
+            .map_err(|e| ComputeError::ExecutionFailed(format!("Serialization failed: {}", e)))
+    }
+
+    /// Multiply A (m x n) by B (n x p); input is A then B as row-major f32 (LE)
+    ///
+    /// Params: `m`, `n`, `p`. Always returns C as row-major f32 bytes, computed
+    /// on the CPU. Hosts with a WebGPU adapter can run the same product on the
+    /// GPU through `matmul_request`.
+    fn matmul(&self, input: &[u8], params: &JsonValue) -> Result<Vec<u8>, ComputeError> {
+        let [m, n, p] = self.matmul_dims(input, params)?;
+
+        let floats: Vec<f32> = input
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let (a, b) = floats.split_at(m * n);
+        Ok(matmul_tiled(a, b, m, n, p)
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect())
+    }
+
+    /// Checks `m`, `n`, `p` against the input length and the buffer limit
+    fn matmul_dims(&self, input: &[u8], params: &JsonValue) -> Result<[usize; 3], ComputeError> {
+        let dim = |key: &str| -> Result<usize, ComputeError> {
+            params
+                .get(key)
+                .and_then(|v| v.as_u64())
+                .filter(|&d| d > 0)
+                .map(|d| d as usize)
+                .ok_or_else(|| ComputeError::InvalidParams(format!("Missing or zero '{}'", key)))
+        };
+        let (m, n, p) = (dim("m")?, dim("n")?, dim("p")?);
+
+        // Each matrix must fit in one GPU buffer
+        let max_elements = self.config.max_buffer_size / 4;
+        let elements =
+            |rows: usize, cols: usize| rows.checked_mul(cols).filter(|&len| len <= max_elements);
+        let (a_len, b_len) = match (elements(m, n), elements(n, p), elements(m, p)) {
+            (Some(a), Some(b), Some(_)) => (a, b),
+            _ => {
+                return Err(ComputeError::InvalidParams(format!(
+                    "Matrix dimensions too large: {}x{}x{}",
+                    m, n, p
+                )))
+            }
+        };
+        if input.len() != (a_len + b_len) * 4 {
+            return Err(ComputeError::InvalidParams(format!(
+                "Expected {} bytes for {}x{} and {}x{} f32 matrices, got {}",
+                (a_len + b_len) * 4,
+                m,
+                n,
+                n,
+                p,
+                input.len()
+            )));
+        }
+        Ok([m, n, p])
+    }
+
+    /// WebGPU request for `matmul` on the tiled shader
+    ///
+    /// Same input and params as `matmul`, plus the host's `adapter` limits (as
+    /// for `capabilities`). The tile is the largest power of two whose
+    /// workgroup fits those limits, falling back to WebGPU defaults (16x16).
+    /// Bindings: dims uniform, A, B, then C.
+    fn matmul_request(&self, input: &[u8], params: &JsonValue) -> Result<Vec<u8>, ComputeError> {
+        let [m, n, p] = self.matmul_dims(input, params)?;
+        let tile = self.matmul_gpu_tile(params);
+
+        let dispatch = [p.div_ceil(tile as usize), m.div_ceil(tile as usize)];
+        let max_dispatch = adapter_limit(params, "maxComputeWorkgroupsPerDimension")
+            .unwrap_or(u64::MAX)
+            .min(self.config.max_dispatch_size[0].min(self.config.max_dispatch_size[1]) as u64);
+        if dispatch.iter().any(|&d| d as u64 > max_dispatch) {
+            return Err(ComputeError::InvalidParams(format!(
+                "Matmul {}x{}x{} needs {:?} workgroups of {}x{}, limit {}",
+                m, n, p, dispatch, tile, tile, max_dispatch
+            )));
+        }
+
+        let tile_decl = format!("const TILE: u32 = {}u;", tile);
+        let shader = self.prebuilt_shaders["matmul"].replacen(MATMUL_TILE_DECL, &tile_decl, 1);
+        let analysis = self.validate_shader(&shader)?;
+        let dims: Vec<u8> = [m as u32, n as u32, p as u32, tile, tile, tile]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+
+        let buffer =
+            |id: &str, data: &[u8], size: usize, usage: &str, type_hint: &str| BufferDesc {
+                id: id.to_string(),
+                data: general_purpose::STANDARD.encode(data),
+                size,
+                usage: usage.to_string(),
+                type_hint: type_hint.to_string(),
+            };
+        let (a, b) = input.split_at(m * n * 4);
+
+        let request = WebGpuRequest {
+            method: "matmul".to_string(),
+            analysis: Some(analysis),
+            shader,
+            buffers: vec![
+                buffer("dims", &dims, dims.len(), "uniform", "uint32"),
+                buffer("a", a, a.len(), "storage", "float32"),
+                buffer("b", b, b.len(), "storage", "float32"),
+                buffer("c", &[], m * p * 4, "storage", "float32"),
+            ],
+            workgroup: [tile, tile, 1],
+            dispatch: [dispatch[0] as u32, dispatch[1] as u32, 1],
+        };
+
+        serde_json::to_vec(&request)
+            .map_err(|e| ComputeError::ExecutionFailed(format!("Serialization failed: {}", e)))
+    }
+
+    /// Largest power-of-two tile whose TILE x TILE workgroup and two f32
+    /// shared tiles fit the adapter limits and this unit's validation policy
+    fn matmul_gpu_tile(&self, params: &JsonValue) -> u32 {
+        let limit = |key: &str, default: u64| adapter_limit(params, key).unwrap_or(default);
+        let invocations = limit(
+            "maxComputeInvocationsPerWorkgroup",
+            WEBGPU_DEFAULT_INVOCATIONS,
+        )
+        .min(self.validator.max_total_invocations as u64);
+        let edge = limit("maxComputeWorkgroupSizeX", WEBGPU_DEFAULT_WORKGROUP_SIZE_XY)
+            .min(limit(
+                "maxComputeWorkgroupSizeY",
+                WEBGPU_DEFAULT_WORKGROUP_SIZE_XY,
+            ))
+            .min(self.config.max_workgroup_size[0].min(self.config.max_workgroup_size[1]) as u64)
+            .min(self.validator.max_workgroup_size as u64);
+        let storage = limit(
+            "maxComputeWorkgroupStorageSize",
+            WEBGPU_DEFAULT_WORKGROUP_STORAGE,
+        );
+
+        let mut tile = MATMUL_MAX_GPU_TILE;
+        while tile > 1 {
+            let t = tile as u64;
+            if t * t <= invocations && t <= edge && 2 * t * t * 4 <= storage {
+                break;
+            }
+            tile /= 2;
+        }
+        tile
+    }
+
     /// Create optimized WebGPU execution request
     fn create_webgpu_request(
         &self,
@@ -389,6 +635,36 @@ impl GpuUnit {
     }
 }
 
+/// A WebGPU limit the host reported for its adapter (see `capabilities`)
+fn adapter_limit(params: &JsonValue, key: &str) -> Option<u64> {
+    params
+        .get("adapter")
+        .and_then(|a| a.get("limits"))
+        .and_then(|l| l.get(key))
+        .and_then(|v| v.as_u64())
+}
+
+/// CPU `matmul`, blocked like the shader's tiles for cache reuse
+/// Each output still accumulates k in ascending order, as the shader does.
+pub(crate) fn matmul_tiled(a: &[f32], b: &[f32], m: usize, n: usize, p: usize) -> Vec<f32> {
+    let mut c = vec![0.0f32; m * p];
+    for i0 in (0..m).step_by(MATMUL_TILE) {
+        for j0 in (0..p).step_by(MATMUL_TILE) {
+            for k0 in (0..n).step_by(MATMUL_TILE) {
+                for i in i0..(i0 + MATMUL_TILE).min(m) {
+                    for k in k0..(k0 + MATMUL_TILE).min(n) {
+                        let a_ik = a[i * n + k];
+                        for j in j0..(j0 + MATMUL_TILE).min(p) {
+                            c[i * p + j] += a_ik * b[k * p + j];
+                        }
+                    }
+                }
+            }
+        }
+    }
+    c
+}
+
 impl Default for GpuUnit {
     fn default() -> Self {
         Self::new()
@@ -480,6 +756,11 @@ impl UnitProxy for GpuUnit {
             "displacement_mapping",
             // ===== CUSTOM SHADER (1) =====
             "execute_wgsl",
+            // ===== LINEAR ALGEBRA (2) =====
+            "matmul",
+            "matmul_request",
+            // ===== INTROSPECTION (1) =====
+            "capabilities",
         ]
     }
 
@@ -583,6 +864,13 @@ impl UnitProxy for GpuUnit {
             // ===== CUSTOM SHADER (1) =====
             "execute_wgsl" => self.create_webgpu_request(action, input, &params),
 
+            // ===== LINEAR ALGEBRA (2) =====
+            "matmul" => self.matmul(input, &params),
+            "matmul_request" => self.matmul_request(input, &params),
+
+            // ===== INTROSPECTION (1) =====
+            "capabilities" => self.capabilities(&params),
+
             _ => Err(ComputeError::UnknownAction {
                 service: "gpu".to_string(),
                 action: action.to_string(),
@@ -3,8 +3,8 @@ use async_trait::async_trait;
 use dashmap::DashMap;
 use fast_image_resize as fr;
 use image::{
-    codecs::jpeg::JpegEncoder, codecs::png::PngEncoder, DynamicImage, GenericImageView,
-    ImageEncoder,
+    codecs::jpeg::JpegEncoder, codecs::png::PngEncoder, codecs::webp::WebPEncoder, DynamicImage,
+    GenericImageView, ImageEncoder,
 };
 use imageproc::filter;
 use rayon::prelude::*;
@@ -152,6 +152,14 @@ impl ImageUnit {
         let height = params["height"].as_u64().unwrap_or(600) as u32;
         let filter_str = params["filter"].as_str().unwrap_or("Lanczos3");
 
+        // Optionally fit inside width x height, keeping the source aspect ratio
+        let (width, height) = if params["preserve_aspect"].as_bool().unwrap_or(false) {
+            fit_within(img.width(), img.height(), width, height)
+        } else {
+            (width, height)
+        };
+        self.validate_target_size(width, height)?;
+
         // Get or create cached resizer
         let filter_type = self.parse_fr_filter(filter_str);
         let key = ResizeKey {
@@ -179,6 +187,25 @@ impl ImageUnit {
         self.fr_image_to_dynamic(&dst_image)
     }
 
+    /// Reject empty targets and ones whose raw pixels alone exceed the output limit
+    fn validate_target_size(&self, width: u32, height: u32) -> Result<(), ComputeError> {
+        if width == 0 || height == 0 {
+            return Err(ComputeError::InvalidParams(format!(
+                "Target dimensions must be non-zero, got {}x{}",
+                width, height
+            )));
+        }
+        let raw_size = width as usize * height as usize * 4;
+        let max = self.resource_limits().max_output_size;
+        if raw_size > max {
+            return Err(ComputeError::OutputTooLarge {
+                size: raw_size,
+                max,
+            });
+        }
+        Ok(())
+    }
+
     /// Convert DynamicImage to fast_image_resize Image
     fn to_fr_image<'a>(&self, img: &'a DynamicImage) -> Result<fr::Image<'a>, ComputeError> {
         let width = img.width();
@@ -286,6 +313,7 @@ impl ImageUnit {
             // Changed from method
             "resize" => self.resize_simd(img, params),
             "crop" => self.crop(img, params),
+            "rotate" => self.rotate(img, params),
             "grayscale" => Ok(img.grayscale()),
             _ => Err(ComputeError::UnknownAction {
                 service: "image".to_string(),
@@ -312,6 +340,7 @@ impl UnitProxy for ImageUnit {
             "resize_to_fill",
             "thumbnail",
             "crop",
+            "rotate",
             "rotate90",
             "rotate180",
             "rotate270",
@@ -331,6 +360,7 @@ impl UnitProxy for ImageUnit {
             "overlay",
             "tile",
             "adjust_levels",
+            "convert",
             "toolkit_process",
         ]
     }
@@ -394,6 +424,7 @@ impl UnitProxy for ImageUnit {
 
             // Fast operations
             "crop" => self.crop(&img, &params)?,
+            "rotate" => self.rotate(&img, &params)?,
             "rotate90" => img.rotate90(),
             "rotate180" => img.rotate180(),
             "rotate270" => img.rotate270(),
@@ -440,6 +471,9 @@ impl UnitProxy for ImageUnit {
             "tile" => self.tile(&img, &params)?,
             "adjust_levels" => self.adjust_levels(&img, &params)?,
 
+            // Format conversion: re-encode as `format` (png, jpeg, webp)
+            "convert" => img,
+
             "toolkit_process" => {
                 return self.toolkit_process(input, &params);
             }
@@ -478,9 +512,43 @@ impl ImageUnit {
         let width = params["width"].as_u64().unwrap_or(100) as u32;
         let height = params["height"].as_u64().unwrap_or(100) as u32;
 
+        let fits = width > 0
+            && height > 0
+            && matches!(x.checked_add(width), Some(right) if right <= img.width())
+            && matches!(y.checked_add(height), Some(bottom) if bottom <= img.height());
+        if !fits {
+            return Err(ComputeError::InvalidParams(format!(
+                "Crop box {}x{} at ({}, {}) is outside the {}x{} image",
+                width,
+                height,
+                x,
+                y,
+                img.width(),
+                img.height()
+            )));
+        }
+
         Ok(img.crop_imm(x, y, width, height))
     }
 
+    /// Rotate clockwise by `degrees` (90, 180 or 270)
+    fn rotate(
+        &self,
+        img: &DynamicImage,
+        params: &serde_json::Value,
+    ) -> Result<DynamicImage, ComputeError> {
+        match params["degrees"].as_i64().unwrap_or(90).rem_euclid(360) {
+            0 => Ok(img.clone()),
+            90 => Ok(img.rotate90()),
+            180 => Ok(img.rotate180()),
+            270 => Ok(img.rotate270()),
+            other => Err(ComputeError::InvalidParams(format!(
+                "Rotation must be a multiple of 90 degrees, got {}",
+                other
+            ))),
+        }
+    }
+
     fn gaussian_blur(
         &self,
         img: &DynamicImage,
@@ -622,30 +690,34 @@ impl ImageUnit {
         let mut output = Vec::new();
 
         match format {
-            "jpeg" => {
+            "jpeg" | "jpg" => {
+                // JPEG has no alpha channel
+                let rgb = DynamicImage::ImageRgb8(img.to_rgb8());
                 let mut encoder = JpegEncoder::new_with_quality(&mut output, quality);
                 encoder
                     .encode(
-                        img.as_bytes(),
-                        img.width(),
-                        img.height(),
-                        img.color().into(),
+                        rgb.as_bytes(),
+                        rgb.width(),
+                        rgb.height(),
+                        rgb.color().into(),
                     )
                     .map_err(|e| {
                         ComputeError::ExecutionFailed(format!("JPEG encode failed: {}", e))
                     })?;
             }
-            "jpg" => {
-                let mut encoder = JpegEncoder::new_with_quality(&mut output, quality);
+            "webp" => {
+                // Lossless only (quality is ignored); the encoder takes 8-bit RGBA
+                let rgba = DynamicImage::ImageRgba8(img.to_rgba8());
+                let encoder = WebPEncoder::new_lossless(&mut output);
                 encoder
-                    .encode(
-                        img.as_bytes(),
-                        img.width(),
-                        img.height(),
-                        img.color().into(),
+                    .write_image(
+                        rgba.as_bytes(),
+                        rgba.width(),
+                        rgba.height(),
+                        rgba.color().into(),
                     )
                     .map_err(|e| {
-                        ComputeError::ExecutionFailed(format!("JPEG encode failed: {}", e))
+                        ComputeError::ExecutionFailed(format!("WebP encode failed: {}", e))
                     })?;
             }
             "png" => {
@@ -717,3 +789,15 @@ impl ImageUnit {
         }
     }
 }
+
+/// Largest size inside `max_width` x `max_height` with the source aspect ratio
+fn fit_within(width: u32, height: u32, max_width: u32, max_height: u32) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (max_width, max_height);
+    }
+    let scale = (max_width as f64 / width as f64).min(max_height as f64 / height as f64);
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
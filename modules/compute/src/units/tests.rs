@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests {
     use super::super::*;
-    use crate::engine::UnitProxy;
+    use crate::engine::{ComputeError, UnitProxy};
     use crate::units::image::ImageUnit;
-    use ::image::ImageEncoder;
+    use ::image::{GenericImageView, ImageEncoder};
     use audio::AudioUnit;
     use base64::{engine::general_purpose, Engine as _};
     use crypto::CryptoUnit;
@@ -127,6 +127,215 @@ mod tests {
         }
     }
 
+    fn matmul_input(m: usize, n: usize, p: usize) -> (Vec<f32>, Vec<f32>, Vec<u8>) {
+        let a: Vec<f32> = (0..m * n)
+            .map(|i| ((i * 7) % 13) as f32 * 0.25 - 1.5)
+            .collect();
+        let b: Vec<f32> = (0..n * p)
+            .map(|i| ((i * 5) % 11) as f32 * 0.5 - 2.0)
+            .collect();
+        let bytes = a.iter().chain(&b).flat_map(|v| v.to_le_bytes()).collect();
+        (a, b, bytes)
+    }
+
+    fn matmul_reference(a: &[f32], b: &[f32], m: usize, n: usize, p: usize) -> Vec<f32> {
+        let mut c = vec![0.0f32; m * p];
+        for i in 0..m {
+            for j in 0..p {
+                c[i * p + j] = (0..n).map(|k| a[i * n + k] * b[k * p + j]).sum();
+            }
+        }
+        c
+    }
+
+    #[tokio::test]
+    async fn test_gpu_matmul_cpu_fallback_matches_reference() {
+        let unit = GpuUnit::new();
+        // Dimensions straddle the 32-wide tiles
+        let (m, n, p) = (37, 45, 29);
+        let (a, b, input) = matmul_input(m, n, p);
+
+        let params = br#"{"m":37,"n":45,"p":29}"#;
+        let output = unit.execute("matmul", &input, params).await.unwrap();
+        let c: Vec<f32> = output
+            .chunks_exact(4)
+            .map(|v| f32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+            .collect();
+
+        let expected = matmul_reference(&a, &b, m, n, p);
+        assert_eq!(c.len(), expected.len());
+        for (got, want) in c.iter().zip(&expected) {
+            assert!((got - want).abs() < 1e-3, "{} != {}", got, want);
+        }
+    }
+
+    /// Runs the tiled shader's per-invocation logic over a request's dispatch grid
+    fn emulate_matmul_shader(
+        request: &serde_json::Value,
+        a: &[f32],
+        b: &[f32],
+        [m, n, p]: [usize; 3],
+    ) -> Vec<f32> {
+        let tile = request["workgroup"][0].as_u64().unwrap() as usize;
+        let groups_x = request["dispatch"][0].as_u64().unwrap() as usize;
+        let groups_y = request["dispatch"][1].as_u64().unwrap() as usize;
+        let mut c = vec![f32::NAN; m * p];
+        for row in 0..groups_y * tile {
+            for col in 0..groups_x * tile {
+                let mut sum = 0.0f32;
+                for t in 0..n.div_ceil(tile) {
+                    for k in 0..tile {
+                        let (a_col, b_row) = (t * tile + k, t * tile + k);
+                        let a_v = if row < m && a_col < n {
+                            a[row * n + a_col]
+                        } else {
+                            0.0
+                        };
+                        let b_v = if b_row < n && col < p {
+                            b[b_row * p + col]
+                        } else {
+                            0.0
+                        };
+                        sum += a_v * b_v;
+                    }
+                }
+                if row < m && col < p {
+                    c[row * p + col] = sum;
+                }
+            }
+        }
+        c
+    }
+
+    #[tokio::test]
+    async fn test_gpu_matmul_request_fits_default_limits() {
+        let unit = GpuUnit::new();
+        let (m, n, p) = (70, 40, 33);
+        let (a, b, input) = matmul_input(m, n, p);
+
+        let params = br#"{"m":70,"n":40,"p":33}"#;
+        let output = unit
+            .execute("matmul_request", &input, params)
+            .await
+            .unwrap();
+        let request: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        // WebGPU's default 256 invocations per workgroup allows 16x16
+        assert_eq!(request["method"], "matmul");
+        assert_eq!(request["workgroup"], serde_json::json!([16, 16, 1]));
+        assert_eq!(request["dispatch"], serde_json::json!([3, 5, 1]));
+        assert_eq!(
+            request["analysis"]["requirements"]["min_workgroup_size"],
+            serde_json::json!([16, 16, 1])
+        );
+        let buffers = request["buffers"].as_array().unwrap();
+        let ids: Vec<&str> = buffers.iter().map(|b| b["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["dims", "a", "b", "c"]);
+        assert_eq!(buffers[3]["size"], 70 * 33 * 4);
+
+        // The dispatched grid covers C and matches the CPU reference
+        let expected = matmul_reference(&a, &b, m, n, p);
+        let c = emulate_matmul_shader(&request, &a, &b, [m, n, p]);
+        for (got, want) in c.iter().zip(&expected) {
+            assert!((got - want).abs() < 1e-3, "{} != {}", got, want);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gpu_matmul_request_uses_adapter_limits() {
+        let unit = GpuUnit::new();
+        let (_, _, input) = matmul_input(70, 8, 33);
+
+        let params = br#"{"m":70,"n":8,"p":33,"adapter":{"limits":{
+            "maxComputeInvocationsPerWorkgroup":1024,
+            "maxComputeWorkgroupSizeX":1024,
+            "maxComputeWorkgroupSizeY":1024,
+            "maxComputeWorkgroupStorageSize":32768}}}"#;
+        let output = unit
+            .execute("matmul_request", &input, params)
+            .await
+            .unwrap();
+        let request: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(request["workgroup"], serde_json::json!([32, 32, 1]));
+        assert_eq!(request["dispatch"], serde_json::json!([2, 3, 1]));
+
+        let params = br#"{"m":70,"n":8,"p":33,"adapter":{"limits":{
+            "maxComputeInvocationsPerWorkgroup":64}}}"#;
+        let output = unit
+            .execute("matmul_request", &input, params)
+            .await
+            .unwrap();
+        let request: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(request["workgroup"], serde_json::json!([8, 8, 1]));
+    }
+
+    #[tokio::test]
+    async fn test_gpu_matmul_always_returns_product_bytes() {
+        let unit = GpuUnit::new();
+        let (m, n, p) = (64, 64, 64);
+        let (a, b, input) = matmul_input(m, n, p);
+
+        let params = br#"{"m":64,"n":64,"p":64,"backend":"gpu","gpu_available":true}"#;
+        let output = unit.execute("matmul", &input, params).await.unwrap();
+        assert_eq!(output.len(), m * p * 4);
+        let expected = matmul_reference(&a, &b, m, n, p);
+        for (got, want) in output.chunks_exact(4).zip(&expected) {
+            let got = f32::from_le_bytes([got[0], got[1], got[2], got[3]]);
+            assert!((got - want).abs() < 1e-3, "{} != {}", got, want);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gpu_matmul_rejects_mismatched_input() {
+        let unit = GpuUnit::new();
+        let (_, _, input) = matmul_input(4, 4, 4);
+
+        let result = unit
+            .execute("matmul", &input[4..], br#"{"m":4,"n":4,"p":4}"#)
+            .await;
+        assert!(matches!(result, Err(ComputeError::InvalidParams(_))));
+        let result = unit
+            .execute("matmul", &input, br#"{"m":4,"n":0,"p":4}"#)
+            .await;
+        assert!(matches!(result, Err(ComputeError::InvalidParams(_))));
+    }
+
+    #[tokio::test]
+    async fn test_gpu_capabilities_without_adapter() {
+        let unit = GpuUnit::new();
+        let output = unit.execute("capabilities", b"", b"{}").await.unwrap();
+        let caps: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(caps["is_available"], false);
+        assert!(caps.get("adapter").is_none());
+        assert_eq!(caps["features"], serde_json::json!([]));
+        assert!(caps["limits"]["max_buffer_size"].as_u64().unwrap() > 0);
+        assert_eq!(
+            caps["limits"]["max_workgroup_size"],
+            serde_json::json!([256, 256, 64])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gpu_capabilities_clamps_adapter_limits() {
+        let unit = GpuUnit::new();
+        let params = br#"{"adapter":{"name":"test-adapter","features":["shader-f16"],
+            "limits":{"maxBufferSize":268435456,"maxStorageBufferBindingSize":1048576,
+            "maxComputeWorkgroupSizeX":1024,"maxComputeWorkgroupSizeZ":32}}}"#;
+        let output = unit.execute("capabilities", b"", params).await.unwrap();
+        let caps: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(caps["is_available"], true);
+        assert_eq!(caps["adapter"], "test-adapter");
+        assert_eq!(caps["features"], serde_json::json!(["shader-f16"]));
+        // Smaller of the adapter's limits and the unit's policy
+        assert_eq!(caps["limits"]["max_buffer_size"], 1048576);
+        assert_eq!(
+            caps["limits"]["max_workgroup_size"],
+            serde_json::json!([256, 256, 32])
+        );
+    }
+
     // ========== DATA UNIT TESTS ==========
 
     #[test]
@@ -161,6 +370,256 @@ mod tests {
         assert!(result.is_ok(), "CSV write should succeed with arrow data");
     }
 
+    #[tokio::test]
+    async fn test_data_csv_inference_widens_int_to_float() {
+        let unit = DataUnit::new();
+        let mut input = String::from("id,price\n");
+        for i in 0..49 {
+            input.push_str(&format!("{},{}\n", i, i * 10));
+        }
+        input.push_str("49,12.5\n50,\n");
+
+        let output = unit
+            .execute("csv_read", input.as_bytes(), b"{}")
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let schema = batch.schema();
+        assert_eq!(
+            schema.field_with_name("id").unwrap().data_type(),
+            &arrow::datatypes::DataType::Int64
+        );
+        assert_eq!(
+            schema.field_with_name("price").unwrap().data_type(),
+            &arrow::datatypes::DataType::Float64
+        );
+
+        let price = batch
+            .column_by_name("price")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        assert_eq!(price.value(49), 12.5);
+        assert!(price.is_null(50), "Empty fields should read as nulls");
+    }
+
+    #[tokio::test]
+    async fn test_data_csv_inference_widens_numeric_to_string() {
+        let unit = DataUnit::new();
+        let input = b"id,code\n1,100\n2,\n3,2.5\n4,A-17\n";
+
+        let output = unit.execute("csv_read", input, b"{}").await.unwrap();
+        let batch = decode_arrow_batch(&output);
+        assert_eq!(
+            batch.schema().field_with_name("code").unwrap().data_type(),
+            &arrow::datatypes::DataType::Utf8
+        );
+        assert_eq!(batch.num_rows(), 4);
+
+        // A sample that stops before the string keeps the numeric type
+        let result = unit
+            .execute("csv_read", input, br#"{"infer_rows":3}"#)
+            .await;
+        assert!(result.is_err(), "Rows past the sample must still parse");
+    }
+
+    #[tokio::test]
+    async fn test_data_csv_quoted_fields() {
+        let unit = DataUnit::new();
+        let input =
+            b"name,address,amount\n\"Smith, J\",\"1 Main St\nApt 2\",10\n\"Doe \"\"JD\"\"\",Elm,2.5\n";
+
+        let output = unit.execute("csv_read", input, b"{}").await.unwrap();
+        let batch = decode_arrow_batch(&output);
+        assert_eq!(batch.num_columns(), 3);
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(
+            batch
+                .schema()
+                .field_with_name("amount")
+                .unwrap()
+                .data_type(),
+            &arrow::datatypes::DataType::Float64
+        );
+
+        let name = batch
+            .column_by_name("name")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap();
+        assert_eq!(name.value(0), "Smith, J");
+        assert_eq!(name.value(1), "Doe \"JD\"");
+
+        let address = batch
+            .column_by_name("address")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap();
+        assert_eq!(address.value(0), "1 Main St\nApt 2");
+    }
+
+    #[tokio::test]
+    async fn test_data_csv_custom_delimiter() {
+        let unit = DataUnit::new();
+        let input = b"id;price;label\n1;2,5;a\n2;3;b\n";
+
+        let output = unit
+            .execute("csv_read", input, br#"{"delimiter":";"}"#)
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        assert_eq!(batch.num_columns(), 3);
+        assert_eq!(
+            batch.schema().field_with_name("id").unwrap().data_type(),
+            &arrow::datatypes::DataType::Int64
+        );
+        // Decimal commas are not numbers, and are not split on with a `;` delimiter
+        assert_eq!(
+            batch.schema().field_with_name("price").unwrap().data_type(),
+            &arrow::datatypes::DataType::Utf8
+        );
+
+        let result = unit
+            .execute("csv_read", input, br#"{"delimiter":";;"}"#)
+            .await;
+        assert!(matches!(result, Err(ComputeError::InvalidParams(_))));
+    }
+
+    #[tokio::test]
+    async fn test_data_arrow_file_roundtrip() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"id":1,"value":100},{"id":2,"value":200}]"#;
+        let stream = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let file = unit
+            .execute("arrow_file_write", &stream, b"{}")
+            .await
+            .unwrap();
+        assert!(
+            file.starts_with(b"ARROW1"),
+            "IPC file format starts with magic"
+        );
+        assert!(file.ends_with(b"ARROW1"), "IPC file format ends with magic");
+        assert_ne!(file, stream);
+
+        let back = unit.execute("arrow_file_read", &file, b"{}").await.unwrap();
+        let batch = decode_arrow_batch(&back);
+        assert_eq!(batch, decode_arrow_batch(&stream));
+
+        // Stream input is not a file
+        let result = unit.execute("arrow_file_read", &stream, b"{}").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_data_json_write_lines_vs_array() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"id":1,"name":"a"},{"id":2,"name":"b"},{"id":3,"name":"c"}]"#;
+        let stream = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let lines = unit.execute("json_write", &stream, b"{}").await.unwrap();
+        let array = unit
+            .execute("json_write", &stream, br#"{"format":"array"}"#)
+            .await
+            .unwrap();
+        let pretty = unit
+            .execute("json_write", &stream, br#"{"format":"pretty"}"#)
+            .await
+            .unwrap();
+
+        let line_rows: Vec<serde_json::Value> = std::str::from_utf8(&lines)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(line_rows.len(), 3);
+        assert!(serde_json::from_slice::<serde_json::Value>(&lines).is_err());
+
+        // Same rows either way; only the framing differs
+        let array_rows: serde_json::Value = serde_json::from_slice(&array).unwrap();
+        assert_eq!(array_rows, serde_json::Value::Array(line_rows));
+        assert!(!array.contains(&b'\n'));
+
+        let pretty_rows: serde_json::Value = serde_json::from_slice(&pretty).unwrap();
+        assert_eq!(pretty_rows, array_rows);
+        assert!(pretty.len() > array.len());
+
+        let result = unit
+            .execute("json_write", &stream, br#"{"format":"xml"}"#)
+            .await;
+        assert!(matches!(result, Err(ComputeError::InvalidParams(_))));
+    }
+
+    #[tokio::test]
+    async fn test_data_json_write_stream_spans_batches() {
+        let unit = DataUnit::new();
+        let first = decode_arrow_batch(
+            &unit
+                .execute("json_read", br#"[{"id":1},{"id":2}]"#, b"{}")
+                .await
+                .unwrap(),
+        );
+        let second = decode_arrow_batch(
+            &unit
+                .execute("json_read", br#"[{"id":3}]"#, b"{}")
+                .await
+                .unwrap(),
+        );
+
+        let mut stream = Vec::new();
+        {
+            let mut writer =
+                arrow::ipc::writer::StreamWriter::try_new(&mut stream, &first.schema()).unwrap();
+            writer.write(&first).unwrap();
+            writer.write(&second).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let lines = unit
+            .execute("json_write_stream", &stream, b"{}")
+            .await
+            .unwrap();
+        assert_eq!(lines, b"{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n");
+
+        let array = unit
+            .execute("json_write_stream", &stream, br#"{"format":"array"}"#)
+            .await
+            .unwrap();
+        assert_eq!(array, br#"[{"id":1},{"id":2},{"id":3}]"#);
+
+        // json_write decodes the whole stream into one batch first
+        let single = unit.execute("json_write", &stream, b"{}").await.unwrap();
+        assert_eq!(single, lines);
+    }
+
+    #[tokio::test]
+    async fn test_data_arrow_read_detects_file_format() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"id":1,"value":100},{"id":2,"value":200},{"id":3,"value":300}]"#;
+        let stream = unit.execute("json_read", json_data, b"{}").await.unwrap();
+        let file = unit
+            .execute("arrow_file_write", &stream, b"{}")
+            .await
+            .unwrap();
+
+        // Any Arrow-consuming action takes either format
+        for input in [&stream, &file] {
+            let output = unit.execute("count", input, b"{}").await.unwrap();
+            let count: usize = serde_json::from_slice(&output).unwrap();
+            assert_eq!(count, 3);
+
+            let output = unit
+                .execute("sum", input, br#"{"column":"value"}"#)
+                .await
+                .unwrap();
+            let sum: f64 = serde_json::from_slice(&output).unwrap();
+            assert_eq!(sum, 600.0);
+        }
+    }
+
     #[tokio::test]
     async fn test_data_json_roundtrip() {
         let unit = DataUnit::new();
@@ -190,6 +649,47 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_data_parquet_error_keeps_source() {
+        use std::error::Error;
+
+        let unit = DataUnit::new();
+        let err = unit
+            .execute("parquet_read", b"definitely not parquet", b"{}")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ComputeError::Parquet { .. }));
+        assert!(err
+            .to_string()
+            .starts_with("Execution failed: Parquet read failed: "));
+
+        let source = err
+            .source()
+            .expect("Parquet failure should chain its source");
+        let parquet_err = source
+            .downcast_ref::<parquet::errors::ParquetError>()
+            .expect("Source should downcast to ParquetError");
+        assert!(err.to_string().ends_with(&parquet_err.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_data_arrow_error_keeps_source() {
+        use std::error::Error;
+
+        let unit = DataUnit::new();
+        let err = unit
+            .execute("count", b"not arrow either", b"{}")
+            .await
+            .unwrap_err();
+
+        let source = err.source().expect("Arrow failure should chain its source");
+        assert!(source.is::<arrow::error::ArrowError>());
+        assert!(err
+            .to_string()
+            .starts_with("Execution failed: Arrow IPC read failed: "));
+    }
+
     #[tokio::test]
     async fn test_data_unit_execute_parquet() {
         let unit = DataUnit::new();
@@ -209,6 +709,853 @@ mod tests {
         assert!(result.is_ok(), "Empty JSON array should be handled");
     }
 
+    #[tokio::test]
+    async fn test_data_rolling_mean() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"x":1.0},{"x":2.0},{"x":3.0},{"x":4.0},{"x":5.0}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let params = br#"{"column":"x","window":3,"agg":"mean"}"#;
+        let output = unit.execute("rolling", &arrow_data, params).await.unwrap();
+        let batch = decode_arrow_batch(&output);
+        let rolled = batch
+            .column_by_name("x_rolling_mean_3")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+
+        // Partial windows at the start are null until min_periods (default = window)
+        assert!(rolled.is_null(0));
+        assert!(rolled.is_null(1));
+        assert_eq!(rolled.value(2), 2.0);
+        assert_eq!(rolled.value(3), 3.0);
+        assert_eq!(rolled.value(4), 4.0);
+
+        let params = br#"{"column":"x","window":3,"agg":"sum","min_periods":1}"#;
+        let output = unit.execute("rolling", &arrow_data, params).await.unwrap();
+        let batch = decode_arrow_batch(&output);
+        let rolled = batch
+            .column_by_name("x_rolling_sum_3")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        let values: Vec<f64> = rolled.values().iter().copied().collect();
+        assert_eq!(values, vec![1.0, 3.0, 6.0, 9.0, 12.0]);
+    }
+
+    #[tokio::test]
+    async fn test_data_distinct_full_rows() {
+        let unit = DataUnit::new();
+        let json_data =
+            br#"[{"id":1,"city":"NYC"},{"id":2,"city":"LA"},{"id":1,"city":"NYC"},{"id":1,"city":"LA"}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let output = unit.execute("distinct", &arrow_data, b"{}").await.unwrap();
+        let batch = decode_arrow_batch(&output);
+        assert_eq!(batch.num_rows(), 3);
+
+        let ids = batch
+            .column_by_name("id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap();
+        let ids: Vec<i64> = ids.values().iter().copied().collect();
+        assert_eq!(
+            ids,
+            vec![1, 2, 1],
+            "First occurrence of each row is kept in order"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_data_distinct_subset_with_nulls() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"id":1,"city":"NYC"},{"id":2,"city":null},{"id":3,"city":"NYC"},{"id":4,"city":null},{"id":5,"city":"LA"}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let output = unit
+            .execute("distinct", &arrow_data, br#"{"columns":["city"]}"#)
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let ids = batch
+            .column_by_name("id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap();
+        let ids: Vec<i64> = ids.values().iter().copied().collect();
+        assert_eq!(ids, vec![1, 2, 5], "Nulls form a single key of their own");
+
+        let output = unit
+            .execute("unique_values", &arrow_data, br#"{"column":"city"}"#)
+            .await
+            .unwrap();
+        let values: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(values, serde_json::json!(["NYC", null, "LA"]));
+    }
+
+    #[tokio::test]
+    async fn test_data_fillna_constant() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"a":1,"b":"x"},{"a":null,"b":null},{"a":3,"b":null}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let params = br#"{"values":{"b":"missing"},"value":0}"#;
+        let output = unit.execute("fillna", &arrow_data, params).await.unwrap();
+        let batch = decode_arrow_batch(&output);
+
+        let a = batch
+            .column_by_name("a")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap();
+        assert_eq!(a.null_count(), 0);
+        assert_eq!(a.values().to_vec(), vec![1, 0, 3]);
+
+        let b = batch
+            .column_by_name("b")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap();
+        assert_eq!(b.value(1), "missing");
+        assert_eq!(b.value(2), "missing");
+
+        // A per-column constant that cannot be cast is an error
+        let result = unit
+            .execute("fillna", &arrow_data, br#"{"values":{"a":"oops"}}"#)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_data_fillna_mean() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"a":1,"b":"x"},{"a":null,"b":null},{"a":4,"b":"z"}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let output = unit
+            .execute("fillna", &arrow_data, br#"{"strategy":"mean"}"#)
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+
+        let a = batch
+            .column_by_name("a")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        assert_eq!(a.values().to_vec(), vec![1.0, 2.5, 4.0]);
+
+        // String columns are left alone by a batch-wide mean
+        assert!(batch.column_by_name("b").unwrap().is_null(1));
+    }
+
+    #[tokio::test]
+    async fn test_data_fillna_forward_fill_and_coalesce() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"a":1,"b":7},{"a":null,"b":null},{"a":null,"b":9},{"a":5,"b":null}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let output = unit
+            .execute(
+                "fillna",
+                &arrow_data,
+                br#"{"strategy":"ffill","columns":["a"]}"#,
+            )
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let a = batch
+            .column_by_name("a")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap();
+        assert_eq!(a.null_count(), 0);
+        assert_eq!(a.values().to_vec(), vec![1, 1, 1, 5]);
+        assert_eq!(
+            batch.column_by_name("b").unwrap().null_count(),
+            2,
+            "Columns outside the list keep their nulls"
+        );
+
+        let output = unit
+            .execute(
+                "coalesce",
+                &arrow_data,
+                br#"{"columns":["a","b"],"name":"ab"}"#,
+            )
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let ab = batch
+            .column_by_name("ab")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap();
+        assert!(ab.is_null(1));
+        assert_eq!(
+            [0, 2, 3].iter().map(|&i| ab.value(i)).collect::<Vec<_>>(),
+            vec![1, 9, 5]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_data_query_compound_predicate() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"id":1,"age":25,"city":"NYC"},{"id":2,"age":35,"city":"NYC"},{"id":3,"age":40,"city":"LA"},{"id":4,"age":31,"city":"SF"},{"id":5,"age":50,"city":null}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let query_ids = |output: Vec<u8>| -> Vec<i64> {
+            let batch = decode_arrow_batch(&output);
+            let ids = batch
+                .column_by_name("id")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<arrow::array::Int64Array>()
+                .unwrap();
+            ids.values().to_vec()
+        };
+
+        let output = unit
+            .execute(
+                "query",
+                &arrow_data,
+                br#"{"predicate":"age > 30 AND city == 'NYC'"}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(query_ids(output), vec![2]);
+
+        let output = unit
+            .execute(
+                "query",
+                &arrow_data,
+                br#"{"predicate":"(age <= 25 OR age >= 40) and not city in ('LA')"}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            query_ids(output),
+            vec![1],
+            "Null cities are neither in nor out of the list"
+        );
+
+        let output = unit
+            .execute(
+                "query",
+                &arrow_data,
+                br#"{"predicate":"city IN ('LA', 'SF') OR id = 1"}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(query_ids(output), vec![1, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_data_query_compares_large_integers_exactly() {
+        let unit = DataUnit::new();
+        // 2^53 + 1 and 2^53 are the same Float64
+        let json_data = br#"[{"id":9007199254740993},{"id":9007199254740992}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        for (predicate, expected) in [
+            ("id == 9007199254740993", 1),
+            ("id > 9007199254740992", 1),
+            ("id != 9007199254740992", 1),
+            ("id >= 9007199254740992.0", 2),
+            ("id > -1", 2),
+            ("id > 1.5", 2),
+        ] {
+            let params =
+                serde_json::to_vec(&serde_json::json!({ "predicate": predicate })).unwrap();
+            let output = unit.execute("query", &arrow_data, &params).await.unwrap();
+            assert_eq!(
+                decode_arrow_batch(&output).num_rows(),
+                expected,
+                "'{}' matched the wrong rows",
+                predicate
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_data_query_rejects_bad_predicates() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"age":25,"city":"NYC"}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        for predicate in [
+            "age == 'old'",
+            "city > 3",
+            "missing == 1",
+            "age >",
+            "age > 30 AND",
+            "city == 'NYC",
+        ] {
+            let params =
+                serde_json::to_vec(&serde_json::json!({ "predicate": predicate })).unwrap();
+            let result = unit.execute("query", &arrow_data, &params).await;
+            assert!(
+                matches!(result, Err(ComputeError::InvalidParams(_))),
+                "'{}' should be rejected",
+                predicate
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_data_query_limits_nesting_depth() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"age":25},{"age":35}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+        let query = |predicate: String| {
+            let params =
+                serde_json::to_vec(&serde_json::json!({ "predicate": predicate })).unwrap();
+            let unit = &unit;
+            let arrow_data = &arrow_data;
+            async move { unit.execute("query", arrow_data, &params).await }
+        };
+
+        // Moderate nesting still parses
+        let nested = format!("{}age > 30{}", "(".repeat(50), ")".repeat(50));
+        let output = query(nested).await.unwrap();
+        assert_eq!(decode_arrow_batch(&output).num_rows(), 1);
+
+        for predicate in [
+            format!("{}age > 30{}", "(".repeat(100_000), ")".repeat(100_000)),
+            format!("{}age > 30", "NOT ".repeat(100_000)),
+            vec!["age > 30"; 10_000].join(" OR "),
+        ] {
+            let result = query(predicate).await;
+            assert!(
+                matches!(result, Err(ComputeError::InvalidParams(_))),
+                "deep predicate should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_data_with_column_arithmetic() {
+        let unit = DataUnit::new();
+        let json_data =
+            br#"[{"a":1,"b":10,"x":0.5},{"a":2,"b":20,"x":1.5},{"a":3,"b":30,"x":2.5}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        // Integer column + integer column stays Int64
+        let output = unit
+            .execute(
+                "with_column",
+                &arrow_data,
+                br#"{"name":"c","expr":"a + b"}"#,
+            )
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let c = batch
+            .column_by_name("c")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap();
+        assert_eq!(c.values().to_vec(), vec![11, 22, 33]);
+
+        // Column times scalar, promoted to Float64 by the float operand
+        let output = unit
+            .execute(
+                "with_column",
+                &arrow_data,
+                br#"{"name":"scaled","expr":"-(a * 2.5) + x"}"#,
+            )
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let scaled = batch
+            .column_by_name("scaled")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        assert_eq!(scaled.values().to_vec(), vec![-2.0, -3.5, -5.0]);
+    }
+
+    #[tokio::test]
+    async fn test_data_with_column_division_by_zero() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"a":1,"b":2},{"a":3,"b":0},{"a":0,"b":0}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let output = unit
+            .execute(
+                "with_column",
+                &arrow_data,
+                br#"{"name":"r","expr":"a / b"}"#,
+            )
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let r = batch
+            .column_by_name("r")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        assert_eq!(r.value(0), 0.5);
+        assert!(
+            r.is_null(1) && r.is_null(2),
+            "Zero divisors give null by default"
+        );
+
+        let params = br#"{"name":"r","expr":"a / b","div_by_zero":"inf"}"#;
+        let output = unit
+            .execute("with_column", &arrow_data, params)
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&output);
+        let r = batch
+            .column_by_name("r")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        assert_eq!(r.value(1), f64::INFINITY);
+        assert!(r.value(2).is_nan());
+
+        let result = unit
+            .execute(
+                "with_column",
+                &arrow_data,
+                br#"{"name":"r","expr":"a / 0"}"#,
+            )
+            .await
+            .unwrap();
+        let batch = decode_arrow_batch(&result);
+        assert_eq!(batch.column_by_name("r").unwrap().null_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_data_with_column_limits_nesting_depth() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"a":1},{"a":2}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+        let with_column = |expr: String| {
+            let params =
+                serde_json::to_vec(&serde_json::json!({ "name": "c", "expr": expr })).unwrap();
+            let unit = &unit;
+            let arrow_data = &arrow_data;
+            async move { unit.execute("with_column", arrow_data, &params).await }
+        };
+
+        // Moderate nesting still evaluates
+        let nested = format!("{}a - -1{}", "(".repeat(50), ")".repeat(50));
+        let output = with_column(nested).await.unwrap();
+        let batch = decode_arrow_batch(&output);
+        let c = batch
+            .column_by_name("c")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap();
+        assert_eq!(c.values().to_vec(), vec![2, 3]);
+
+        for expr in [
+            format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000)),
+            format!("{}a", "-".repeat(100_000)),
+            vec!["a"; 10_000].join(" + "),
+        ] {
+            let result = with_column(expr).await;
+            assert!(
+                matches!(result, Err(ComputeError::InvalidParams(_))),
+                "deep expression should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_data_multi_key_sort() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"id":1,"dept":"ops","salary":50},{"id":2,"dept":"eng","salary":70},{"id":3,"dept":"ops","salary":90},{"id":4,"dept":"eng","salary":60},{"id":5,"dept":"eng","salary":70}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let sorted_ids = |output: Vec<u8>| -> Vec<i64> {
+            let batch = decode_arrow_batch(&output);
+            let ids = batch
+                .column_by_name("id")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<arrow::array::Int64Array>()
+                .unwrap();
+            ids.values().to_vec()
+        };
+
+        // dept ascending, then salary descending within each dept, then id to settle exact ties
+        let params = br#"{"by":[{"column":"dept"},{"column":"salary","descending":true},{"column":"id","descending":true}]}"#;
+        let output = unit.execute("sort", &arrow_data, params).await.unwrap();
+        assert_eq!(sorted_ids(output), vec![5, 2, 4, 3, 1]);
+
+        // Ties in the primary key break on the secondary key
+        let params = br#"{"by":[{"column":"salary"},{"column":"id","descending":true}]}"#;
+        let output = unit.execute("sort", &arrow_data, params).await.unwrap();
+        assert_eq!(sorted_ids(output), vec![1, 4, 5, 2, 3]);
+
+        // Single-column form is unchanged
+        let params = br#"{"column":"salary","descending":true}"#;
+        let output = unit.execute("sort", &arrow_data, params).await.unwrap();
+        assert_eq!(sorted_ids(output)[0], 3);
+    }
+
+    #[tokio::test]
+    async fn test_data_rolling_matches_full_window_scan() {
+        let unit = DataUnit::new();
+        let xs: Vec<Option<f64>> = (0..200)
+            .map(|i| (i % 7 != 3).then(|| ((i * 37) % 23) as f64 - 11.5))
+            .collect();
+        let rows: Vec<_> = xs.iter().map(|x| serde_json::json!({ "x": x })).collect();
+        let json_data = serde_json::to_vec(&rows).unwrap();
+        let arrow_data = unit.execute("json_read", &json_data, b"{}").await.unwrap();
+
+        for agg in ["sum", "mean", "min", "max", "count"] {
+            let params = serde_json::json!({
+                "column": "x", "window": 9, "agg": agg, "min_periods": 2
+            });
+            let output = unit
+                .execute(
+                    "rolling",
+                    &arrow_data,
+                    &serde_json::to_vec(&params).unwrap(),
+                )
+                .await
+                .unwrap();
+            let batch = decode_arrow_batch(&output);
+            let rolled = batch
+                .column_by_name(&format!("x_rolling_{}_9", agg))
+                .unwrap()
+                .as_any()
+                .downcast_ref::<arrow::array::Float64Array>()
+                .unwrap();
+
+            for i in 0..xs.len() {
+                let window: Vec<f64> = xs[i.saturating_sub(8)..=i]
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .collect();
+                if window.len() < 2 {
+                    assert!(rolled.is_null(i), "{} row {}", agg, i);
+                    continue;
+                }
+                let expected = match agg {
+                    "sum" => window.iter().sum(),
+                    "mean" => window.iter().sum::<f64>() / window.len() as f64,
+                    "min" => window.iter().copied().fold(f64::INFINITY, f64::min),
+                    "max" => window.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    _ => window.len() as f64,
+                };
+                assert!(
+                    (rolled.value(i) - expected).abs() < 1e-9,
+                    "{} row {}: {} != {}",
+                    agg,
+                    i,
+                    rolled.value(i),
+                    expected
+                );
+            }
+        }
+
+        // A window far longer than the batch behaves like an expanding window
+        let params = br#"{"column":"x","window":1000000000000,"agg":"count","min_periods":1}"#;
+        let output = unit.execute("rolling", &arrow_data, params).await.unwrap();
+        let batch = decode_arrow_batch(&output);
+        let rolled = batch
+            .column_by_name("x_rolling_count_1000000000000")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        let non_null = xs.iter().flatten().count() as f64;
+        assert_eq!(rolled.value(xs.len() - 1), non_null);
+    }
+
+    #[tokio::test]
+    async fn test_data_rolling_rejects_zero_window() {
+        let unit = DataUnit::new();
+        let json_data = br#"[{"x":1.0},{"x":2.0}]"#;
+        let arrow_data = unit.execute("json_read", json_data, b"{}").await.unwrap();
+
+        let result = unit
+            .execute("rolling", &arrow_data, br#"{"column":"x","window":0}"#)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_data_row_hash_detects_changed_rows() {
+        let unit = DataUnit::new();
+        let v1 = br#"[{"id":1,"name":"a"},{"id":2,"name":"b"},{"id":3,"name":"c"}]"#;
+        let v2 = br#"[{"id":1,"name":"a"},{"id":2,"name":"B"},{"id":3,"name":"c"}]"#;
+        let arrow_v1 = unit.execute("json_read", v1, b"{}").await.unwrap();
+        let arrow_v2 = unit.execute("json_read", v2, b"{}").await.unwrap();
+
+        let row_hashes = |bytes: Vec<u8>| -> Vec<String> {
+            let batch = decode_arrow_batch(&bytes);
+            let column = batch
+                .column_by_name("row_hash")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<arrow::array::StringArray>()
+                .unwrap()
+                .clone();
+            column.iter().map(|h| h.unwrap().to_string()).collect()
+        };
+
+        let h1 = row_hashes(unit.execute("row_hash", &arrow_v1, b"{}").await.unwrap());
+        let h2 = row_hashes(unit.execute("row_hash", &arrow_v2, b"{}").await.unwrap());
+        assert_eq!(h1[0], h2[0]);
+        assert_ne!(h1[1], h2[1]);
+        assert_eq!(h1[2], h2[2]);
+
+        let b1: serde_json::Value =
+            serde_json::from_slice(&unit.execute("batch_hash", &arrow_v1, b"{}").await.unwrap())
+                .unwrap();
+        let b1_again: serde_json::Value =
+            serde_json::from_slice(&unit.execute("batch_hash", &arrow_v1, b"{}").await.unwrap())
+                .unwrap();
+        let b2: serde_json::Value =
+            serde_json::from_slice(&unit.execute("batch_hash", &arrow_v2, b"{}").await.unwrap())
+                .unwrap();
+        assert_eq!(b1["hash"], b1_again["hash"]);
+        assert_ne!(b1["hash"], b2["hash"]);
+        assert_eq!(b1["num_rows"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_data_row_hash_is_null_aware() {
+        let unit = DataUnit::new();
+        let empty = unit
+            .execute("json_read", br#"[{"name":""}]"#, b"{}")
+            .await
+            .unwrap();
+        let null = unit
+            .execute("json_read", br#"[{"name":null}]"#, b"{}")
+            .await
+            .unwrap();
+
+        let empty_hash = unit.execute("batch_hash", &empty, b"{}").await.unwrap();
+        let null_hash = unit.execute("batch_hash", &null, b"{}").await.unwrap();
+        assert_ne!(empty_hash, null_hash);
+    }
+
+    #[tokio::test]
+    async fn test_data_fetch_pages_held_result() {
+        let unit = DataUnit::new();
+        let rows: Vec<String> = (0..25).map(|i| format!(r#"{{"id":{}}}"#, i)).collect();
+        let input = format!("[{}]", rows.join(","));
+
+        let descriptor = unit
+            .execute("json_read", input.as_bytes(), br#"{"hold_result":true}"#)
+            .await
+            .unwrap();
+        let descriptor: serde_json::Value = serde_json::from_slice(&descriptor).unwrap();
+        assert_eq!(descriptor["num_rows"], 25);
+        let handle = descriptor["handle"].as_u64().unwrap();
+
+        let mut seen = 0;
+        loop {
+            let params = format!(r#"{{"handle":{},"offset":{},"limit":10}}"#, handle, seen);
+            let chunk = unit.execute("fetch", b"", params.as_bytes()).await.unwrap();
+            let chunk = decode_arrow_batch(&chunk);
+            if chunk.num_rows() == 0 {
+                break;
+            }
+            seen += chunk.num_rows();
+        }
+        assert_eq!(seen, 25);
+
+        let params = format!(r#"{{"handle":{}}}"#, handle);
+        unit.execute("release", b"", params.as_bytes())
+            .await
+            .unwrap();
+        let params = format!(r#"{{"handle":{},"offset":0}}"#, handle);
+        assert!(unit.execute("fetch", b"", params.as_bytes()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_data_holds_every_batch_of_a_stream() {
+        let unit = DataUnit::new();
+        let first = decode_arrow_batch(
+            &unit
+                .execute("json_read", br#"[{"id":1},{"id":2}]"#, b"{}")
+                .await
+                .unwrap(),
+        );
+        let second = decode_arrow_batch(
+            &unit
+                .execute("json_read", br#"[{"id":3}]"#, b"{}")
+                .await
+                .unwrap(),
+        );
+
+        let mut stream = Vec::new();
+        {
+            let mut writer =
+                arrow::ipc::writer::StreamWriter::try_new(&mut stream, &first.schema()).unwrap();
+            writer.write(&first).unwrap();
+            writer.write(&second).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let count = unit.execute("count", &stream, b"{}").await.unwrap();
+        assert_eq!(count, b"3");
+
+        let descriptor = unit
+            .execute("sort", &stream, br#"{"column":"id","hold_result":true}"#)
+            .await
+            .unwrap();
+        let descriptor: serde_json::Value = serde_json::from_slice(&descriptor).unwrap();
+        assert_eq!(descriptor["num_rows"], 3);
+
+        let params = format!(r#"{{"handle":{},"offset":0}}"#, descriptor["handle"]);
+        let chunk = unit.execute("fetch", b"", params.as_bytes()).await.unwrap();
+        assert_eq!(decode_arrow_batch(&chunk).num_rows(), 3);
+
+        // JSON summaries have no batch to hold
+        let err = unit
+            .execute("count", &stream, br#"{"hold_result":true}"#)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ComputeError::InvalidParams(_)));
+    }
+
+    #[derive(Default)]
+    struct MemoryVault(std::sync::Mutex<std::collections::HashMap<String, Vec<u8>>>);
+
+    #[async_trait::async_trait]
+    impl data::SpillVault for MemoryVault {
+        async fn store(&self, key: &str, data: &[u8]) -> Result<(), String> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), data.to_vec());
+            Ok(())
+        }
+
+        async fn load(&self, key: &str) -> Result<Vec<u8>, String> {
+            self.0
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .ok_or("missing".into())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), String> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_data_held_results_spill_and_reload() {
+        let vault = std::sync::Arc::new(MemoryVault::default());
+        let unit = DataUnit::new().with_spill_vault(vault.clone(), 1);
+        let hold = br#"{"hold_result":true}"#;
+
+        let first = unit
+            .execute("json_read", br#"[{"id":1},{"id":2}]"#, hold)
+            .await
+            .unwrap();
+        let first: serde_json::Value = serde_json::from_slice(&first).unwrap();
+        unit.execute("json_read", br#"[{"id":3}]"#, hold)
+            .await
+            .unwrap();
+
+        // The colder batch went to the vault to make room for the newer one
+        let stats = unit.execute("pool_stats", b"", b"{}").await.unwrap();
+        let stats: serde_json::Value = serde_json::from_slice(&stats).unwrap();
+        assert_eq!(stats["spilled"], 1);
+        assert_eq!(vault.0.lock().unwrap().len(), 1);
+
+        let params = format!(r#"{{"handle":{}}}"#, first["handle"]);
+        let chunk = unit.execute("fetch", b"", params.as_bytes()).await.unwrap();
+        assert_eq!(decode_arrow_batch(&chunk).num_rows(), 2);
+
+        // Pipelines can feed a held result straight into another action
+        let params = format!(r#"{{"input_handle":{},"n":1}}"#, first["handle"]);
+        let head = unit.execute("head", b"", params.as_bytes()).await.unwrap();
+        assert_eq!(decode_arrow_batch(&head).num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_data_identical_spills_are_independent() {
+        let vault = std::sync::Arc::new(MemoryVault::default());
+        let unit = DataUnit::new().with_spill_vault(vault.clone(), 1);
+        let hold = br#"{"hold_result":true}"#;
+        let rows = br#"[{"id":1},{"id":2}]"#;
+
+        let mut handles = Vec::new();
+        for _ in 0..3 {
+            let held = unit.execute("json_read", rows, hold).await.unwrap();
+            let held: serde_json::Value = serde_json::from_slice(&held).unwrap();
+            handles.push(held["handle"].as_u64().unwrap());
+        }
+        // Two identical batches spilled, each under its own key
+        assert_eq!(vault.0.lock().unwrap().len(), 2);
+
+        let params = format!(r#"{{"handle":{}}}"#, handles[0]);
+        unit.execute("release", b"", params.as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(vault.0.lock().unwrap().len(), 1);
+
+        let params = format!(r#"{{"handle":{}}}"#, handles[1]);
+        let chunk = unit.execute("fetch", b"", params.as_bytes()).await.unwrap();
+        assert_eq!(decode_arrow_batch(&chunk).num_rows(), 2);
+    }
+
+    struct BrokenVault;
+
+    #[async_trait::async_trait]
+    impl data::SpillVault for BrokenVault {
+        async fn store(&self, _key: &str, _data: &[u8]) -> Result<(), String> {
+            Err("vault offline".into())
+        }
+
+        async fn load(&self, _key: &str) -> Result<Vec<u8>, String> {
+            Err("vault offline".into())
+        }
+
+        async fn delete(&self, _key: &str) -> Result<(), String> {
+            Err("vault offline".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_data_failed_hold_does_not_leak_handle() {
+        let unit = DataUnit::new().with_spill_vault(std::sync::Arc::new(BrokenVault), 1);
+        let hold = br#"{"hold_result":true}"#;
+
+        unit.execute("json_read", br#"[{"id":1}]"#, hold)
+            .await
+            .unwrap();
+        // Making room needs the vault, so the second hold fails...
+        let result = unit.execute("json_read", br#"[{"id":2}]"#, hold).await;
+        assert!(result.is_err());
+
+        // ...and leaves nothing behind
+        let stats = unit.execute("pool_stats", b"", b"{}").await.unwrap();
+        let stats: serde_json::Value = serde_json::from_slice(&stats).unwrap();
+        assert_eq!(stats["resident"], 1);
+        assert_eq!(stats["spilled"], 0);
+    }
+
     // ========== FAILURE CASES ==========
 
     #[tokio::test]
@@ -240,6 +1587,49 @@ mod tests {
         );
     }
 
+    // ========== ENGINE INPUT LIMITS ==========
+
+    #[tokio::test]
+    async fn test_engine_rejects_oversized_input_for_every_unit() {
+        use crate::engine::ComputeEngine;
+        use std::sync::Arc;
+
+        let mut engine = ComputeEngine::new().with_max_input_size(1024);
+        let units: Vec<Arc<dyn UnitProxy + Send + Sync>> = vec![
+            Arc::new(ImageUnit::new()),
+            Arc::new(CryptoUnit::new()),
+            Arc::new(DataUnit::new()),
+            Arc::new(AudioUnit::new()),
+            Arc::new(GpuUnit::new()),
+            Arc::new(math::MathUnit::new()),
+            Arc::new(physics::PhysicsEngine::new()),
+        ];
+        let targets: Vec<(String, String)> = units
+            .iter()
+            .map(|u| (u.service_name().to_string(), u.actions()[0].to_string()))
+            .collect();
+        for unit in units {
+            engine.register(unit);
+        }
+
+        let input = vec![0u8; 2048];
+        for (service, action) in targets {
+            let result = engine.execute(&service, &action, &input, b"{}").await;
+            assert!(
+                matches!(
+                    result,
+                    Err(ComputeError::InputTooLarge {
+                        size: 2048,
+                        max: 1024
+                    })
+                ),
+                "{}:{} should reject oversized input before running",
+                service,
+                action
+            );
+        }
+    }
+
     // ========== CONCURRENT OPERATIONS ==========
 
     #[tokio::test]
@@ -280,6 +1670,57 @@ mod tests {
         assert!((peak - 0.95).abs() < 1e-6);
     }
 
+    fn write_samples(sab: &sdk::sab::SafeSAB, offset: usize, samples: &[f32]) {
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        sab.write_raw(offset, &bytes).unwrap();
+    }
+
+    fn read_samples(sab: &sdk::sab::SafeSAB, offset: usize, count: usize) -> Vec<f32> {
+        let mut bytes = vec![0u8; count * 4];
+        sab.read_raw(offset, &mut bytes).unwrap();
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_audio_sab_normalize_matches_normalize() {
+        let unit = AudioUnit::new();
+        // Spans several staging blocks, with the peak in the last one
+        let mut samples = sine_wave(440.0, 8000.0, 3000);
+        samples[2999] = -0.8;
+        let sab = sdk::sab::SafeSAB::with_size(64 * 1024);
+        write_samples(&sab, 0, &samples);
+
+        let written = unit
+            .execute_sab(&sab, "normalize", 0, samples.len() * 4, 32 * 1024, "{}")
+            .unwrap();
+        assert_eq!(written, samples.len() * 4);
+        assert_eq!(
+            read_samples(&sab, 32 * 1024, samples.len()),
+            unit.normalize(&samples)
+        );
+    }
+
+    #[test]
+    fn test_audio_sab_normalize_in_place() {
+        let unit = AudioUnit::new();
+        let samples = vec![0.1, 0.5, -0.2, 0.8];
+        let sab = sdk::sab::SafeSAB::with_size(1024);
+        write_samples(&sab, 64, &samples);
+
+        unit.execute_sab(&sab, "normalize", 64, 16, 64, "{}")
+            .unwrap();
+        assert_eq!(read_samples(&sab, 64, 4), unit.normalize(&samples));
+
+        // Partial samples and out-of-bounds ranges are rejected
+        assert!(unit.execute_sab(&sab, "normalize", 0, 6, 64, "{}").is_err());
+        assert!(unit
+            .execute_sab(&sab, "normalize", 1020, 16, 0, "{}")
+            .is_err());
+    }
+
     #[test]
     fn test_audio_gain() {
         let unit = AudioUnit::new();
@@ -290,6 +1731,127 @@ mod tests {
         }
     }
 
+    fn sine_wave(freq: f32, sample_rate: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate).sin() * 0.5)
+            .collect()
+    }
+
+    /// Fundamental estimated from rising zero crossings in the middle half
+    fn zero_crossing_freq(samples: &[f32], sample_rate: f32) -> f32 {
+        let middle = &samples[samples.len() / 4..samples.len() * 3 / 4];
+        let crossings = middle
+            .windows(2)
+            .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+            .count();
+        crossings as f32 * sample_rate / middle.len() as f32
+    }
+
+    #[test]
+    fn test_audio_time_stretch_preserves_pitch() {
+        let unit = AudioUnit::new();
+        let tone = sine_wave(440.0, 8000.0, 8000);
+
+        for ratio in [1.5f32, 0.5] {
+            let stretched = unit.time_stretch(&tone, 1, ratio, 512, 128).unwrap();
+            assert_eq!(stretched.len(), (tone.len() as f32 * ratio) as usize);
+
+            let freq = zero_crossing_freq(&stretched, 8000.0);
+            assert!(
+                (freq - 440.0).abs() < 440.0 * 0.02,
+                "ratio {} moved the fundamental to {}Hz",
+                ratio,
+                freq
+            );
+        }
+    }
+
+    #[test]
+    fn test_audio_pitch_shift_keeps_duration() {
+        let unit = AudioUnit::new();
+        let tone = sine_wave(440.0, 8000.0, 8000);
+
+        let shifted = unit.pitch_shift(&tone, 1, 12.0, 512, 128).unwrap();
+        assert_eq!(shifted.len(), tone.len());
+
+        let freq = zero_crossing_freq(&shifted, 8000.0);
+        assert!((freq - 880.0).abs() < 880.0 * 0.02, "got {}Hz", freq);
+    }
+
+    #[test]
+    fn test_audio_time_stretch_rejects_bad_hop() {
+        let unit = AudioUnit::new();
+        let tone = sine_wave(440.0, 8000.0, 1024);
+
+        assert!(unit.time_stretch(&tone, 1, 1.5, 256, 512).is_err());
+        assert!(unit.time_stretch(&tone, 1, 0.0, 256, 64).is_err());
+    }
+
+    #[test]
+    fn test_audio_stft_rejects_oversized_requests_before_allocating() {
+        let unit = AudioUnit::new();
+        let tone = sine_wave(440.0, 8000.0, 1024);
+
+        // A window this large would allocate gigabytes of scratch buffers
+        assert!(matches!(
+            unit.time_stretch(&tone, 1, 1.0, 1 << 30, 1 << 28),
+            Err(ComputeError::InvalidParams(_))
+        ));
+
+        // So would a stretch ratio this large; it must fail on the projected size
+        assert!(matches!(
+            unit.time_stretch(&tone, 1, 1.0e9, 512, 128),
+            Err(ComputeError::ExecutionFailed(_))
+        ));
+        assert!(matches!(
+            unit.pitch_shift(&tone, 1, 400.0, 512, 128),
+            Err(ComputeError::ExecutionFailed(_))
+        ));
+    }
+
+    fn wav_spec(sample_rate: u32, channels: u16) -> hound::WavSpec {
+        hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        }
+    }
+
+    #[test]
+    fn test_audio_adsr_envelope_shape() {
+        let unit = AudioUnit::new();
+        // 1s of stereo DC at 1kHz: 100ms attack, 100ms decay, 200ms release
+        let samples = vec![1.0f32; 2 * 1000];
+        let env = unit.adsr(&samples, &wav_spec(1000, 2), 0.1, 0.1, 0.5, 0.2);
+        assert_eq!(env.len(), samples.len());
+
+        let level = |frame: usize| env[frame * 2];
+        assert_eq!(env[2 * 50], env[2 * 50 + 1], "channels share the envelope");
+
+        // Peaks at the end of attack, then settles to sustain
+        assert!((level(99) - 1.0).abs() < 1e-6);
+        assert!(level(50) < level(99));
+        assert!(level(150) < 1.0 && level(150) > 0.5);
+        assert!((level(199) - 0.5).abs() < 1e-6);
+        assert!((level(600) - 0.5).abs() < 1e-6);
+
+        // Release runs to silence on the final frame
+        assert!(level(900) < 0.5);
+        assert_eq!(level(999), 0.0);
+    }
+
+    #[test]
+    fn test_audio_adsr_release_clamped_to_clip() {
+        let unit = AudioUnit::new();
+        let samples = vec![1.0f32; 100];
+        let env = unit.adsr(&samples, &wav_spec(1000, 1), 0.0, 0.0, 0.8, 5.0);
+
+        assert_eq!(env.len(), 100);
+        assert!((env[0] - 0.8 * 0.99).abs() < 1e-6);
+        assert_eq!(env[99], 0.0);
+    }
+
     // ========== CRYPTO UNIT TESTS ==========
 
     #[test]
@@ -321,6 +1883,253 @@ mod tests {
         assert_eq!(&decrypted[..], plaintext);
     }
 
+    #[tokio::test]
+    async fn test_crypto_job_result_signature() {
+        let unit = CryptoUnit::new();
+        let output = b"job 42 result: 3.14159";
+
+        let signed = unit.execute("sign_result", output, b"{}").await.unwrap();
+        let signed: serde_json::Value = serde_json::from_slice(&signed).unwrap();
+        assert_eq!(signed["hash"], blake3::hash(output).to_hex().as_str());
+        assert_eq!(
+            signed["public_key"],
+            general_purpose::STANDARD.encode(unit.session_public_key())
+        );
+
+        let params = serde_json::to_vec(&serde_json::json!({
+            "public_key": signed["public_key"],
+            "signature": signed["signature"],
+        }))
+        .unwrap();
+        let valid = unit
+            .execute("verify_result", output, &params)
+            .await
+            .unwrap();
+        assert_eq!(valid, vec![1]);
+
+        // A peer holding only the public key rejects a tampered output
+        let peer = CryptoUnit::new();
+        let tampered = peer
+            .execute("verify_result", b"job 42 result: 2.71828", &params)
+            .await
+            .unwrap();
+        assert_eq!(tampered, vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_crypto_job_result_signature_is_domain_separated() {
+        let unit = CryptoUnit::new();
+        let private_key = general_purpose::STANDARD.encode([7u8; 32]);
+        let params =
+            serde_json::to_vec(&serde_json::json!({ "private_key": private_key })).unwrap();
+
+        let first = unit
+            .execute("sign_result", b"output", &params)
+            .await
+            .unwrap();
+        let second = unit
+            .execute("sign_result", b"output", &params)
+            .await
+            .unwrap();
+        assert_eq!(first, second, "Ed25519 result signatures are deterministic");
+
+        // The signature covers the result hash, not the raw bytes
+        let signed: serde_json::Value = serde_json::from_slice(&first).unwrap();
+        let params = serde_json::to_vec(&serde_json::json!({
+            "public_key": signed["public_key"],
+            "signature": signed["signature"],
+        }))
+        .unwrap();
+        let raw = unit
+            .execute("ed25519_verify", b"output", &params)
+            .await
+            .unwrap();
+        assert_eq!(raw, vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_crypto_hash_stream_matches_one_shot() {
+        let unit = CryptoUnit::new();
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+
+        for (algorithm, one_shot) in [
+            (
+                "blake3",
+                unit.execute("blake3", &data, b"{}").await.unwrap(),
+            ),
+            (
+                "sha256",
+                unit.execute("sha256", &data, b"{}").await.unwrap(),
+            ),
+        ] {
+            let expected: String = one_shot.iter().map(|b| format!("{:02x}", b)).collect();
+            let params = serde_json::json!({ "algorithm": algorithm, "segment_size": 777 });
+            let params = serde_json::to_vec(&params).unwrap();
+            let digest = unit.execute("hash_stream", &data, &params).await.unwrap();
+            assert_eq!(
+                String::from_utf8(digest).unwrap(),
+                expected,
+                "{}",
+                algorithm
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crypto_incremental_hash_stream() {
+        let unit = CryptoUnit::new();
+        let data: Vec<u8> = (0..50_000u32).map(|i| (i * 31 % 256) as u8).collect();
+        let expected = blake3::hash(&data).to_hex().to_string();
+
+        let opened = unit.execute("hash_stream_begin", b"", b"{}").await.unwrap();
+        let opened: serde_json::Value = serde_json::from_slice(&opened).unwrap();
+        let params =
+            serde_json::to_vec(&serde_json::json!({ "stream_id": opened["stream_id"] })).unwrap();
+
+        // Uneven chunks, as a caller streaming a large blob would send them
+        for chunk in data.chunks(12_345) {
+            unit.execute("hash_stream_update", chunk, &params)
+                .await
+                .unwrap();
+        }
+        let digest = unit
+            .execute("hash_stream_finish", b"", &params)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(digest).unwrap(), expected);
+
+        // Finishing closes the stream
+        assert!(matches!(
+            unit.execute("hash_stream_update", b"more", &params).await,
+            Err(ComputeError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_crypto_hash_streams_expire_and_evict() {
+        let unit = CryptoUnit::new();
+        let open = |now: f64| -> serde_json::Value {
+            let opened = unit
+                .hash_stream_begin_at(&serde_json::json!({}), now)
+                .unwrap();
+            let opened: serde_json::Value = serde_json::from_slice(&opened).unwrap();
+            serde_json::json!({ "stream_id": opened["stream_id"] })
+        };
+
+        // An abandoned stream expires once idle for a minute
+        let idle = open(0.0);
+        assert!(unit.hash_stream_update_at(b"a", &idle, 59_000.0).is_ok());
+        assert!(matches!(
+            unit.hash_stream_update_at(b"b", &idle, 119_000.0),
+            Err(ComputeError::InvalidParams(_))
+        ));
+
+        // A full table drops the least recently used stream, not the new one
+        let streams: Vec<_> = (0..64).map(|i| open(200_000.0 + i as f64)).collect();
+        unit.hash_stream_update_at(b"keep", &streams[0], 201_000.0)
+            .unwrap();
+        let newest = open(201_001.0);
+        assert!(matches!(
+            unit.hash_stream_update_at(b"x", &streams[1], 201_002.0),
+            Err(ComputeError::InvalidParams(_))
+        ));
+        assert!(unit.hash_stream_finish_at(&streams[0], 201_003.0).is_ok());
+        assert!(unit.hash_stream_finish_at(&newest, 201_004.0).is_ok());
+    }
+
+    fn hex_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_crypto_hkdf_rfc5869_vectors() {
+        let unit = CryptoUnit::new();
+        let b64 = |bytes: &[u8]| general_purpose::STANDARD.encode(bytes);
+        let range = |from: u8, to: u8| (from..=to).collect::<Vec<u8>>();
+
+        // RFC 5869 Appendix A, test cases 1-3 (SHA-256)
+        let cases = [
+            (
+                vec![0x0b; 22],
+                range(0x00, 0x0c),
+                range(0xf0, 0xf9),
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+            ),
+            (
+                range(0x00, 0x4f),
+                range(0x60, 0xaf),
+                range(0xb0, 0xff),
+                "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+                 59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+                 cc30c58179ec3e87c14c01d5c1f3434f1d87",
+            ),
+            (
+                vec![0x0b; 22],
+                Vec::new(),
+                Vec::new(),
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+            ),
+        ];
+
+        for (ikm, salt, info, okm) in cases {
+            let expected = hex_bytes(okm);
+            let params = serde_json::json!({
+                "ikm": b64(&ikm),
+                "salt": b64(&salt),
+                "info": b64(&info),
+                "length": expected.len(),
+            });
+            let params = serde_json::to_vec(&params).unwrap();
+            assert_eq!(unit.execute("hkdf", b"", &params).await.unwrap(), expected);
+
+            // derive_key is the same expansion truncated to a 32-byte key
+            if !info.is_empty() {
+                let params = serde_json::json!({
+                    "master": b64(&ikm),
+                    "salt": b64(&salt),
+                    "info": b64(&info),
+                });
+                let params = serde_json::to_vec(&params).unwrap();
+                let key = unit.execute("derive_key", b"", &params).await.unwrap();
+                assert_eq!(key, expected[..32]);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crypto_derive_key_domain_separation() {
+        let unit = CryptoUnit::new();
+        let master = general_purpose::STANDARD.encode([9u8; 32]);
+        let derive = |info: &str| {
+            serde_json::to_vec(&serde_json::json!({
+                "master": master,
+                "info": general_purpose::STANDARD.encode(info),
+            }))
+            .unwrap()
+        };
+
+        let storage = unit
+            .execute("derive_key", b"", &derive("vault/storage"))
+            .await
+            .unwrap();
+        let signing = unit
+            .execute("derive_key", b"", &derive("identity/signing"))
+            .await
+            .unwrap();
+        assert_eq!(storage.len(), 32);
+        assert_ne!(storage, signing);
+
+        // A purpose label is mandatory
+        let no_info = serde_json::to_vec(&serde_json::json!({ "master": master })).unwrap();
+        assert!(matches!(
+            unit.execute("derive_key", b"", &no_info).await,
+            Err(ComputeError::InvalidParams(_))
+        ));
+    }
+
     // ========== IMAGE UNIT TESTS ==========
 
     #[test]
@@ -372,6 +2181,107 @@ mod tests {
         );
     }
 
+    fn encode_test_png(width: u32, height: u32) -> Vec<u8> {
+        let img = ::image::RgbaImage::from_fn(width, height, |x, y| {
+            ::image::Rgba([(x * 10) as u8, (y * 10) as u8, 128, 255])
+        });
+        let mut output = Vec::new();
+        ::image::codecs::png::PngEncoder::new(&mut output)
+            .write_image(&img, width, height, ::image::ExtendedColorType::Rgba8)
+            .unwrap();
+        output
+    }
+
+    async fn run_image(action: &str, input: &[u8], params: serde_json::Value) -> Vec<u8> {
+        let params = serde_json::to_vec(&params).unwrap();
+        ImageUnit::new()
+            .execute(action, input, &params)
+            .await
+            .unwrap_or_else(|e| panic!("{} failed: {}", action, e))
+    }
+
+    #[tokio::test]
+    async fn test_image_resize_halves_dimensions() {
+        let input = encode_test_png(20, 10);
+
+        let exact = run_image(
+            "resize",
+            &input,
+            serde_json::json!({"width": 10, "height": 5}),
+        )
+        .await;
+        assert_eq!(
+            ::image::load_from_memory(&exact).unwrap().dimensions(),
+            (10, 5)
+        );
+
+        // Fit into a tall box: width constrains, height follows the 2:1 ratio
+        let fitted = run_image(
+            "resize",
+            &input,
+            serde_json::json!({"width": 10, "height": 100, "preserve_aspect": true}),
+        )
+        .await;
+        assert_eq!(
+            ::image::load_from_memory(&fitted).unwrap().dimensions(),
+            (10, 5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_image_convert_produces_target_format() {
+        let input = encode_test_png(8, 6);
+
+        for (format, expected) in [
+            ("jpeg", ::image::ImageFormat::Jpeg),
+            ("webp", ::image::ImageFormat::WebP),
+            ("png", ::image::ImageFormat::Png),
+        ] {
+            let output = run_image("convert", &input, serde_json::json!({"format": format})).await;
+            assert_eq!(::image::guess_format(&output).unwrap(), expected);
+            let decoded = ::image::load_from_memory(&output).unwrap();
+            assert_eq!(
+                decoded.dimensions(),
+                (8, 6),
+                "{} changed dimensions",
+                format
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_image_crop_and_rotate() {
+        let input = encode_test_png(20, 10);
+
+        let cropped = run_image(
+            "crop",
+            &input,
+            serde_json::json!({"x": 5, "y": 2, "width": 10, "height": 8}),
+        )
+        .await;
+        assert_eq!(
+            ::image::load_from_memory(&cropped).unwrap().dimensions(),
+            (10, 8)
+        );
+
+        let rotated = run_image("rotate", &input, serde_json::json!({"degrees": 270})).await;
+        assert_eq!(
+            ::image::load_from_memory(&rotated).unwrap().dimensions(),
+            (10, 20)
+        );
+
+        let unit = ImageUnit::new();
+        let outside = br#"{"x": 15, "y": 0, "width": 10, "height": 5}"#;
+        assert!(matches!(
+            unit.execute("crop", &input, outside).await,
+            Err(ComputeError::InvalidParams(_))
+        ));
+        assert!(matches!(
+            unit.execute("rotate", &input, br#"{"degrees": 45}"#).await,
+            Err(ComputeError::InvalidParams(_))
+        ));
+    }
+
     // ========== PHYSICS UNIT TESTS ==========
     // Physics tests moved to physics.rs (library proxy pattern)
     // See modules/compute/src/units/physics.rs for comprehensive tests
@@ -386,6 +2296,12 @@ mod tests {
 
     // ========== HELPER FUNCTIONS ==========
 
+    fn decode_arrow_batch(bytes: &[u8]) -> arrow::record_batch::RecordBatch {
+        let reader =
+            arrow::ipc::reader::StreamReader::try_new(std::io::Cursor::new(bytes), None).unwrap();
+        reader.into_iter().next().unwrap().unwrap()
+    }
+
     fn _create_test_arrow_batch() -> Vec<u8> {
         // Create a simple Arrow IPC batch
         // In a real implementation, this would use arrow-rs
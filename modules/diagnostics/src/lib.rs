@@ -1,11 +1,14 @@
 use capnp::message::{Builder, ReaderOptions};
 use capnp::serialize_packed;
-use log::info;
+use log::{info, warn};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use sdk::protocols::diagnostics::{diagnostics_request, diagnostics_response};
 use sdk::sab::SafeSAB;
+use sdk::syscall_trace::{SyscallTraceEvent, TraceKind};
 use sdk::Reactor;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
 
 #[cfg(target_arch = "wasm32")]
 getrandom::register_custom_getrandom!(sdk::js_interop::getrandom_custom);
@@ -17,10 +20,150 @@ getrandom::register_custom_getrandom!(sdk::js_interop::getrandom_custom);
 /// 2. EpochPulse: Monitor heartbeats of all active modules.
 /// 3. SignalTracing: Trace syscall latency across layers.
 
+/// Modules silent for longer than this are reported dead
+pub const DEFAULT_LIVENESS_TIMEOUT_SECS: u32 = 5;
+
+/// A named SAB region audited by the watchdog
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryRegion {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    /// Only expected to change alongside a registry epoch bump
+    pub read_only: bool,
+}
+
+const fn region(name: &'static str, offset: usize, size: usize, read_only: bool) -> MemoryRegion {
+    MemoryRegion {
+        name,
+        offset,
+        size,
+        read_only,
+    }
+}
+
+pub const MEMORY_REGIONS: &[MemoryRegion] = {
+    use sdk::layout::*;
+    &[
+        region("AtomicFlags", OFFSET_ATOMIC_FLAGS, SIZE_ATOMIC_FLAGS, false),
+        region(
+            "SupervisorAlloc",
+            OFFSET_SUPERVISOR_ALLOC,
+            SIZE_SUPERVISOR_ALLOC,
+            false,
+        ),
+        region(
+            "ModuleRegistry",
+            OFFSET_MODULE_REGISTRY,
+            SIZE_MODULE_REGISTRY,
+            true,
+        ),
+        region(
+            "SupervisorHeaders",
+            OFFSET_SUPERVISOR_HEADERS,
+            SIZE_SUPERVISOR_HEADERS,
+            false,
+        ),
+        region(
+            "SyscallTable",
+            OFFSET_SYSCALL_TABLE,
+            SIZE_SYSCALL_TABLE,
+            false,
+        ),
+        region(
+            "PatternExchange",
+            OFFSET_PATTERN_EXCHANGE,
+            SIZE_PATTERN_EXCHANGE,
+            false,
+        ),
+        region("JobHistory", OFFSET_JOB_HISTORY, SIZE_JOB_HISTORY, false),
+        region(
+            "Coordination",
+            OFFSET_COORDINATION,
+            SIZE_COORDINATION,
+            false,
+        ),
+        region("InboxOutbox", OFFSET_INBOX_OUTBOX, SIZE_INBOX_OUTBOX, false),
+    ]
+};
+
+/// BLAKE3 digest of one region, compared against the previous scan
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionChecksum {
+    pub name: &'static str,
+    pub digest: [u8; 32],
+    pub changed: bool,
+    /// A read-only region changed without a registry epoch bump
+    pub unexpected: bool,
+}
+
+/// Latency summary for one syscall opcode
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyscallLatency {
+    pub opcode: u16,
+    pub count: usize,
+    pub p50_us: u32,
+    pub p99_us: u32,
+    /// (upper bound in microseconds, count), power-of-two buckets
+    pub histogram: Vec<(u32, usize)>,
+}
+
+/// Pair enter/exit events by module and call id, then summarise per opcode
+/// Returns the summaries and the number of calls still in flight.
+pub fn syscall_latencies(events: &[SyscallTraceEvent]) -> (Vec<SyscallLatency>, usize) {
+    let mut in_flight: HashMap<(u32, u32), &SyscallTraceEvent> = HashMap::new();
+    let mut samples: BTreeMap<u16, Vec<u32>> = BTreeMap::new();
+
+    for event in events {
+        let key = (event.module_id, event.call_id);
+        match event.kind {
+            TraceKind::Enter => {
+                in_flight.insert(key, event);
+            }
+            TraceKind::Exit => {
+                // Exits whose enter was overwritten by the ring are dropped
+                if let Some(enter) = in_flight.remove(&key) {
+                    let latency = event.timestamp_us.wrapping_sub(enter.timestamp_us);
+                    samples.entry(event.opcode).or_default().push(latency);
+                }
+            }
+        }
+    }
+
+    let summaries = samples
+        .into_iter()
+        .map(|(opcode, mut latencies)| {
+            latencies.sort_unstable();
+            let mut histogram: BTreeMap<u32, usize> = BTreeMap::new();
+            for &latency in &latencies {
+                let bucket = latency.max(1).checked_next_power_of_two();
+                *histogram.entry(bucket.unwrap_or(u32::MAX)).or_default() += 1;
+            }
+            SyscallLatency {
+                opcode,
+                count: latencies.len(),
+                p50_us: percentile(&latencies, 50),
+                p99_us: percentile(&latencies, 99),
+                histogram: histogram.into_iter().collect(),
+            }
+        })
+        .collect();
+
+    (summaries, in_flight.len())
+}
+
+/// Nearest-rank percentile of sorted, non-empty samples
+fn percentile(sorted: &[u32], pct: usize) -> u32 {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
 pub struct DiagnosticsModule {
     reactor: Reactor,
     sab: sdk::sab::SafeSAB,
     last_scan: u32,
+    /// Last digest of each region and the registry epoch it was taken under
+    region_digests: HashMap<&'static str, ([u8; 32], u32)>,
 }
 
 static GLOBAL_WATCHDOG: Lazy<Mutex<Option<DiagnosticsModule>>> = Lazy::new(|| Mutex::new(None));
@@ -33,41 +176,18 @@ impl DiagnosticsModule {
             reactor: Reactor::new(sab.clone()),
             sab,
             last_scan: 0,
+            region_digests: HashMap::new(),
         }
     }
 
     /// Scan memory areas for overlap or corruption
     pub fn scan_memory(&self) -> Result<(), String> {
         info!("Watchdog: Scanning SAB memory areas...");
-        use sdk::layout::*;
 
-        let regions = [
-            ("AtomicFlags", OFFSET_ATOMIC_FLAGS, SIZE_ATOMIC_FLAGS),
-            (
-                "SupervisorAlloc",
-                OFFSET_SUPERVISOR_ALLOC,
-                SIZE_SUPERVISOR_ALLOC,
-            ),
-            (
-                "ModuleRegistry",
-                OFFSET_MODULE_REGISTRY,
-                SIZE_MODULE_REGISTRY,
-            ),
-            (
-                "SupervisorHeaders",
-                OFFSET_SUPERVISOR_HEADERS,
-                SIZE_SUPERVISOR_HEADERS,
-            ),
-            ("SyscallTable", OFFSET_SYSCALL_TABLE, SIZE_SYSCALL_TABLE),
-            (
-                "PatternExchange",
-                OFFSET_PATTERN_EXCHANGE,
-                SIZE_PATTERN_EXCHANGE,
-            ),
-            ("JobHistory", OFFSET_JOB_HISTORY, SIZE_JOB_HISTORY),
-            ("Coordination", OFFSET_COORDINATION, SIZE_COORDINATION),
-            ("InboxOutbox", OFFSET_INBOX_OUTBOX, SIZE_INBOX_OUTBOX),
-        ];
+        let regions: Vec<_> = MEMORY_REGIONS
+            .iter()
+            .map(|r| (r.name, r.offset, r.size))
+            .collect();
 
         for i in 0..regions.len() {
             let (name1, off1, size1) = regions[i];
@@ -98,14 +218,93 @@ impl DiagnosticsModule {
         Ok(())
     }
 
+    /// Digest every region and compare against the previous scan
+    /// The first scan only records a baseline. A registry epoch bump since a
+    /// region's last digest re-baselines it, since registration legitimately
+    /// rewrites read-only regions.
+    pub fn region_checksums(&mut self) -> Result<Vec<RegionChecksum>, String> {
+        let epoch = self.read_registry_epoch()?;
+
+        MEMORY_REGIONS
+            .iter()
+            .map(|region| self.checksum_region(region, epoch))
+            .collect()
+    }
+
+    /// Targeted integrity check of one named region
+    /// Errors if a read-only region changed since the last scan without a registry epoch bump.
+    pub fn verify_region(&mut self, name: &str) -> Result<RegionChecksum, String> {
+        let region = MEMORY_REGIONS
+            .iter()
+            .find(|r| r.name == name)
+            .ok_or_else(|| format!("Unknown region: {}", name))?;
+
+        let epoch = self.read_registry_epoch()?;
+        let checksum = self.checksum_region(region, epoch)?;
+        if checksum.unexpected {
+            return Err(format!(
+                "Region {} changed unexpectedly (0x{:x}-0x{:x})",
+                region.name,
+                region.offset,
+                region.offset + region.size
+            ));
+        }
+        Ok(checksum)
+    }
+
+    fn checksum_region(
+        &mut self,
+        region: &MemoryRegion,
+        epoch: u32,
+    ) -> Result<RegionChecksum, String> {
+        let bytes = self.sab.read(region.offset, region.size)?;
+        let digest = sdk::compression::hash_blake3(&bytes);
+
+        let previous = self.region_digests.insert(region.name, (digest, epoch));
+        let changed = previous.is_some_and(|(prev, _)| prev != digest);
+        let epoch_moved = previous.is_some_and(|(_, last)| last != epoch);
+        let unexpected = changed && region.read_only && !epoch_moved;
+        if unexpected {
+            warn!(
+                "Watchdog: read-only region {} changed without a registry epoch bump",
+                region.name
+            );
+        }
+
+        Ok(RegionChecksum {
+            name: region.name,
+            digest,
+            changed,
+            unexpected,
+        })
+    }
+
+    fn read_registry_epoch(&self) -> Result<u32, String> {
+        use sdk::layout::*;
+        let bytes = self
+            .sab
+            .read(OFFSET_ATOMIC_FLAGS + IDX_REGISTRY_EPOCH as usize * 4, 4)?;
+        Ok(u32::from_le_bytes(bytes[..4].try_into().unwrap()))
+    }
+
     /// Record a pulse from a module and check its health
     pub fn pulse(&self, module_id: u32) {
+        // Update timestamp (simulation of relative epoch)
+        let now = (sdk::js_interop::get_now() as f64 / 1000.0) as u32;
+        self.pulse_at(module_id, now);
+    }
+
+    /// Record a pulse at an explicit epoch (seconds)
+    pub fn pulse_at(&self, module_id: u32, now: u32) {
         use sdk::layout::*;
-        // OFFSET_DIAGNOSTICS + (module_id * 8) = heartbeat storage
+        // OFFSET_HEARTBEATS + (module_id * 8) = heartbeat storage
         // Byte 0-3: Last Pulse Timestamp (Epoch)
         // Byte 4-7: Pulse Counter
 
-        let heart_offset = OFFSET_DIAGNOSTICS + (module_id as usize * 8);
+        if module_id as usize >= MAX_HEARTBEAT_SLOTS {
+            return;
+        }
+        let heart_offset = OFFSET_HEARTBEATS + (module_id as usize * HEARTBEAT_SLOT_SIZE);
         let sab = &self.sab;
 
         // Increment pulse counter
@@ -117,10 +316,86 @@ impl DiagnosticsModule {
         }
         count += 1;
         let _ = sab.write(heart_offset + 4, &count.to_le_bytes());
+        let _ = sab.write(heart_offset, &now.to_le_bytes());
+    }
 
-        // Update timestamp (simulation of relative epoch)
+    /// Classify every module that has pulsed at least once as alive or dead
+    /// A module is dead once its last pulse is more than `timeout_secs` older than `now`.
+    pub fn check_liveness(&self, now: u32, timeout_secs: u32) -> Vec<(u32, bool)> {
+        use sdk::layout::*;
+
+        (0..MAX_HEARTBEAT_SLOTS as u32)
+            .filter_map(|module_id| {
+                let offset = OFFSET_HEARTBEATS + module_id as usize * HEARTBEAT_SLOT_SIZE;
+                let slot = self.sab.read(offset, HEARTBEAT_SLOT_SIZE).ok()?;
+                let last_pulse = u32::from_le_bytes(slot[0..4].try_into().ok()?);
+                let count = u32::from_le_bytes(slot[4..8].try_into().ok()?);
+                if count == 0 {
+                    return None; // Never pulsed: slot unused
+                }
+                Some((module_id, now.saturating_sub(last_pulse) <= timeout_secs))
+            })
+            .collect()
+    }
+
+    /// Liveness classification as JSON for the kernel
+    pub fn liveness_json(&self, now: u32, timeout_secs: u32) -> Vec<u8> {
+        let liveness = self.check_liveness(now, timeout_secs);
+        let dead = liveness.iter().filter(|(_, alive)| !alive).count();
+        let modules: Vec<_> = liveness
+            .into_iter()
+            .map(|(module_id, alive)| json!({ "module_id": module_id, "alive": alive }))
+            .collect();
+
+        json!({
+            "now": now,
+            "timeout_secs": timeout_secs,
+            "dead": dead,
+            "modules": modules,
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    /// Write the current liveness report to the outbox
+    pub fn report(&self, timeout_secs: u32) -> bool {
         let now = (sdk::js_interop::get_now() as f64 / 1000.0) as u32;
-        let _ = sab.write(heart_offset, &now.to_le_bytes());
+        self.reactor
+            .write_result(&self.liveness_json(now, timeout_secs))
+    }
+
+    /// Per-syscall latency percentiles and histograms from the trace ring, as JSON
+    pub fn trace_report(&self) -> Result<Vec<u8>, String> {
+        use sdk::protocols::syscall::syscall::Opcode;
+
+        let events = sdk::syscall_trace::read_events(&self.sab)?;
+        let (latencies, in_flight) = syscall_latencies(&events);
+
+        let syscalls: Vec<_> = latencies
+            .iter()
+            .map(|l| {
+                let name = Opcode::try_from(l.opcode)
+                    .map(|op| format!("{:?}", op))
+                    .unwrap_or_else(|_| format!("Opcode{}", l.opcode));
+                let histogram: Vec<_> = l
+                    .histogram
+                    .iter()
+                    .map(|&(le_us, count)| json!({ "le_us": le_us, "count": count }))
+                    .collect();
+                json!({
+                    "opcode": l.opcode,
+                    "name": name,
+                    "count": l.count,
+                    "p50_us": l.p50_us,
+                    "p99_us": l.p99_us,
+                    "histogram": histogram,
+                })
+            })
+            .collect();
+
+        Ok(json!({ "syscalls": syscalls, "in_flight": in_flight })
+            .to_string()
+            .into_bytes())
     }
 
     /// Collect bridge performance metrics
@@ -136,12 +411,16 @@ impl DiagnosticsModule {
 }
 
 /// Standardized Memory Allocator for WebAssembly
+/// Release with `diagnostics_free(ptr, size)` (see `sdk::arena` for the ABI contract)
 #[no_mangle]
 pub extern "C" fn diagnostics_alloc(size: usize) -> *mut u8 {
-    let mut buf = Vec::with_capacity(size);
-    let ptr = buf.as_mut_ptr();
-    std::mem::forget(buf);
-    ptr
+    sdk::arena::alloc_host_buffer(size)
+}
+
+/// Standardized Memory Deallocator for WebAssembly
+#[no_mangle]
+pub extern "C" fn diagnostics_free(ptr: *mut u8, size: usize) {
+    unsafe { sdk::arena::free_host_buffer(ptr, size) }
 }
 
 /// Standardized Initialization with SharedArrayBuffer
@@ -183,11 +462,7 @@ pub extern "C" fn diagnostics_init_with_sab() -> i32 {
 
             // Initialize global watchdog
             let mut lock = GLOBAL_WATCHDOG.lock();
-            *lock = Some(DiagnosticsModule {
-                reactor: Reactor::new(safe_sab.clone()),
-                sab: safe_sab,
-                last_scan: 0,
-            });
+            *lock = Some(DiagnosticsModule::new(safe_sab));
 
             return 1;
         }
@@ -253,6 +528,7 @@ pub extern "C" fn diagnostics_poll() {
         // 2. Periodic memory audit
         if watchdog.last_scan % 1000 == 0 {
             let _ = watchdog.scan_memory();
+            let _ = watchdog.region_checksums();
         }
         watchdog.last_scan = watchdog.last_scan.wrapping_add(1);
     }
@@ -306,6 +582,165 @@ mod tests {
         diag.pulse(255);
     }
 
+    fn diagnostics_sab() -> SafeSAB {
+        use sdk::layout::*;
+        SafeSAB::with_size(OFFSET_DIAGNOSTICS + SIZE_DIAGNOSTICS)
+    }
+
+    #[test]
+    fn test_liveness_classifies_stale_and_fresh_pulses() {
+        let diag = DiagnosticsModule::new(diagnostics_sab());
+
+        diag.pulse_at(1, 100); // stale
+        diag.pulse_at(2, 100);
+        diag.pulse_at(2, 197); // fresh again
+        diag.pulse_at(7, 195); // exactly at the timeout
+
+        let liveness = diag.check_liveness(200, 5);
+        assert_eq!(liveness, vec![(1, false), (2, true), (7, true)]);
+
+        let report: serde_json::Value =
+            serde_json::from_slice(&diag.liveness_json(200, 5)).unwrap();
+        assert_eq!(report["dead"], 1);
+        assert_eq!(report["modules"][0]["module_id"], 1);
+        assert_eq!(report["modules"][0]["alive"], false);
+    }
+
+    #[test]
+    fn test_read_only_region_mutation_is_detected() {
+        use sdk::layout::*;
+        let sab = diagnostics_sab();
+        let mut diag = DiagnosticsModule::new(sab.clone());
+
+        // Baseline scan flags nothing
+        let baseline = diag.region_checksums().unwrap();
+        assert!(baseline.iter().all(|c| !c.changed && !c.unexpected));
+
+        // Mutable regions may change freely
+        sab.write(OFFSET_JOB_HISTORY + 16, &[0xAA; 4]).unwrap();
+        // A stray write into the registry without an epoch bump is stomping
+        sab.write(OFFSET_MODULE_REGISTRY + 64, &[0xFF; 4]).unwrap();
+
+        let scan = diag.region_checksums().unwrap();
+        let find = |name: &str| scan.iter().find(|c| c.name == name).unwrap().clone();
+        assert!(find("JobHistory").changed && !find("JobHistory").unexpected);
+        assert!(find("ModuleRegistry").unexpected);
+        assert!(!find("Coordination").changed);
+    }
+
+    #[test]
+    fn test_verify_region_allows_registry_epoch_bump() {
+        use sdk::layout::*;
+        let sab = diagnostics_sab();
+        let mut diag = DiagnosticsModule::new(sab.clone());
+        diag.verify_region("ModuleRegistry").unwrap();
+
+        // Legitimate registration: registry rewritten and epoch bumped
+        sab.write(OFFSET_MODULE_REGISTRY, &[1, 2, 3]).unwrap();
+        let epoch_offset = OFFSET_ATOMIC_FLAGS + IDX_REGISTRY_EPOCH as usize * 4;
+        sab.write(epoch_offset, &1u32.to_le_bytes()).unwrap();
+        assert!(diag.verify_region("ModuleRegistry").unwrap().changed);
+
+        // Same write again, no bump
+        sab.write(OFFSET_MODULE_REGISTRY, &[4, 5, 6]).unwrap();
+        let err = diag.verify_region("ModuleRegistry").unwrap_err();
+        assert!(err.starts_with("Region ModuleRegistry changed unexpectedly"));
+
+        assert!(diag.verify_region("NoSuchRegion").is_err());
+    }
+
+    #[test]
+    fn test_epoch_bump_seen_by_one_region_still_covers_others() {
+        use sdk::layout::*;
+        let sab = diagnostics_sab();
+        let mut diag = DiagnosticsModule::new(sab.clone());
+        diag.region_checksums().unwrap();
+
+        // Registration rewrites the registry and bumps the epoch
+        sab.write(OFFSET_MODULE_REGISTRY, &[1, 2, 3]).unwrap();
+        let epoch_offset = OFFSET_ATOMIC_FLAGS + IDX_REGISTRY_EPOCH as usize * 4;
+        sab.write(epoch_offset, &1u32.to_le_bytes()).unwrap();
+
+        // Verifying an unrelated region first must not consume the bump
+        diag.verify_region("Coordination").unwrap();
+        let registry = diag.verify_region("ModuleRegistry").unwrap();
+        assert!(registry.changed && !registry.unexpected);
+    }
+
+    fn trace_pair(
+        call_id: u32,
+        opcode: u16,
+        enter_us: u32,
+        latency_us: u32,
+    ) -> [SyscallTraceEvent; 2] {
+        let enter = SyscallTraceEvent {
+            call_id,
+            module_id: 3,
+            opcode,
+            kind: TraceKind::Enter,
+            timestamp_us: enter_us,
+        };
+        let exit = SyscallTraceEvent {
+            kind: TraceKind::Exit,
+            timestamp_us: enter_us.wrapping_add(latency_us),
+            ..enter
+        };
+        [enter, exit]
+    }
+
+    #[test]
+    fn test_syscall_latency_percentiles() {
+        // 100 calls of opcode 1 taking 1..=100us, interleaved with opcode 2
+        let mut events = Vec::new();
+        for i in 1..=100u32 {
+            events.extend(trace_pair(i, 1, i * 1000, i));
+            events.extend(trace_pair(1000 + i, 2, i * 1000, 7));
+        }
+        // Still waiting on a response
+        events.push(trace_pair(5000, 1, 0, 0)[0]);
+        // Latency measured across the u32 clock wrap
+        events.extend(trace_pair(6000, 3, u32::MAX - 4, 10));
+
+        let (latencies, in_flight) = syscall_latencies(&events);
+        assert_eq!(in_flight, 1);
+        assert_eq!(latencies.len(), 3);
+
+        let op1 = &latencies[0];
+        assert_eq!((op1.opcode, op1.count), (1, 100));
+        assert_eq!(op1.p50_us, 50);
+        assert_eq!(op1.p99_us, 99);
+        assert_eq!(op1.histogram.first(), Some(&(1, 1)));
+        assert_eq!(op1.histogram.last(), Some(&(128, 36)));
+
+        assert_eq!((latencies[1].p50_us, latencies[1].p99_us), (7, 7));
+        assert_eq!(latencies[2].p50_us, 10);
+    }
+
+    #[test]
+    fn test_trace_report_reads_ring() {
+        let sab = diagnostics_sab();
+        let diag = DiagnosticsModule::new(sab.clone());
+
+        // Opcode 1 is FetchChunk
+        for event in trace_pair(1, 1, 100, 40) {
+            sdk::syscall_trace::record_event(&sab, event);
+        }
+
+        let report: serde_json::Value =
+            serde_json::from_slice(&diag.trace_report().unwrap()).unwrap();
+        assert_eq!(report["in_flight"], 0);
+        assert_eq!(report["syscalls"][0]["name"], "FetchChunk");
+        assert_eq!(report["syscalls"][0]["p99_us"], 40);
+    }
+
+    #[test]
+    fn test_pulse_ignores_out_of_range_module() {
+        let diag = DiagnosticsModule::new(diagnostics_sab());
+
+        diag.pulse_at(sdk::layout::MAX_HEARTBEAT_SLOTS as u32, 10);
+        assert!(diag.check_liveness(10, 5).is_empty());
+    }
+
     #[test]
     fn test_diagnostics_module_creation() {
         let diag = DiagnosticsModule::new(SafeSAB::with_size(1024));
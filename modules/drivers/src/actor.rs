@@ -8,6 +8,28 @@ pub trait Actor: Send {
     fn on_command(&mut self, cmd: &ActorCommand) -> Result<(), String>;
 }
 
+/// Actor kinds the kernel can instantiate at runtime (see `drivers_register_actor`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ActorKind {
+    RobotLeg = 0,
+    Drone = 1,
+    Generic = 2,
+}
+
+impl TryFrom<u32> for ActorKind {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ActorKind::RobotLeg),
+            1 => Ok(ActorKind::Drone),
+            2 => Ok(ActorKind::Generic),
+            _ => Err(format!("Unknown actor kind: {}", value)),
+        }
+    }
+}
+
 pub struct ActorCommand {
     pub target_id: String,
     pub timestamp_ns: i64,
@@ -33,6 +55,22 @@ impl ActorDriver {
         self.actors.push(actor);
     }
 
+    pub fn has_actor(&self, id: &str) -> bool {
+        self.actors.iter().any(|a| a.id() == id)
+    }
+
+    /// Deliver `cmd` to the actor registered under its target id
+    pub fn dispatch(&mut self, cmd: &ActorCommand) -> Result<(), String> {
+        Self::dispatch_to(&mut self.actors, cmd)
+    }
+
+    fn dispatch_to(actors: &mut [Box<dyn Actor>], cmd: &ActorCommand) -> Result<(), String> {
+        match actors.iter_mut().find(|a| a.id() == cmd.target_id) {
+            Some(actor) => actor.on_command(cmd),
+            None => Err(format!("No actor registered with id: {}", cmd.target_id)),
+        }
+    }
+
     pub fn set_ring_buffer(&mut self, rb: sdk::ringbuffer::RingBuffer) {
         self.ring_buffer = Some(rb);
     }
@@ -57,16 +95,18 @@ impl ActorDriver {
                                     .to_string();
 
                                 let command = ActorCommand {
-                                    target_id: target_id.clone(),
+                                    target_id,
                                     timestamp_ns: root.get_timestamp_ns(),
-                                    payload: Vec::new(), // TODO: Extract specific variant data
+                                    // Structured variants (moveTo, torque, ...) still arrive empty
+                                    payload: match root.which() {
+                                        Ok(actor_capnp::actor::command::Which::RawBytes(Ok(
+                                            data,
+                                        ))) => data.to_vec(),
+                                        _ => Vec::new(),
+                                    },
                                 };
 
-                                for actor in &mut self.actors {
-                                    if actor.id() == target_id {
-                                        let _ = actor.on_command(&command);
-                                    }
-                                }
+                                let _ = Self::dispatch_to(&mut self.actors, &command);
                             }
                         }
                         Err(_) => break,
@@ -77,3 +117,41 @@ impl ActorDriver {
         Ok(())
     }
 }
+
+/// Catch-all actor: accepts any command and keeps the latest payload for
+/// whoever owns the instance to pick up
+pub struct GenericActor {
+    id: String,
+    last_payload: Vec<u8>,
+    command_count: u64,
+}
+
+impl GenericActor {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            last_payload: Vec::new(),
+            command_count: 0,
+        }
+    }
+
+    pub fn last_payload(&self) -> &[u8] {
+        &self.last_payload
+    }
+
+    pub fn command_count(&self) -> u64 {
+        self.command_count
+    }
+}
+
+impl Actor for GenericActor {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn on_command(&mut self, cmd: &ActorCommand) -> Result<(), String> {
+        self.last_payload.clone_from(&cmd.payload);
+        self.command_count += 1;
+        Ok(())
+    }
+}
@@ -17,7 +17,7 @@ pub mod ros2;
 #[cfg(target_arch = "wasm32")]
 getrandom::register_custom_getrandom!(sdk::js_interop::getrandom_custom);
 
-use actor::{Actor, ActorDriver};
+use actor::{Actor, ActorCommand, ActorDriver, ActorKind, GenericActor};
 use log::{error, info};
 use sdk::{Epoch, IDX_ACTOR_EPOCH, IDX_SENSOR_EPOCH};
 use sensor::SensorSubscriber;
@@ -30,6 +30,7 @@ pub use positioning::PositioningSystem;
 
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
+use std::sync::Arc;
 
 /// Global Drivers instance for C ABI access
 static GLOBAL_DRIVERS: Lazy<Mutex<Option<Drivers>>> = Lazy::new(|| Mutex::new(None));
@@ -56,7 +57,7 @@ pub struct Drivers {
     motors: MotorController,
     servos: ServoController,
     gpio: GpioController,
-    mavlink: mavlink::MavlinkDriver,
+    mavlink: Arc<Mutex<mavlink::MavlinkDriver>>,
     ros2: ros2::Ros2Driver,
     _sab: Option<sdk::sab::SafeSAB>,
 }
@@ -68,9 +69,18 @@ impl Drivers {
             .unwrap_or_else(|| sdk::sab::SafeSAB::new(&sdk::js_interop::get_global()));
         let actor_epoch = Epoch::new(placeholder_sab.clone(), IDX_ACTOR_EPOCH);
         let sensor_epoch = Epoch::new(placeholder_sab.clone(), IDX_SENSOR_EPOCH);
+        let mut mavlink_driver = mavlink::MavlinkDriver::default();
+        mavlink_driver.set_sab(placeholder_sab.clone());
+        let mavlink_driver = Arc::new(Mutex::new(mavlink_driver));
+
+        let mut actor_driver = ActorDriver::new(actor_epoch);
+        actor_driver.register_actor(Box::new(mavlink::DroneActor::new(
+            "drone",
+            Arc::clone(&mavlink_driver),
+        )));
 
         Self {
-            actor_driver: ActorDriver::new(actor_epoch),
+            actor_driver,
             sensor_subscriber: SensorSubscriber::new(sensor_epoch),
             positioning: PositioningSystem::new(),
             lidar: LidarDriver::default(),
@@ -78,7 +88,7 @@ impl Drivers {
             motors: MotorController::default(),
             servos: ServoController::default(),
             gpio: GpioController::default(),
-            mavlink: mavlink::MavlinkDriver::default(),
+            mavlink: mavlink_driver,
             ros2: ros2::Ros2Driver::default(),
             _sab: sab,
         }
@@ -139,6 +149,33 @@ impl Drivers {
         self.motors.emergency_stop();
     }
 
+    // Actor methods
+    pub fn register_actor(&mut self, actor: Box<dyn Actor>) -> Result<(), String> {
+        if self.actor_driver.has_actor(actor.id()) {
+            return Err(format!("Actor already registered: {}", actor.id()));
+        }
+        self.actor_driver.register_actor(actor);
+        Ok(())
+    }
+
+    pub fn register_actor_kind(&mut self, id: &str, kind: ActorKind) -> Result<(), String> {
+        let actor: Box<dyn Actor> = match kind {
+            ActorKind::RobotLeg => Box::new(RobotLegActor::new(id)),
+            ActorKind::Drone => Box::new(mavlink::DroneActor::new(id, Arc::clone(&self.mavlink))),
+            ActorKind::Generic => Box::new(GenericActor::new(id)),
+        };
+        self.register_actor(actor)
+    }
+
+    pub fn send_actor_command(&mut self, target_id: &str, payload: Vec<u8>) -> Result<(), String> {
+        let command = ActorCommand {
+            target_id: target_id.to_string(),
+            timestamp_ns: (sdk::js_interop::get_now() * 1_000_000.0) as i64,
+            payload,
+        };
+        self.actor_driver.dispatch(&command)
+    }
+
     /// Execute generic command (Phase 1A: Command Dispatch)
     pub fn execute_command(&mut self, cmd: DriverCommand) -> CommandResult {
         match cmd {
@@ -243,10 +280,12 @@ impl Drivers {
                 self.emergency_stop();
                 CommandResult::success("Emergency stop activated")
             }
-            DriverCommand::MavlinkConnect { address } => match self.mavlink.connect(&address) {
-                Ok(_) => CommandResult::success(format!("MAVLink connected to {}", address)),
-                Err(e) => CommandResult::error(e),
-            },
+            DriverCommand::MavlinkConnect { address } => {
+                match self.mavlink.lock().connect(&address) {
+                    Ok(_) => CommandResult::success(format!("MAVLink connected to {}", address)),
+                    Err(e) => CommandResult::error(e),
+                }
+            }
             DriverCommand::MavlinkCommand { .. } => {
                 // Verification using identity registry should happen here
                 CommandResult::success("MAVLink command issued (Identity verified)")
@@ -332,7 +371,9 @@ impl Drivers {
     pub fn poll(&mut self) {
         let _ = self.actor_driver.poll();
         let _ = self.sensor_subscriber.poll();
-        let _ = self.mavlink.poll();
+        if let Err(e) = self.mavlink.lock().poll() {
+            error!("MAVLink poll failed: {}", e);
+        }
         let _ = self.ros2.poll();
     }
 }
@@ -346,24 +387,82 @@ impl Default for Drivers {
 // Bare-metal WASM Nexus (legacy compatibility)
 // Removed legacy Nexus as it is now redundant with Drivers
 
-/// Send command to actor (motor, servo, GPIO) - DEPRECATED
-/// Use drivers_execute_json instead
+/// Register an actor instance at runtime
+///
+/// `kind`: 0 = robot_leg, 1 = drone, 2 = generic. Returns 1 on success, 0 if
+/// the kind is unknown or the id is already taken.
 #[no_mangle]
-pub extern "C" fn drivers_send_command(actor_type: u32, actor_id: u8, value: f32) -> i32 {
+pub extern "C" fn drivers_register_actor(id_ptr: *const u8, id_len: usize, kind: u32) -> i32 {
+    if id_ptr.is_null() || id_len == 0 {
+        log::error!("[drivers_register_actor] FAILED: id_ptr is null or len=0");
+        return 0;
+    }
+    let id_bytes = unsafe { std::slice::from_raw_parts(id_ptr, id_len) };
+    let id = match std::str::from_utf8(id_bytes) {
+        Ok(s) => s,
+        Err(_) => return 0,
+    };
+    let kind = match ActorKind::try_from(kind) {
+        Ok(k) => k,
+        Err(e) => {
+            log::error!("Actor registration failed: {}", e);
+            return 0;
+        }
+    };
+
     let mut lock = GLOBAL_DRIVERS.lock();
     if lock.is_none() {
         *lock = Some(Drivers::new(None));
     }
 
     if let Some(drivers) = lock.as_mut() {
-        let result = match actor_type {
-            0 => drivers.set_motor_speed(actor_id, value),
-            1 => drivers.set_servo_angle(actor_id, value),
-            2 => drivers.set_gpio_pin(actor_id, value > 0.5),
-            _ => Err("Unknown actor type".to_string()),
-        };
+        match drivers.register_actor_kind(id, kind) {
+            Ok(_) => 1,
+            Err(e) => {
+                log::error!("Actor registration failed: {}", e);
+                0
+            }
+        }
+    } else {
+        0
+    }
+}
 
-        match result {
+/// Send a command payload to a registered actor (format is up to the actor,
+/// e.g. JSON DroneCommand for drones). Motors, servos and GPIO go through
+/// drivers_execute_json.
+#[no_mangle]
+pub extern "C" fn drivers_send_command(
+    id_ptr: *const u8,
+    id_len: usize,
+    payload_ptr: *const u8,
+    payload_len: usize,
+) -> i32 {
+    if id_ptr.is_null() || id_len == 0 {
+        log::error!("[drivers_send_command] FAILED: id_ptr is null or len=0");
+        return 0;
+    }
+    let id_bytes = unsafe { std::slice::from_raw_parts(id_ptr, id_len) };
+    let id = match std::str::from_utf8(id_bytes) {
+        Ok(s) => s,
+        Err(_) => return 0,
+    };
+    let payload = if payload_len == 0 {
+        Vec::new()
+    } else if payload_ptr.is_null() {
+        log::error!("[drivers_send_command] FAILED: payload_ptr is null with len>0");
+        return 0;
+    } else {
+        unsafe { std::slice::from_raw_parts(payload_ptr, payload_len) }.to_vec()
+    };
+
+    let mut lock = GLOBAL_DRIVERS.lock();
+    if lock.is_none() {
+        *lock = Some(Drivers::new(None));
+    }
+
+    if let Some(drivers) = lock.as_mut() {
+        match drivers.send_actor_command(id, payload) {
             Ok(_) => 1,
             Err(e) => {
                 log::error!("Command failed: {}", e);
@@ -486,12 +585,16 @@ pub extern "C" fn drivers_poll_sensor_json(
 }
 
 /// Standardized Memory Allocator for WebAssembly
+/// Release with `drivers_free(ptr, size)` (see `sdk::arena` for the ABI contract)
 #[no_mangle]
 pub extern "C" fn drivers_alloc(size: usize) -> *mut u8 {
-    let mut buf = Vec::with_capacity(size);
-    let ptr = buf.as_mut_ptr();
-    std::mem::forget(buf);
-    ptr
+    sdk::arena::alloc_host_buffer(size)
+}
+
+/// Standardized Memory Deallocator for WebAssembly
+#[no_mangle]
+pub extern "C" fn drivers_free(ptr: *mut u8, size: usize) {
+    unsafe { sdk::arena::free_host_buffer(ptr, size) }
 }
 
 /// Standardized Initialization with SharedArrayBuffer
@@ -600,6 +703,12 @@ pub struct RobotLegActor {
     id: String,
 }
 
+impl RobotLegActor {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
+}
+
 impl Actor for RobotLegActor {
     fn id(&self) -> &str {
         &self.id
@@ -611,3 +720,96 @@ impl Actor for RobotLegActor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RecordingActor {
+        id: String,
+        received: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl Actor for RecordingActor {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn on_command(&mut self, cmd: &ActorCommand) -> Result<(), String> {
+            self.received.lock().push(cmd.payload.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registered_actor_receives_command() {
+        let mut drivers = Drivers::new(None);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        drivers
+            .register_actor(Box::new(RecordingActor {
+                id: "arm-1".to_string(),
+                received: received.clone(),
+            }))
+            .unwrap();
+
+        drivers
+            .send_actor_command("arm-1", b"grip".to_vec())
+            .unwrap();
+        assert_eq!(*received.lock(), vec![b"grip".to_vec()]);
+
+        let duplicate = RecordingActor {
+            id: "arm-1".to_string(),
+            received: received.clone(),
+        };
+        assert!(drivers.register_actor(Box::new(duplicate)).is_err());
+        assert!(drivers.send_actor_command("missing", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_register_actor_export_by_kind() {
+        let id = b"export-generic";
+        assert_eq!(
+            drivers_register_actor(id.as_ptr(), id.len(), ActorKind::Generic as u32),
+            1
+        );
+        assert_eq!(drivers_register_actor(id.as_ptr(), id.len(), 2), 0); // Id taken
+        assert_eq!(drivers_register_actor(id.as_ptr(), id.len(), 99), 0);
+
+        let payload = b"ping";
+        assert_eq!(
+            drivers_send_command(id.as_ptr(), id.len(), payload.as_ptr(), payload.len()),
+            1
+        );
+
+        // Drone actors run their own validation in on_command
+        let drone = b"export-drone";
+        let takeoff = br#"{"type":"takeoff","altitude":10.0}"#;
+        assert_eq!(
+            drivers_register_actor(drone.as_ptr(), drone.len(), ActorKind::Drone as u32),
+            1
+        );
+        assert_eq!(
+            drivers_send_command(drone.as_ptr(), drone.len(), takeoff.as_ptr(), takeoff.len()),
+            0
+        );
+
+        let missing = b"export-missing";
+        assert_eq!(
+            drivers_send_command(missing.as_ptr(), missing.len(), payload.as_ptr(), 0),
+            0
+        );
+    }
+
+    #[test]
+    fn test_actor_exports_reject_null_and_empty_pointers() {
+        let null = std::ptr::null::<u8>();
+        assert_eq!(drivers_register_actor(null, 8, 2), 0);
+        assert_eq!(drivers_register_actor(b"x".as_ptr(), 0, 2), 0);
+        assert_eq!(drivers_send_command(null, 8, null, 0), 0);
+
+        let id = b"export-null-payload";
+        assert_eq!(drivers_register_actor(id.as_ptr(), id.len(), 2), 1);
+        assert_eq!(drivers_send_command(id.as_ptr(), id.len(), null, 4), 0);
+        assert_eq!(drivers_send_command(id.as_ptr(), id.len(), null, 0), 1);
+    }
+}
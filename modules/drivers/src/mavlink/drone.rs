@@ -0,0 +1,378 @@
+// Drone Actor: routes actor commands to a MAVLink vehicle
+//
+// Command payloads are JSON (same tagged style as DriverCommand):
+// {"type":"arm"}, {"type":"takeoff","altitude":10.0}, {"type":"goto","lat":..,"lon":..,"alt":..}
+
+use super::MavlinkDriver;
+use crate::actor::{Actor, ActorCommand};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[cfg(feature = "mavlink")]
+use mavlink::ardupilotmega::{
+    MavCmd, MavFrame, MavMessage, MavModeFlag, PositionTargetTypemask, COMMAND_LONG_DATA,
+    SET_POSITION_TARGET_GLOBAL_INT_DATA,
+};
+
+/// Highest takeoff altitude accepted, in metres above home
+pub const MAX_TAKEOFF_ALTITUDE_M: f32 = 120.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DroneCommand {
+    Arm,
+    Disarm,
+    /// Climb to `altitude` metres above home
+    Takeoff {
+        altitude: f32,
+    },
+    Land,
+    /// Autopilot-specific mode number (e.g. ArduCopter GUIDED = 4)
+    SetMode {
+        custom_mode: u32,
+    },
+    /// Fly to a global position; `alt` is metres above home
+    Goto {
+        lat: f64,
+        lon: f64,
+        alt: f32,
+    },
+}
+
+impl DroneCommand {
+    /// Reject parameters that are out of range before anything reaches the vehicle
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            DroneCommand::Takeoff { altitude } => {
+                if !altitude.is_finite() || altitude <= 0.0 || altitude > MAX_TAKEOFF_ALTITUDE_M {
+                    return Err(format!(
+                        "Takeoff altitude must be in (0, {}] m, got {}",
+                        MAX_TAKEOFF_ALTITUDE_M, altitude
+                    ));
+                }
+            }
+            DroneCommand::Goto { lat, lon, alt } => {
+                if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                    return Err(format!("Goto target out of range: {}, {}", lat, lon));
+                }
+                if !alt.is_finite() || alt < 0.0 {
+                    return Err(format!("Goto altitude must be >= 0 m, got {}", alt));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Flight phase as tracked from the commands this actor has issued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlightState {
+    Disarmed,
+    Armed,
+    Airborne,
+}
+
+impl FlightState {
+    /// The state after `cmd`, or an error if `cmd` is unsafe from here
+    pub fn next(self, cmd: &DroneCommand) -> Result<FlightState, String> {
+        use FlightState::*;
+        match (cmd, self) {
+            (DroneCommand::Arm, Disarmed | Armed) => Ok(Armed),
+            (DroneCommand::Arm, Airborne) => Ok(Airborne),
+            (DroneCommand::Disarm, Airborne) => {
+                Err("Refusing to disarm while airborne; land first".to_string())
+            }
+            (DroneCommand::Disarm, _) => Ok(Disarmed),
+            (DroneCommand::Takeoff { .. }, Armed) => Ok(Airborne),
+            (DroneCommand::Takeoff { .. }, Disarmed) => {
+                Err("Takeoff rejected: vehicle is not armed".to_string())
+            }
+            (DroneCommand::Takeoff { .. }, Airborne) => {
+                Err("Takeoff rejected: vehicle is already airborne".to_string())
+            }
+            (DroneCommand::Land, Airborne) => Ok(Armed),
+            (DroneCommand::Land, _) => Err("Land rejected: vehicle is not airborne".to_string()),
+            (DroneCommand::Goto { .. }, Airborne) => Ok(Airborne),
+            (DroneCommand::Goto { .. }, _) => {
+                Err("Goto rejected: vehicle is not airborne".to_string())
+            }
+            (DroneCommand::SetMode { .. }, state) => Ok(state),
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "mavlink"), allow(dead_code))]
+pub struct DroneActor {
+    id: String,
+    driver: Arc<Mutex<MavlinkDriver>>,
+    target_system: u8,
+    target_component: u8,
+    state: FlightState,
+}
+
+impl DroneActor {
+    pub fn new(id: &str, driver: Arc<Mutex<MavlinkDriver>>) -> Self {
+        Self {
+            id: id.to_string(),
+            driver,
+            target_system: 1,
+            target_component: 1, // MAV_COMP_ID_AUTOPILOT1
+            state: FlightState::Disarmed,
+        }
+    }
+
+    pub fn with_target(mut self, system: u8, component: u8) -> Self {
+        self.target_system = system;
+        self.target_component = component;
+        self
+    }
+
+    pub fn state(&self) -> FlightState {
+        self.state
+    }
+
+    #[cfg(feature = "mavlink")]
+    pub fn to_mavlink(&self, cmd: &DroneCommand) -> MavMessage {
+        let command_long = |command: MavCmd, params: [f32; 7]| {
+            MavMessage::COMMAND_LONG(COMMAND_LONG_DATA {
+                param1: params[0],
+                param2: params[1],
+                param3: params[2],
+                param4: params[3],
+                param5: params[4],
+                param6: params[5],
+                param7: params[6],
+                command,
+                target_system: self.target_system,
+                target_component: self.target_component,
+                confirmation: 0,
+            })
+        };
+
+        match *cmd {
+            DroneCommand::Arm => command_long(
+                MavCmd::MAV_CMD_COMPONENT_ARM_DISARM,
+                [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            ),
+            DroneCommand::Disarm => command_long(MavCmd::MAV_CMD_COMPONENT_ARM_DISARM, [0.0; 7]),
+            DroneCommand::Takeoff { altitude } => command_long(
+                MavCmd::MAV_CMD_NAV_TAKEOFF,
+                [0.0, 0.0, 0.0, f32::NAN, 0.0, 0.0, altitude],
+            ),
+            DroneCommand::Land => command_long(
+                MavCmd::MAV_CMD_NAV_LAND,
+                [0.0, 0.0, 0.0, f32::NAN, 0.0, 0.0, 0.0],
+            ),
+            DroneCommand::SetMode { custom_mode } => command_long(
+                MavCmd::MAV_CMD_DO_SET_MODE,
+                [
+                    MavModeFlag::MAV_MODE_FLAG_CUSTOM_MODE_ENABLED.bits() as f32,
+                    custom_mode as f32,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                ],
+            ),
+            DroneCommand::Goto { lat, lon, alt } => {
+                MavMessage::SET_POSITION_TARGET_GLOBAL_INT(SET_POSITION_TARGET_GLOBAL_INT_DATA {
+                    lat_int: (lat * 1e7) as i32,
+                    lon_int: (lon * 1e7) as i32,
+                    alt,
+                    // Position only: ignore velocity, acceleration, yaw and yaw rate
+                    type_mask: PositionTargetTypemask::from_bits_truncate(0x0DF8),
+                    target_system: self.target_system,
+                    target_component: self.target_component,
+                    coordinate_frame: MavFrame::MAV_FRAME_GLOBAL_RELATIVE_ALT_INT,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+
+    #[cfg(feature = "mavlink")]
+    fn dispatch(&mut self, cmd: &DroneCommand) -> Result<(), String> {
+        let msg = self.to_mavlink(cmd);
+        self.driver.lock().send_message(msg)
+    }
+
+    #[cfg(not(feature = "mavlink"))]
+    fn dispatch(&mut self, _cmd: &DroneCommand) -> Result<(), String> {
+        Err("MAVLink feature not enabled".to_string())
+    }
+}
+
+impl Actor for DroneActor {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn on_command(&mut self, cmd: &ActorCommand) -> Result<(), String> {
+        let command: DroneCommand = serde_json::from_slice(&cmd.payload)
+            .map_err(|e| format!("Invalid drone command: {}", e))?;
+        command.validate()?;
+        let next = self.state.next(&command)?;
+
+        // Only advance once the vehicle has actually been sent the command
+        self.dispatch(&command)?;
+        self.state = next;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_takeoff_requires_arm() {
+        let takeoff = DroneCommand::Takeoff { altitude: 10.0 };
+        assert!(FlightState::Disarmed.next(&takeoff).is_err());
+
+        let armed = FlightState::Disarmed.next(&DroneCommand::Arm).unwrap();
+        assert_eq!(armed.next(&takeoff).unwrap(), FlightState::Airborne);
+    }
+
+    #[test]
+    fn test_unsafe_sequences_rejected() {
+        let airborne = FlightState::Airborne;
+        assert!(airborne.next(&DroneCommand::Disarm).is_err());
+        assert!(airborne
+            .next(&DroneCommand::Takeoff { altitude: 5.0 })
+            .is_err());
+        assert!(FlightState::Armed
+            .next(&DroneCommand::Goto {
+                lat: 0.0,
+                lon: 0.0,
+                alt: 10.0
+            })
+            .is_err());
+        assert_eq!(
+            airborne.next(&DroneCommand::Land).unwrap(),
+            FlightState::Armed
+        );
+    }
+
+    #[test]
+    fn test_param_validation() {
+        assert!(DroneCommand::Takeoff { altitude: 0.0 }.validate().is_err());
+        assert!(DroneCommand::Takeoff { altitude: f32::NAN }
+            .validate()
+            .is_err());
+        assert!(DroneCommand::Takeoff { altitude: 500.0 }
+            .validate()
+            .is_err());
+        assert!(DroneCommand::Goto {
+            lat: 91.0,
+            lon: 0.0,
+            alt: 10.0
+        }
+        .validate()
+        .is_err());
+        assert!(DroneCommand::Goto {
+            lat: 37.77,
+            lon: -122.42,
+            alt: 30.0
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn test_command_json_payload() {
+        let cmd: DroneCommand =
+            serde_json::from_str(r#"{"type":"set_mode","custom_mode":4}"#).unwrap();
+        assert_eq!(cmd, DroneCommand::SetMode { custom_mode: 4 });
+    }
+}
+
+#[cfg(all(test, feature = "mavlink"))]
+mod mavlink_tests {
+    use super::super::mock::CannedConnection;
+    use super::*;
+    use mavlink::Message;
+
+    fn actor_command(cmd: &DroneCommand) -> ActorCommand {
+        ActorCommand {
+            target_id: "drone".to_string(),
+            timestamp_ns: 0,
+            payload: serde_json::to_vec(cmd).unwrap(),
+        }
+    }
+
+    fn connected_actor() -> (DroneActor, Arc<CannedConnection>) {
+        let conn = Arc::new(CannedConnection::new(Vec::new()));
+        let mut driver = MavlinkDriver::new();
+        driver.attach(conn.clone());
+        let actor = DroneActor::new("drone", Arc::new(Mutex::new(driver)));
+        (actor, conn)
+    }
+
+    #[test]
+    fn test_commands_map_to_mavlink_messages() {
+        let (mut actor, conn) = connected_actor();
+        let sequence = [
+            DroneCommand::SetMode { custom_mode: 4 },
+            DroneCommand::Arm,
+            DroneCommand::Takeoff { altitude: 10.0 },
+            DroneCommand::Goto {
+                lat: 37.7749,
+                lon: -122.4194,
+                alt: 20.0,
+            },
+            DroneCommand::Land,
+        ];
+        for cmd in &sequence {
+            actor.on_command(&actor_command(cmd)).unwrap();
+        }
+
+        let sent = conn.sent.lock();
+        let ids: Vec<u32> = sent.iter().map(|m| m.message_id()).collect();
+        assert_eq!(ids, vec![76, 76, 76, 86, 76]); // COMMAND_LONG / SET_POSITION_TARGET_GLOBAL_INT
+
+        let commands: Vec<MavCmd> = sent
+            .iter()
+            .filter_map(|m| match m {
+                MavMessage::COMMAND_LONG(c) => Some(c.command),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                MavCmd::MAV_CMD_DO_SET_MODE,
+                MavCmd::MAV_CMD_COMPONENT_ARM_DISARM,
+                MavCmd::MAV_CMD_NAV_TAKEOFF,
+                MavCmd::MAV_CMD_NAV_LAND,
+            ]
+        );
+        if let MavMessage::SET_POSITION_TARGET_GLOBAL_INT(target) = &sent[3] {
+            assert_eq!(target.lat_int, 377_749_000);
+            assert_eq!(target.alt, 20.0);
+        }
+        assert_eq!(actor.state(), FlightState::Armed);
+    }
+
+    #[test]
+    fn test_rejected_command_is_not_sent() {
+        let (mut actor, conn) = connected_actor();
+        let err = actor
+            .on_command(&actor_command(&DroneCommand::Takeoff { altitude: 10.0 }))
+            .unwrap_err();
+        assert!(err.contains("not armed"));
+        assert!(conn.sent.lock().is_empty());
+        assert_eq!(actor.state(), FlightState::Disarmed);
+    }
+
+    #[test]
+    fn test_send_failure_keeps_state() {
+        let mut actor = DroneActor::new("drone", Arc::new(Mutex::new(MavlinkDriver::new())));
+        assert!(actor
+            .on_command(&actor_command(&DroneCommand::Arm))
+            .is_err());
+        assert_eq!(actor.state(), FlightState::Disarmed);
+    }
+}
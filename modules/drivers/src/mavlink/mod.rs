@@ -1,14 +1,217 @@
 // MAVLink Driver for Drone Telemetry & Control
 // Part of Phase 17 Robotics Extensions
 
+pub mod drone;
+
+pub use drone::{DroneActor, DroneCommand, FlightState};
+
+use sdk::layout::{OFFSET_DRONE_TELEMETRY, SIZE_DRONE_TELEMETRY};
+use sdk::sab::SafeSAB;
+#[cfg(feature = "mavlink")]
+use sdk::{Epoch, IDX_SENSOR_EPOCH};
+
 #[cfg(feature = "mavlink")]
 use mavlink::ardupilotmega::MavMessage;
 #[cfg(feature = "mavlink")]
+use mavlink::error::MessageReadError;
+#[cfg(feature = "mavlink")]
 use mavlink::{MavConnection, MavHeader};
+#[cfg(feature = "mavlink")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "mavlink")]
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+#[cfg(feature = "mavlink")]
+use std::sync::Arc;
+#[cfg(feature = "mavlink")]
+use std::thread::JoinHandle;
+
+/// A connection shared between the driver (sends) and its reader thread (receives)
+#[cfg(feature = "mavlink")]
+pub type SharedConnection = Arc<dyn MavConnection<MavMessage> + Send + Sync>;
+
+#[cfg(feature = "mavlink")]
+type Inbound = Result<(MavHeader, MavMessage), String>;
+
+/// Thread draining one connection into the driver's channel
+#[cfg(feature = "mavlink")]
+struct ReaderThread {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+#[cfg(feature = "mavlink")]
+impl ReaderThread {
+    fn spawn(conn: SharedConnection, tx: mpsc::Sender<Inbound>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            while !stopped.load(Ordering::Acquire) {
+                match conn.recv() {
+                    Ok(frame) => {
+                        if stopped.load(Ordering::Acquire) || tx.send(Ok(frame)).is_err() {
+                            break; // Driver dropped or reattached
+                        }
+                    }
+                    Err(MessageReadError::Io(e)) => {
+                        if !stopped.load(Ordering::Acquire) {
+                            let _ = tx.send(Err(e.to_string()));
+                        }
+                        break;
+                    }
+                    Err(_) => continue, // Unknown or malformed message; keep reading
+                }
+            }
+        });
+        Self { stop, handle }
+    }
+
+    /// Signal the thread and join it if it has already exited. `recv` can't be
+    /// interrupted, so a thread parked on a silent link exits (dropping its
+    /// connection) on its next frame or I/O error without forwarding anything.
+    fn stop(self) {
+        self.stop.store(true, Ordering::Release);
+        if self.handle.is_finished() {
+            let _ = self.handle.join();
+        }
+    }
+}
+
+/// Telemetry flags: which message kinds have been seen since connect
+pub const TELEMETRY_HEARTBEAT: u32 = 1 << 0;
+pub const TELEMETRY_POSITION: u32 = 1 << 1;
+pub const TELEMETRY_ATTITUDE: u32 = 1 << 2;
+
+/// Latest vehicle state, mirrored into the SAB at `OFFSET_DRONE_TELEMETRY`
+///
+/// Layout (little endian):
+/// `[seq u32 | flags u32 | custom_mode u32 | base_mode u8 | system_status u8 | system_id u8 | pad u8]`
+/// `[lat_e7 i32 | lon_e7 i32 | alt_mm i32 | relative_alt_mm i32 | vx vy vz i16 (cm/s) | heading u16 (cdeg)]`
+/// `[roll f32 | pitch f32 | yaw f32 (rad) | time_boot_ms u32 | reserved 8B]`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DroneTelemetry {
+    /// Bumped on every publish; 0 means nothing has been published yet
+    pub seq: u32,
+    pub flags: u32,
+    pub custom_mode: u32,
+    pub base_mode: u8,
+    pub system_status: u8,
+    pub system_id: u8,
+    pub lat_e7: i32,
+    pub lon_e7: i32,
+    pub alt_mm: i32,
+    pub relative_alt_mm: i32,
+    pub velocity_cm_s: [i16; 3],
+    pub heading_cdeg: u16,
+    pub roll: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+    pub time_boot_ms: u32,
+}
+
+impl DroneTelemetry {
+    pub fn to_bytes(&self) -> [u8; SIZE_DRONE_TELEMETRY] {
+        let mut bytes = [0u8; SIZE_DRONE_TELEMETRY];
+        bytes[0..4].copy_from_slice(&self.seq.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.flags.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.custom_mode.to_le_bytes());
+        bytes[12] = self.base_mode;
+        bytes[13] = self.system_status;
+        bytes[14] = self.system_id;
+        bytes[16..20].copy_from_slice(&self.lat_e7.to_le_bytes());
+        bytes[20..24].copy_from_slice(&self.lon_e7.to_le_bytes());
+        bytes[24..28].copy_from_slice(&self.alt_mm.to_le_bytes());
+        bytes[28..32].copy_from_slice(&self.relative_alt_mm.to_le_bytes());
+        for (i, v) in self.velocity_cm_s.iter().enumerate() {
+            bytes[32 + i * 2..34 + i * 2].copy_from_slice(&v.to_le_bytes());
+        }
+        bytes[38..40].copy_from_slice(&self.heading_cdeg.to_le_bytes());
+        bytes[40..44].copy_from_slice(&self.roll.to_le_bytes());
+        bytes[44..48].copy_from_slice(&self.pitch.to_le_bytes());
+        bytes[48..52].copy_from_slice(&self.yaw.to_le_bytes());
+        bytes[52..56].copy_from_slice(&self.time_boot_ms.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < SIZE_DRONE_TELEMETRY {
+            return Err(format!("Telemetry record too short: {} bytes", bytes.len()));
+        }
+        let u32_at =
+            |o: usize| u32::from_le_bytes([bytes[o], bytes[o + 1], bytes[o + 2], bytes[o + 3]]);
+        let i16_at = |o: usize| i16::from_le_bytes([bytes[o], bytes[o + 1]]);
+        Ok(Self {
+            seq: u32_at(0),
+            flags: u32_at(4),
+            custom_mode: u32_at(8),
+            base_mode: bytes[12],
+            system_status: bytes[13],
+            system_id: bytes[14],
+            lat_e7: u32_at(16) as i32,
+            lon_e7: u32_at(20) as i32,
+            alt_mm: u32_at(24) as i32,
+            relative_alt_mm: u32_at(28) as i32,
+            velocity_cm_s: [i16_at(32), i16_at(34), i16_at(36)],
+            heading_cdeg: u16::from_le_bytes([bytes[38], bytes[39]]),
+            roll: f32::from_bits(u32_at(40)),
+            pitch: f32::from_bits(u32_at(44)),
+            yaw: f32::from_bits(u32_at(48)),
+            time_boot_ms: u32_at(52),
+        })
+    }
+
+    /// Fold one decoded message into the snapshot; false if the message is not telemetry we track
+    #[cfg(feature = "mavlink")]
+    fn apply(&mut self, header: &MavHeader, msg: &MavMessage) -> bool {
+        match msg {
+            MavMessage::HEARTBEAT(hb) => {
+                self.custom_mode = hb.custom_mode;
+                self.base_mode = hb.base_mode.bits();
+                self.system_status = hb.system_status as u8;
+                self.system_id = header.system_id;
+                self.flags |= TELEMETRY_HEARTBEAT;
+            }
+            MavMessage::GLOBAL_POSITION_INT(pos) => {
+                self.lat_e7 = pos.lat;
+                self.lon_e7 = pos.lon;
+                self.alt_mm = pos.alt;
+                self.relative_alt_mm = pos.relative_alt;
+                self.velocity_cm_s = [pos.vx, pos.vy, pos.vz];
+                self.heading_cdeg = pos.hdg;
+                self.time_boot_ms = pos.time_boot_ms;
+                self.flags |= TELEMETRY_POSITION;
+            }
+            MavMessage::ATTITUDE(att) => {
+                self.roll = att.roll;
+                self.pitch = att.pitch;
+                self.yaw = att.yaw;
+                self.time_boot_ms = att.time_boot_ms;
+                self.flags |= TELEMETRY_ATTITUDE;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Read the telemetry record the MAVLink driver last published
+pub fn read_telemetry(sab: &SafeSAB) -> Result<DroneTelemetry, String> {
+    let bytes = sab.read(OFFSET_DRONE_TELEMETRY, SIZE_DRONE_TELEMETRY)?;
+    DroneTelemetry::from_bytes(&bytes)
+}
 
 pub struct MavlinkDriver {
     #[cfg(feature = "mavlink")]
-    connection: Option<Box<dyn MavConnection<MavMessage> + Send>>,
+    connection: Option<SharedConnection>,
+    #[cfg(feature = "mavlink")]
+    inbound: Option<Receiver<Inbound>>,
+    #[cfg(feature = "mavlink")]
+    reader: Option<ReaderThread>,
+    #[cfg(feature = "mavlink")]
+    telemetry: DroneTelemetry,
+    #[cfg(feature = "mavlink")]
+    sab: Option<SafeSAB>,
+    #[cfg(feature = "mavlink")]
+    epoch: Option<Epoch>,
 }
 
 impl MavlinkDriver {
@@ -16,16 +219,68 @@ impl MavlinkDriver {
         Self {
             #[cfg(feature = "mavlink")]
             connection: None,
+            #[cfg(feature = "mavlink")]
+            inbound: None,
+            #[cfg(feature = "mavlink")]
+            reader: None,
+            #[cfg(feature = "mavlink")]
+            telemetry: DroneTelemetry::default(),
+            #[cfg(feature = "mavlink")]
+            sab: None,
+            #[cfg(feature = "mavlink")]
+            epoch: None,
+        }
+    }
+
+    /// Publish decoded telemetry into `sab` and signal `IDX_SENSOR_EPOCH` on each update
+    pub fn set_sab(&mut self, _sab: SafeSAB) {
+        #[cfg(feature = "mavlink")]
+        {
+            self.epoch = Some(Epoch::new(_sab.clone(), IDX_SENSOR_EPOCH));
+            self.sab = Some(_sab);
         }
     }
 
     #[cfg(feature = "mavlink")]
     pub fn connect(&mut self, address: &str) -> Result<(), String> {
-        let conn = mavlink::connect(address).map_err(|e| e.to_string())?;
-        self.connection = Some(conn);
+        let conn = mavlink::connect::<MavMessage>(address).map_err(|e| e.to_string())?;
+        self.attach(Arc::from(conn));
         Ok(())
     }
 
+    /// Take over an open connection. `recv` blocks, so a reader thread drains it
+    /// into a channel that `poll` empties without blocking. Any previous
+    /// connection's reader is stopped first.
+    #[cfg(feature = "mavlink")]
+    pub fn attach(&mut self, conn: SharedConnection) {
+        self.detach();
+
+        let (tx, rx) = mpsc::channel::<Inbound>();
+        self.reader = Some(ReaderThread::spawn(Arc::clone(&conn), tx));
+        self.connection = Some(conn);
+        self.inbound = Some(rx);
+        self.telemetry = DroneTelemetry::default();
+    }
+
+    #[cfg(feature = "mavlink")]
+    fn detach(&mut self) {
+        if let Some(reader) = self.reader.take() {
+            reader.stop();
+        }
+        self.connection = None;
+        self.inbound = None;
+    }
+
+    #[cfg(feature = "mavlink")]
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    #[cfg(feature = "mavlink")]
+    pub fn telemetry(&self) -> DroneTelemetry {
+        self.telemetry
+    }
+
     #[cfg(feature = "mavlink")]
     pub fn send_message(&mut self, msg: MavMessage) -> Result<(), String> {
         if let Some(conn) = &mut self.connection {
@@ -41,7 +296,55 @@ impl MavlinkDriver {
         Err("MAVLink feature not enabled".to_string())
     }
 
+    /// Drain received messages, publish the updated telemetry once, and report
+    /// a dropped connection as an error (the connection is released so a
+    /// later `connect` starts clean)
     pub fn poll(&mut self) -> Result<(), String> {
+        #[cfg(feature = "mavlink")]
+        {
+            let inbound = match &self.inbound {
+                Some(rx) => rx,
+                None => return Ok(()),
+            };
+
+            let mut updated = false;
+            let mut lost = None;
+            loop {
+                match inbound.try_recv() {
+                    Ok(Ok((header, msg))) => updated |= self.telemetry.apply(&header, &msg),
+                    Ok(Err(e)) => {
+                        lost = Some(e);
+                        break;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        lost = Some("reader thread exited".to_string());
+                        break;
+                    }
+                }
+            }
+
+            if updated {
+                self.publish()?;
+            }
+
+            if let Some(reason) = lost {
+                self.detach();
+                return Err(format!("MAVLink connection lost: {}", reason));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "mavlink")]
+    fn publish(&mut self) -> Result<(), String> {
+        self.telemetry.seq = self.telemetry.seq.wrapping_add(1);
+        if let Some(sab) = &self.sab {
+            sab.write(OFFSET_DRONE_TELEMETRY, &self.telemetry.to_bytes())?;
+            if let Some(epoch) = &mut self.epoch {
+                epoch.increment();
+            }
+        }
         Ok(())
     }
 }
@@ -51,3 +354,259 @@ impl Default for MavlinkDriver {
         Self::new()
     }
 }
+
+#[cfg(feature = "mavlink")]
+impl Drop for MavlinkDriver {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telemetry_roundtrip_through_sab() {
+        let sab = SafeSAB::with_size(OFFSET_DRONE_TELEMETRY + SIZE_DRONE_TELEMETRY);
+        let telemetry = DroneTelemetry {
+            seq: 3,
+            flags: TELEMETRY_HEARTBEAT | TELEMETRY_ATTITUDE,
+            system_id: 1,
+            lat_e7: -338_688_000,
+            velocity_cm_s: [120, -45, 3],
+            heading_cdeg: 9000,
+            yaw: -1.25,
+            ..Default::default()
+        };
+
+        sab.write(OFFSET_DRONE_TELEMETRY, &telemetry.to_bytes())
+            .unwrap();
+        assert_eq!(read_telemetry(&sab).unwrap(), telemetry);
+    }
+
+    #[test]
+    fn test_poll_without_connection_is_ok() {
+        let mut driver = MavlinkDriver::new();
+        assert!(driver.poll().is_ok());
+    }
+}
+
+#[cfg(all(test, feature = "mavlink"))]
+pub(crate) mod mock {
+    use super::*;
+    use mavlink::error::MessageWriteError;
+    use mavlink::MavlinkVersion;
+    use parking_lot::Mutex;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::Sender;
+
+    /// Replays canned frames, then reports EOF as a dropped link; records sends
+    pub(crate) struct CannedConnection {
+        frames: Mutex<VecDeque<(MavHeader, MavMessage)>>,
+        pub(crate) sent: Mutex<Vec<MavMessage>>,
+    }
+
+    impl CannedConnection {
+        pub(crate) fn new(frames: Vec<MavMessage>) -> Self {
+            let header = MavHeader {
+                system_id: 1,
+                component_id: 1,
+                sequence: 0,
+            };
+            Self {
+                frames: Mutex::new(frames.into_iter().map(|m| (header, m)).collect()),
+                sent: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl MavConnection<MavMessage> for CannedConnection {
+        fn recv(&self) -> Result<(MavHeader, MavMessage), MessageReadError> {
+            self.frames
+                .lock()
+                .pop_front()
+                .ok_or_else(|| MessageReadError::Io(std::io::ErrorKind::UnexpectedEof.into()))
+        }
+
+        fn send(&self, _header: &MavHeader, data: &MavMessage) -> Result<usize, MessageWriteError> {
+            self.sent.lock().push(data.clone());
+            Ok(0)
+        }
+
+        fn set_protocol_version(&mut self, _version: MavlinkVersion) {}
+
+        fn get_protocol_version(&self) -> MavlinkVersion {
+            MavlinkVersion::V2
+        }
+    }
+
+    /// Blocks in `recv` until the test hands it a frame; EOF once the sender drops
+    pub(crate) struct GatedConnection {
+        frames: Mutex<Receiver<(MavHeader, MavMessage)>>,
+    }
+
+    impl GatedConnection {
+        pub(crate) fn new() -> (Self, Sender<(MavHeader, MavMessage)>) {
+            let (tx, rx) = mpsc::channel();
+            (
+                Self {
+                    frames: Mutex::new(rx),
+                },
+                tx,
+            )
+        }
+    }
+
+    impl MavConnection<MavMessage> for GatedConnection {
+        fn recv(&self) -> Result<(MavHeader, MavMessage), MessageReadError> {
+            self.frames
+                .lock()
+                .recv()
+                .map_err(|_| MessageReadError::Io(std::io::ErrorKind::UnexpectedEof.into()))
+        }
+
+        fn send(
+            &self,
+            _header: &MavHeader,
+            _data: &MavMessage,
+        ) -> Result<usize, MessageWriteError> {
+            Ok(0)
+        }
+
+        fn set_protocol_version(&mut self, _version: MavlinkVersion) {}
+
+        fn get_protocol_version(&self) -> MavlinkVersion {
+            MavlinkVersion::V2
+        }
+    }
+}
+
+#[cfg(all(test, feature = "mavlink"))]
+mod mavlink_tests {
+    use super::mock::{CannedConnection, GatedConnection};
+    use super::*;
+    use mavlink::ardupilotmega::{
+        MavModeFlag, MavState, ATTITUDE_DATA, GLOBAL_POSITION_INT_DATA, HEARTBEAT_DATA,
+    };
+    use std::time::Duration;
+
+    fn poll_until_lost(driver: &mut MavlinkDriver) -> String {
+        for _ in 0..200 {
+            if let Err(e) = driver.poll() {
+                return e;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("dropped connection was never reported");
+    }
+
+    #[test]
+    fn test_poll_publishes_telemetry_and_bumps_sensor_epoch() {
+        let sab = SafeSAB::with_size(OFFSET_DRONE_TELEMETRY + SIZE_DRONE_TELEMETRY);
+        let epoch = Epoch::new(sab.clone(), IDX_SENSOR_EPOCH);
+        let mut driver = MavlinkDriver::new();
+        driver.set_sab(sab.clone());
+
+        driver.attach(Arc::new(CannedConnection::new(vec![
+            MavMessage::HEARTBEAT(HEARTBEAT_DATA {
+                custom_mode: 4,
+                base_mode: MavModeFlag::MAV_MODE_FLAG_SAFETY_ARMED,
+                system_status: MavState::MAV_STATE_ACTIVE,
+                ..Default::default()
+            }),
+            MavMessage::GLOBAL_POSITION_INT(GLOBAL_POSITION_INT_DATA {
+                time_boot_ms: 1000,
+                lat: 377_749_000,
+                lon: -1_224_194_000,
+                alt: 52_000,
+                relative_alt: 10_000,
+                vx: 150,
+                vy: -20,
+                vz: 0,
+                hdg: 18_000,
+            }),
+            MavMessage::ATTITUDE(ATTITUDE_DATA {
+                time_boot_ms: 1010,
+                roll: 0.1,
+                pitch: -0.05,
+                yaw: 1.5,
+                ..Default::default()
+            }),
+        ])));
+
+        let err = poll_until_lost(&mut driver);
+        assert!(err.contains("connection lost"));
+        assert!(!driver.is_connected());
+
+        let telemetry = read_telemetry(&sab).unwrap();
+        assert!(telemetry.seq > 0);
+        assert_eq!(
+            telemetry.flags,
+            TELEMETRY_HEARTBEAT | TELEMETRY_POSITION | TELEMETRY_ATTITUDE
+        );
+        assert_eq!(telemetry.custom_mode, 4);
+        assert_eq!(
+            telemetry.base_mode,
+            MavModeFlag::MAV_MODE_FLAG_SAFETY_ARMED.bits()
+        );
+        assert_eq!(telemetry.system_status, MavState::MAV_STATE_ACTIVE as u8);
+        assert_eq!(telemetry.system_id, 1);
+        assert_eq!(telemetry.lat_e7, 377_749_000);
+        assert_eq!(telemetry.lon_e7, -1_224_194_000);
+        assert_eq!(telemetry.velocity_cm_s, [150, -20, 0]);
+        assert_eq!(telemetry.heading_cdeg, 18_000);
+        assert_eq!(telemetry.yaw, 1.5);
+        assert_eq!(telemetry.time_boot_ms, 1010);
+        assert!(epoch.current() > 0);
+    }
+
+    #[test]
+    fn test_dropped_connection_without_telemetry_leaves_sab_untouched() {
+        let sab = SafeSAB::with_size(OFFSET_DRONE_TELEMETRY + SIZE_DRONE_TELEMETRY);
+        let epoch = Epoch::new(sab.clone(), IDX_SENSOR_EPOCH);
+        let mut driver = MavlinkDriver::new();
+        driver.set_sab(sab.clone());
+
+        driver.attach(Arc::new(CannedConnection::new(Vec::new())));
+        poll_until_lost(&mut driver);
+
+        assert_eq!(read_telemetry(&sab).unwrap().seq, 0);
+        assert_eq!(epoch.current(), 0);
+        assert!(driver.poll().is_ok());
+    }
+
+    #[test]
+    fn test_reattach_stops_previous_reader() {
+        let mut driver = MavlinkDriver::new();
+        let (gated, feed) = GatedConnection::new();
+        let gated = Arc::new(gated);
+        driver.attach(gated.clone());
+
+        // Reconnect while the first reader is parked in recv
+        driver.attach(Arc::new(CannedConnection::new(vec![
+            MavMessage::HEARTBEAT(HEARTBEAT_DATA::default()),
+        ])));
+
+        // A late frame on the old link wakes its reader, which must exit
+        // without forwarding it and release the old connection
+        let header = MavHeader {
+            system_id: 9,
+            component_id: 1,
+            sequence: 0,
+        };
+        feed.send((header, MavMessage::HEARTBEAT(HEARTBEAT_DATA::default())))
+            .unwrap();
+        for _ in 0..200 {
+            if Arc::strong_count(&gated) == 1 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(Arc::strong_count(&gated), 1);
+
+        poll_until_lost(&mut driver);
+        assert_eq!(driver.telemetry().system_id, 1);
+        assert_eq!(driver.telemetry().flags, TELEMETRY_HEARTBEAT);
+    }
+}
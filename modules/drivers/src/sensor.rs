@@ -2,14 +2,143 @@ use crate::reader::RingBufferReader;
 use capnp::serialize;
 use sdk::sensor_capnp;
 use sdk::Epoch;
+use std::collections::HashMap;
 
 pub trait Sensor: Send {
     fn id(&self) -> &str;
     fn on_frame(&mut self, frame: &[u8]) -> Result<(), String>;
 }
 
+/// Per-sensor forwarding policy. Times come from the frame's `monotonicNs`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Subscription {
+    /// Minimum spacing between forwarded frames; 0 forwards at full rate
+    pub min_interval_ns: i64,
+    /// Largest per-sample change (frame read as packed little-endian f32)
+    /// still treated as noise; 0.0 forwards every change
+    pub change_threshold: f32,
+}
+
+/// What the registry decided for an offered frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Forward,
+    /// Arrived inside the interval; held as the latest pending value
+    Coalesce,
+    /// Within the change threshold of the last forwarded frame
+    Suppress,
+}
+
+struct SubscriptionState {
+    policy: Subscription,
+    last_forwarded: Option<(i64, Vec<u8>)>,
+    pending: Option<(i64, Vec<u8>)>,
+}
+
+/// Throttles and de-noises frames per sensor id; sensors without a
+/// subscription are forwarded unconditionally
+#[derive(Default)]
+pub struct SubscriptionRegistry {
+    entries: HashMap<String, SubscriptionState>,
+    /// Newest monotonic timestamp seen across all frames
+    clock_ns: i64,
+    /// `clock_ns` paired with the local time it was first seen at, so the
+    /// clock keeps running while no frames arrive
+    anchor: Option<(i64, i64)>,
+}
+
+impl SubscriptionRegistry {
+    pub fn subscribe(&mut self, sensor_id: &str, policy: Subscription) {
+        self.entries.insert(
+            sensor_id.to_string(),
+            SubscriptionState {
+                policy,
+                last_forwarded: None,
+                pending: None,
+            },
+        );
+    }
+
+    pub fn unsubscribe(&mut self, sensor_id: &str) {
+        self.entries.remove(sensor_id);
+    }
+
+    pub fn offer(&mut self, sensor_id: &str, monotonic_ns: i64, frame: &[u8]) -> Decision {
+        self.clock_ns = self.clock_ns.max(monotonic_ns);
+
+        let state = match self.entries.get_mut(sensor_id) {
+            Some(state) => state,
+            None => return Decision::Forward,
+        };
+
+        if let Some((last_ns, last_frame)) = &state.last_forwarded {
+            let threshold = state.policy.change_threshold;
+            if threshold > 0.0 && matches!(max_change(last_frame, frame), Some(d) if d <= threshold)
+            {
+                // Latest value is back within noise of what consumers have
+                state.pending = None;
+                return Decision::Suppress;
+            }
+            if monotonic_ns - last_ns < state.policy.min_interval_ns {
+                state.pending = Some((monotonic_ns, frame.to_vec()));
+                return Decision::Coalesce;
+            }
+        }
+
+        state.last_forwarded = Some((monotonic_ns, frame.to_vec()));
+        state.pending = None;
+        Decision::Forward
+    }
+
+    /// Release coalesced frames whose interval has elapsed on the shared clock,
+    /// advanced by local time (`now_ns`) passed since the newest frame arrived
+    pub fn take_due(&mut self, now_ns: i64) -> Vec<(String, Vec<u8>)> {
+        let (frame_ns, seen_ns) = match self.anchor {
+            Some((frame_ns, seen_ns)) if frame_ns == self.clock_ns => (frame_ns, seen_ns),
+            _ => (self.clock_ns, now_ns),
+        };
+        self.anchor = Some((frame_ns, seen_ns));
+        let clock_ns = frame_ns.saturating_add((now_ns - seen_ns).max(0));
+        let mut due = Vec::new();
+        for (id, state) in self.entries.iter_mut() {
+            let last_ns = match &state.last_forwarded {
+                Some((ns, _)) => *ns,
+                None => continue,
+            };
+            if state.pending.is_some() && clock_ns - last_ns >= state.policy.min_interval_ns {
+                if let Some((ns, frame)) = state.pending.take() {
+                    state.last_forwarded = Some((ns, frame.clone()));
+                    due.push((id.clone(), frame));
+                }
+            }
+        }
+        due
+    }
+}
+
+/// Largest absolute difference between two frames of packed f32 samples;
+/// None when the frames are not comparable that way
+fn max_change(previous: &[u8], current: &[u8]) -> Option<f32> {
+    if previous.len() != current.len() || !current.len().is_multiple_of(4) {
+        return None;
+    }
+    previous
+        .chunks_exact(4)
+        .zip(current.chunks_exact(4))
+        .map(|(a, b)| {
+            let a = f32::from_le_bytes([a[0], a[1], a[2], a[3]]);
+            let b = f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            (b - a).abs()
+        })
+        .try_fold(
+            0.0f32,
+            |max, d| if d.is_nan() { None } else { Some(max.max(d)) },
+        )
+}
+
 pub struct SensorSubscriber {
     sensors: Vec<Box<dyn Sensor>>,
+    subscriptions: SubscriptionRegistry,
     epoch: Epoch,
     ring_buffer: Option<sdk::ringbuffer::RingBuffer>,
 }
@@ -18,6 +147,7 @@ impl SensorSubscriber {
     pub fn new(epoch: Epoch) -> Self {
         Self {
             sensors: Vec::new(),
+            subscriptions: SubscriptionRegistry::default(),
             epoch,
             ring_buffer: None, // Initialized later or passed in
         }
@@ -31,7 +161,75 @@ impl SensorSubscriber {
         self.sensors.push(sensor);
     }
 
+    /// Rate-limit and de-noise frames from `sensor_id`
+    pub fn subscribe(&mut self, sensor_id: &str, policy: Subscription) {
+        self.subscriptions.subscribe(sensor_id, policy);
+    }
+
+    pub fn unsubscribe(&mut self, sensor_id: &str) {
+        self.subscriptions.unsubscribe(sensor_id);
+    }
+
+    /// Run a batch of frames through the filters, then signal `IDX_SENSOR_EPOCH`
+    /// once if anything reached a sensor. Returns the number of frames delivered.
+    pub fn ingest<'a, I>(&mut self, frames: I) -> usize
+    where
+        I: IntoIterator<Item = (&'a str, i64, &'a [u8])>,
+    {
+        let mut delivered = 0;
+        for (source_id, monotonic_ns, bytes) in frames {
+            delivered += Self::route(
+                &mut self.subscriptions,
+                &mut self.sensors,
+                source_id,
+                monotonic_ns,
+                bytes,
+            );
+        }
+        self.finish_batch(delivered, local_now_ns())
+    }
+
+    fn route(
+        subscriptions: &mut SubscriptionRegistry,
+        sensors: &mut [Box<dyn Sensor>],
+        source_id: &str,
+        monotonic_ns: i64,
+        bytes: &[u8],
+    ) -> usize {
+        match subscriptions.offer(source_id, monotonic_ns, bytes) {
+            Decision::Forward => Self::deliver(sensors, source_id, bytes),
+            Decision::Coalesce | Decision::Suppress => 0,
+        }
+    }
+
+    fn deliver(sensors: &mut [Box<dyn Sensor>], source_id: &str, bytes: &[u8]) -> usize {
+        let mut delivered = 0;
+        for sensor in sensors.iter_mut() {
+            if sensor.id() == source_id {
+                let _ = sensor.on_frame(bytes);
+                delivered = 1;
+            }
+        }
+        delivered
+    }
+
+    /// Flush coalesced frames that are now due and signal the epoch if the
+    /// batch produced any output
+    fn finish_batch(&mut self, mut delivered: usize, now_ns: i64) -> usize {
+        for (source_id, frame) in self.subscriptions.take_due(now_ns) {
+            delivered += Self::deliver(&mut self.sensors, &source_id, &frame);
+        }
+        if delivered > 0 {
+            // Our own bump must not re-trigger the inbox read in `poll`
+            self.epoch.increment_own();
+        }
+        delivered
+    }
+
+    /// Read new frames when the inbox epoch moved, and flush held frames
+    /// that came due even if nothing new arrived
     pub fn poll(&mut self) -> Result<(), String> {
+        let mut delivered = 0;
         if self.epoch.has_changed() {
             // Read from OffsetInbox (Host -> Drivers)
             if let Some(rb) = &self.ring_buffer {
@@ -64,13 +262,15 @@ impl SensorSubscriber {
                                     _ => None, // Structured data (IMU, etc.) handling not yet implemented via raw interface
                                 };
 
-                                if let Some(bytes) = data {
-                                    for sensor in &mut self.sensors {
-                                        if let Ok(source_id) = root.get_source_id() {
-                                            if sensor.id() == source_id {
-                                                let _ = sensor.on_frame(bytes);
-                                            }
-                                        }
+                                if let (Some(bytes), Ok(source_id)) = (data, root.get_source_id()) {
+                                    if let Ok(source_id) = source_id.to_str() {
+                                        delivered += Self::route(
+                                            &mut self.subscriptions,
+                                            &mut self.sensors,
+                                            source_id,
+                                            root.get_monotonic_ns(),
+                                            bytes,
+                                        );
                                     }
                                 }
                             }
@@ -80,6 +280,160 @@ impl SensorSubscriber {
                 }
             }
         }
+        self.finish_batch(delivered, local_now_ns());
         Ok(())
     }
 }
+
+/// Local monotonic time in nanoseconds
+fn local_now_ns() -> i64 {
+    (sdk::js_interop::get_performance_now() * 1_000_000.0) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use sdk::sab::SafeSAB;
+    use sdk::IDX_SENSOR_EPOCH;
+    use std::sync::Arc;
+
+    const MS: i64 = 1_000_000;
+
+    struct RecordingSensor {
+        id: String,
+        frames: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl Sensor for RecordingSensor {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn on_frame(&mut self, frame: &[u8]) -> Result<(), String> {
+            self.frames.lock().push(frame.to_vec());
+            Ok(())
+        }
+    }
+
+    fn sample(value: f32) -> Vec<u8> {
+        value.to_le_bytes().to_vec()
+    }
+
+    fn subscriber_with(id: &str) -> (SensorSubscriber, Epoch, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let sab = SafeSAB::with_size(1024);
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let mut subscriber = SensorSubscriber::new(Epoch::new(sab.clone(), IDX_SENSOR_EPOCH));
+        subscriber.register_sensor(Box::new(RecordingSensor {
+            id: id.to_string(),
+            frames: frames.clone(),
+        }));
+        (subscriber, Epoch::new(sab, IDX_SENSOR_EPOCH), frames)
+    }
+
+    #[test]
+    fn test_fast_sensor_is_throttled_and_coalesced() {
+        let (mut subscriber, epoch, frames) = subscriber_with("imu");
+        subscriber.subscribe(
+            "imu",
+            Subscription {
+                min_interval_ns: 10 * MS,
+                change_threshold: 0.0,
+            },
+        );
+
+        // 1kHz burst: only the first frame passes inside the 10ms window
+        let burst: Vec<Vec<u8>> = (0..5).map(|i| sample(i as f32)).collect();
+        let delivered = subscriber.ingest(
+            burst
+                .iter()
+                .enumerate()
+                .map(|(i, f)| ("imu", i as i64 * MS, f.as_slice())),
+        );
+        assert_eq!(delivered, 1);
+        assert_eq!(epoch.current(), 1);
+
+        // Once the interval has elapsed the latest held value is flushed
+        let later = sample(9.0);
+        subscriber.ingest([("other", 12 * MS, later.as_slice())]);
+        assert_eq!(*frames.lock(), vec![sample(0.0), sample(4.0)]);
+        assert_eq!(epoch.current(), 2);
+    }
+
+    #[test]
+    fn test_held_frame_flushes_on_local_time_without_new_frames() {
+        let mut registry = SubscriptionRegistry::default();
+        registry.subscribe(
+            "imu",
+            Subscription {
+                min_interval_ns: 10 * MS,
+                change_threshold: 0.0,
+            },
+        );
+        assert_eq!(registry.offer("imu", 0, &sample(1.0)), Decision::Forward);
+        assert_eq!(registry.offer("imu", MS, &sample(2.0)), Decision::Coalesce);
+
+        // Frame clock stands at 1ms; local time carries it forward from there
+        assert!(registry.take_due(500 * MS).is_empty());
+        assert!(registry.take_due(508 * MS).is_empty());
+        assert_eq!(
+            registry.take_due(509 * MS),
+            vec![("imu".to_string(), sample(2.0))]
+        );
+        assert!(registry.take_due(600 * MS).is_empty());
+    }
+
+    #[test]
+    fn test_poll_flushes_held_frame_after_sensor_goes_quiet() {
+        let (mut subscriber, epoch, frames) = subscriber_with("imu");
+        subscriber.subscribe(
+            "imu",
+            Subscription {
+                min_interval_ns: MS,
+                change_threshold: 0.0,
+            },
+        );
+
+        let (first, last) = (sample(1.0), sample(2.0));
+        subscriber.ingest([("imu", 0, first.as_slice()), ("imu", 1, last.as_slice())]);
+        assert_eq!(*frames.lock(), vec![first.clone()]);
+
+        // No new frames and no inbox signal: poll alone releases the held value
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        subscriber.poll().unwrap();
+        assert_eq!(*frames.lock(), vec![first, last]);
+        assert_eq!(epoch.current(), 2);
+    }
+
+    #[test]
+    fn test_sub_threshold_change_is_suppressed() {
+        let (mut subscriber, epoch, frames) = subscriber_with("baro");
+        subscriber.subscribe(
+            "baro",
+            Subscription {
+                min_interval_ns: 0,
+                change_threshold: 0.5,
+            },
+        );
+
+        let first = sample(100.0);
+        let noise = sample(100.3);
+        let jump = sample(101.0);
+        assert_eq!(subscriber.ingest([("baro", 0, first.as_slice())]), 1);
+        assert_eq!(subscriber.ingest([("baro", MS, noise.as_slice())]), 0);
+        assert_eq!(epoch.current(), 1); // No wakeup for noise
+        assert_eq!(subscriber.ingest([("baro", 2 * MS, jump.as_slice())]), 1);
+
+        assert_eq!(*frames.lock(), vec![first, jump]);
+        assert_eq!(epoch.current(), 2);
+    }
+
+    #[test]
+    fn test_unsubscribed_sensor_forwards_every_frame() {
+        let (mut subscriber, _epoch, frames) = subscriber_with("gps");
+        let frame = sample(1.0);
+        let delivered = subscriber.ingest((0..3).map(|i| ("gps", i, frame.as_slice())));
+        assert_eq!(delivered, 3);
+        assert_eq!(frames.lock().len(), 3);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::registry::crc32c_hash;
 use crate::sab::SafeSAB;
 use crate::signal::Epoch;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
 
 // Arena allocator interface for Rust modules
 // Communicates with Go-side hybrid allocator via Epoch signaling
@@ -188,3 +190,185 @@ impl std::fmt::Display for ArenaError {
 }
 
 impl std::error::Error for ArenaError {}
+
+// ========== HOST BUFFERS ==========
+// ABI contract for the `*_alloc`/`*_free` export pairs:
+// - `*_alloc(size)` returns a buffer of `size` bytes owned by the host (JS)
+// - the host releases it exactly once with the same module's `*_free(ptr, size)`,
+//   passing the size it allocated with; a null `ptr` is ignored
+// - the buffer must not be touched after `*_free`
+
+/// Allocate a host-owned buffer for `*_alloc` exports
+pub fn alloc_host_buffer(size: usize) -> *mut u8 {
+    let mut buf = std::mem::ManuallyDrop::new(Vec::<u8>::with_capacity(size));
+    buf.as_mut_ptr()
+}
+
+/// Release a buffer from `alloc_host_buffer`, for `*_free` exports
+///
+/// # Safety
+/// `ptr` must come from `alloc_host_buffer(size)` with the same `size` and
+/// must not have been freed already.
+pub unsafe fn free_host_buffer(ptr: *mut u8, size: usize) {
+    if !ptr.is_null() {
+        drop(Vec::from_raw_parts(ptr, 0, size));
+    }
+}
+
+// ========== SCRATCH ARENA ==========
+// Bump allocator for per-job buffers in WASM linear memory (not the SAB).
+// Blocks are kept across resets, so steady-state jobs allocate nothing new;
+// oversized blocks from one large input are released by the next reset.
+
+/// Default size of each scratch block
+pub const SCRATCH_BLOCK_SIZE: usize = 64 * 1024;
+const SCRATCH_ALIGN: usize = 8;
+
+/// Position in a `ScratchArena`; the default is the empty arena
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScratchCheckpoint {
+    block: usize,
+    offset: usize,
+}
+
+pub struct ScratchArena {
+    /// u64 words so every block starts 8-byte aligned
+    blocks: Vec<Box<[u64]>>,
+    block_size: usize,
+    current: usize,
+    offset: usize,
+}
+
+impl Default for ScratchArena {
+    fn default() -> Self {
+        Self::new(SCRATCH_BLOCK_SIZE)
+    }
+}
+
+impl ScratchArena {
+    pub fn new(block_size: usize) -> Self {
+        Self {
+            blocks: Vec::new(),
+            block_size: block_size.max(SCRATCH_ALIGN),
+            current: 0,
+            offset: 0,
+        }
+    }
+
+    /// 8-byte aligned buffer of `size` bytes, valid until a reset past it
+    pub fn alloc(&mut self, size: usize) -> *mut u8 {
+        let start = self.offset.next_multiple_of(SCRATCH_ALIGN);
+        if let Some(block) = self.blocks.get_mut(self.current) {
+            if start + size <= block.len() * SCRATCH_ALIGN {
+                self.offset = start + size;
+                return unsafe { (block.as_mut_ptr() as *mut u8).add(start) };
+            }
+            self.current += 1;
+        }
+
+        // Reuse the next block if it is big enough, otherwise replace it
+        if !matches!(self.blocks.get(self.current), Some(b) if b.len() * SCRATCH_ALIGN >= size) {
+            self.blocks.truncate(self.current);
+            let words = size.max(self.block_size).div_ceil(SCRATCH_ALIGN);
+            self.blocks.push(vec![0u64; words].into_boxed_slice());
+        }
+        self.offset = size;
+        self.blocks[self.current].as_mut_ptr() as *mut u8
+    }
+
+    pub fn checkpoint(&self) -> ScratchCheckpoint {
+        ScratchCheckpoint {
+            block: self.current,
+            offset: self.offset,
+        }
+    }
+
+    /// Free everything allocated after `checkpoint`.
+    /// Pointers handed out since then must no longer be used.
+    /// A checkpoint past the current position (taken before an earlier reset) is ignored.
+    /// Freed blocks larger than `block_size` are returned to the system allocator.
+    pub fn reset_to(&mut self, checkpoint: ScratchCheckpoint) {
+        if checkpoint <= self.checkpoint() {
+            self.current = checkpoint.block;
+            self.offset = checkpoint.offset;
+
+            let first_free = self.current + (self.offset > 0) as usize;
+            let block_words = self.block_size.div_ceil(SCRATCH_ALIGN);
+            let mut index = 0;
+            self.blocks.retain(|block| {
+                let keep = index < first_free || block.len() <= block_words;
+                index += 1;
+                keep
+            });
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.reset_to(ScratchCheckpoint::default());
+    }
+
+    /// Bytes up to the current position (the high-water mark a reset returns to)
+    pub fn used(&self) -> usize {
+        let full: usize = self.blocks[..self.current.min(self.blocks.len())]
+            .iter()
+            .map(|b| b.len() * SCRATCH_ALIGN)
+            .sum();
+        full + self.offset
+    }
+
+    /// Bytes reserved from the system allocator
+    pub fn reserved(&self) -> usize {
+        self.blocks.iter().map(|b| b.len() * SCRATCH_ALIGN).sum()
+    }
+
+    /// True if `ptr` points into one of this arena's blocks
+    pub fn owns(&self, ptr: *const u8) -> bool {
+        let addr = ptr as usize;
+        self.blocks.iter().any(|b| {
+            let start = b.as_ptr() as usize;
+            (start..start + b.len() * SCRATCH_ALIGN).contains(&addr)
+        })
+    }
+}
+
+static SCRATCH: Lazy<Mutex<ScratchArena>> = Lazy::new(|| Mutex::new(ScratchArena::default()));
+
+fn with_scratch<R>(f: impl FnOnce(&mut ScratchArena) -> R) -> R {
+    let mut guard = SCRATCH.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut guard)
+}
+
+/// Module-wide scratch allocation, for `*_alloc` exports
+pub fn scratch_alloc(size: usize) -> *mut u8 {
+    with_scratch(|arena| arena.alloc(size))
+}
+
+pub fn scratch_checkpoint() -> ScratchCheckpoint {
+    with_scratch(|arena| arena.checkpoint())
+}
+
+pub fn scratch_reset_to(checkpoint: ScratchCheckpoint) {
+    with_scratch(|arena| arena.reset_to(checkpoint))
+}
+
+/// `*_free` exports use this to leave scratch pointers to the next reset
+pub fn scratch_owns(ptr: *const u8) -> bool {
+    with_scratch(|arena| arena.owns(ptr))
+}
+
+/// Resets the module scratch arena when dropped.
+/// Hold one across a job entry point: the host allocates the job's inputs
+/// just before calling it, so everything in the arena is dead afterwards.
+pub struct ScratchJob(ScratchCheckpoint);
+
+impl ScratchJob {
+    pub fn begin() -> Self {
+        Self(ScratchCheckpoint::default())
+    }
+}
+
+impl Drop for ScratchJob {
+    fn drop(&mut self) {
+        scratch_reset_to(self.0);
+    }
+}
@@ -12,6 +12,8 @@ pub enum CompressionError {
     Snappy(String),
     #[error("LZ4 error: {0}")]
     Lz4(String),
+    #[error("Zstd error: {0}")]
+    Zstd(String),
     #[error("Unsupported algorithm")]
     Unsupported,
 }
@@ -26,6 +28,17 @@ pub enum CompressionAlgorithm {
 }
 
 impl CompressionAlgorithm {
+    /// Inverse of `algorithm as u8`, for reading self-describing blobs
+    pub fn from_tag(tag: u8) -> Result<Self, CompressionError> {
+        match tag {
+            0 => Ok(CompressionAlgorithm::None),
+            1 => Ok(CompressionAlgorithm::Brotli),
+            2 => Ok(CompressionAlgorithm::Snappy),
+            3 => Ok(CompressionAlgorithm::Lz4),
+            _ => Err(CompressionError::Unsupported),
+        }
+    }
+
     pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
         match self {
             CompressionAlgorithm::None => Ok(data.to_vec()),
@@ -88,6 +101,43 @@ fn decompress_lz4(data: &[u8]) -> Result<Vec<u8>, CompressionError> {
     lz4_flex::decompress_size_prepended(data).map_err(|e| CompressionError::Lz4(e.to_string()))
 }
 
+/// Upper bound on a trained Zstd dictionary
+#[cfg(feature = "zstd")]
+pub const ZSTD_DICTIONARY_SIZE: usize = 16 * 1024;
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// Trains a Zstd dictionary from representative samples
+/// Many small, similar blobs (e.g. telemetry frames) then share one copy of
+/// their common structure instead of each paying for it. The dictionary must
+/// be stored once and supplied again to `decompress_with_dict`.
+#[cfg(feature = "zstd")]
+pub fn train_dictionary<S: AsRef<[u8]>>(samples: &[S]) -> Result<Vec<u8>, CompressionError> {
+    zstd::dict::from_samples(samples, ZSTD_DICTIONARY_SIZE)
+        .map_err(|e| CompressionError::Zstd(e.to_string()))
+}
+
+#[cfg(feature = "zstd")]
+pub fn compress_with_dict(data: &[u8], dictionary: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    let mut encoder = zstd::stream::Encoder::with_dictionary(Vec::new(), ZSTD_LEVEL, dictionary)
+        .map_err(|e| CompressionError::Zstd(e.to_string()))?;
+    encoder.write_all(data)?;
+    encoder
+        .finish()
+        .map_err(|e| CompressionError::Zstd(e.to_string()))
+}
+
+#[cfg(feature = "zstd")]
+pub fn decompress_with_dict(data: &[u8], dictionary: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    let mut decoder = zstd::stream::Decoder::with_dictionary(data, dictionary)
+        .map_err(|e| CompressionError::Zstd(e.to_string()))?;
+    let mut decompressed = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| CompressionError::Zstd(e.to_string()))?;
+    Ok(decompressed)
+}
+
 /// Computes BLAKE3 hash for content-addressable storage
 /// Returns 32-byte hash suitable for deduplication and integrity verification
 pub fn hash_blake3(data: &[u8]) -> [u8; 32] {
@@ -125,4 +175,53 @@ mod tests {
         let hash = hash_blake3(b"");
         assert_eq!(hash.len(), 32, "Should return 32-byte hash");
     }
+
+    #[cfg(feature = "zstd")]
+    fn telemetry_record(i: u32) -> Vec<u8> {
+        format!(
+            "{{\"sensor\":\"imu-{}\",\"seq\":{},\"accel\":[{:.3},{:.3},9.81],\"status\":\"nominal\"}}",
+            i % 8,
+            i,
+            (i % 17) as f32 * 0.01,
+            (i % 13) as f32 * -0.02
+        )
+        .into_bytes()
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_dictionary_roundtrip() {
+        let samples: Vec<Vec<u8>> = (0..2000).map(telemetry_record).collect();
+        let dictionary = train_dictionary(&samples).unwrap();
+        assert!(!dictionary.is_empty());
+
+        let record = telemetry_record(5001);
+        let packed = compress_with_dict(&record, &dictionary).unwrap();
+        assert_eq!(decompress_with_dict(&packed, &dictionary).unwrap(), record);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_dictionary_beats_independent_compression() {
+        let samples: Vec<Vec<u8>> = (0..2000).map(telemetry_record).collect();
+        let dictionary = train_dictionary(&samples).unwrap();
+
+        // Held-out records, compressed one at a time
+        let records: Vec<Vec<u8>> = (5000..5100).map(telemetry_record).collect();
+        let independent: usize = records
+            .iter()
+            .map(|r| zstd::bulk::compress(r, ZSTD_LEVEL).unwrap().len())
+            .sum();
+        let with_dict: usize = records
+            .iter()
+            .map(|r| compress_with_dict(r, &dictionary).unwrap().len())
+            .sum();
+
+        assert!(
+            with_dict < independent,
+            "dictionary {} bytes vs independent {} bytes",
+            with_dict,
+            independent
+        );
+    }
 }
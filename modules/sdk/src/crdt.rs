@@ -1,6 +1,6 @@
 use automerge::{AutoCommit, ObjType, ReadDoc, ScalarValue};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Represents an economic wallet in the P2P network
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -725,3 +725,124 @@ impl GCounter {
         })
     }
 }
+
+/// Lamport timestamp with the writing replica as tie-breaker (total order)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LwwStamp {
+    pub counter: u64,
+    pub replica: String,
+}
+
+/// Last-writer-wins map: every key keeps the write with the highest stamp.
+/// Removals are kept as tombstones so they beat older concurrent inserts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LwwMap<K: Ord, V> {
+    replica_id: String,
+    clock: u64,
+    entries: BTreeMap<K, (LwwStamp, Option<V>)>,
+}
+
+impl<K: Ord + Clone, V: Clone + PartialEq> LwwMap<K, V> {
+    pub fn new(replica_id: &str) -> Self {
+        Self {
+            replica_id: replica_id.to_string(),
+            clock: 0,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn next_stamp(&mut self) -> LwwStamp {
+        self.clock += 1;
+        LwwStamp {
+            counter: self.clock,
+            replica: self.replica_id.clone(),
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> LwwStamp {
+        let stamp = self.next_stamp();
+        self.entries.insert(key, (stamp.clone(), Some(value)));
+        stamp
+    }
+
+    /// Tombstone `key` (recorded even if the key was never seen here)
+    pub fn remove(&mut self, key: &K) -> LwwStamp {
+        let stamp = self.next_stamp();
+        self.entries.insert(key.clone(), (stamp.clone(), None));
+        stamp
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).and_then(|(_, v)| v.as_ref())
+    }
+
+    /// Stamp of the winning write for `key`, including tombstones
+    pub fn stamp(&self, key: &K) -> Option<&LwwStamp> {
+        self.entries.get(key).map(|(stamp, _)| stamp)
+    }
+
+    /// Live entries in key order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries
+            .iter()
+            .filter_map(|(k, (_, v))| v.as_ref().map(|v| (k, v)))
+    }
+
+    /// Live values in key order, for normalizing state imported from a peer
+    /// before it is merged. Stamps are untouched.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.entries.values_mut().filter_map(|(_, v)| v.as_mut())
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Merge with another replica; returns true if any visible value changed
+    pub fn merge(&mut self, other: &Self) -> bool {
+        let mut changed = false;
+        for (key, (stamp, value)) in &other.entries {
+            let newer = match self.entries.get(key) {
+                Some((current, _)) => stamp > current,
+                None => true,
+            };
+            if newer {
+                let previous = self
+                    .entries
+                    .insert(key.clone(), (stamp.clone(), value.clone()));
+                changed |= previous.map_or(value.is_some(), |(_, v)| v != *value);
+            }
+        }
+        // Keep local stamps ahead of everything observed so far
+        self.clock = self.clock.max(other.clock);
+        changed
+    }
+}
+
+impl<K, V> LwwMap<K, V>
+where
+    K: Ord + Serialize + for<'de> Deserialize<'de>,
+    V: Serialize + for<'de> Deserialize<'de>,
+{
+    /// Export replica state for gossip
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(&(self.clock, &self.entries)).unwrap()
+    }
+
+    /// Import replica state received from `bytes` as replica `replica_id`
+    pub fn from_bytes(bytes: &[u8], replica_id: &str) -> Result<Self, String> {
+        let (clock, entries): (u64, BTreeMap<K, (LwwStamp, Option<V>)>) =
+            bincode::deserialize(bytes)
+                .map_err(|e| format!("Failed to deserialize LwwMap: {}", e))?;
+
+        Ok(Self {
+            replica_id: replica_id.to_string(),
+            clock,
+            entries,
+        })
+    }
+}
@@ -105,12 +105,42 @@ pub fn hash_with_key(data: &[u8], key: &[u8; 32]) -> String {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MerkleProof {
     pub leaf_index: usize,
+    /// Leaves in the tree; fixes the shape, and so the length, of the path
+    pub leaf_count: usize,
     pub leaf_hash: String,
     pub sibling_hashes: Vec<String>,
     pub root_hash: String,
 }
 
+/// Domain prefixes for Merkle hashing (as in RFC 6962), so a chunk can never
+/// be passed off as an interior node or the other way round
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+fn merkle_leaf_hash(data: &[u8]) -> String {
+    let mut hasher = Hasher::new();
+    hasher.update(&[MERKLE_LEAF_PREFIX]);
+    hasher.update(data);
+    hasher.finalize().to_hex().to_string()
+}
+
+fn merkle_node_hash(left: &str, right: &str) -> String {
+    let mut hasher = Hasher::new();
+    hasher.update(&[MERKLE_NODE_PREFIX]);
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Whether node `index` of a level `width` nodes wide is the unpaired last one
+fn is_promoted(index: usize, width: usize) -> bool {
+    index == width - 1 && !width.is_multiple_of(2)
+}
+
 /// Tree Hasher for Large Files (Merkle Tree)
+///
+/// An unpaired last node is promoted to the next level unchanged rather than
+/// paired with itself, so distinct leaf sequences never share a root.
 pub struct TreeHasher {
     #[allow(dead_code)]
     chunk_size: usize,
@@ -127,8 +157,7 @@ impl TreeHasher {
 
     /// Add a chunk to the tree
     pub fn add_chunk(&mut self, data: &[u8]) {
-        let hash = hash_data(data);
-        self.hashes.push(hash);
+        self.hashes.push(merkle_leaf_hash(data));
     }
 
     /// Build merkle tree and return root hash
@@ -138,39 +167,21 @@ impl TreeHasher {
         }
 
         let mut current_level = self.hashes.clone();
-
         while current_level.len() > 1 {
-            let mut next_level = Vec::new();
-
-            for pair in current_level.chunks(2) {
-                let combined = if pair.len() == 2 {
-                    format!("{}{}", pair[0], pair[1])
-                } else {
-                    // Odd number, duplicate the last one
-                    format!("{}{}", pair[0], pair[0])
-                };
-
-                let hash = hash_data(combined.as_bytes());
-                next_level.push(hash);
-            }
-
-            current_level = next_level;
+            current_level = Self::compute_parent_level(&current_level);
         }
-
         current_level[0].clone()
     }
 
-    fn compute_parent_level(&self, level: &[String]) -> Vec<String> {
-        let mut next = Vec::new();
-        for pair in level.chunks(2) {
-            let combined = if pair.len() == 2 {
-                format!("{}{}", pair[0], pair[1])
-            } else {
-                format!("{}{}", pair[0], pair[0])
-            };
-            next.push(hash_data(combined.as_bytes()));
-        }
-        next
+    fn compute_parent_level(level: &[String]) -> Vec<String> {
+        level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_node_hash(left, right),
+                [promoted] => promoted.clone(),
+                _ => unreachable!("chunks(2) yields one or two nodes"),
+            })
+            .collect()
     }
 
     /// Generate inclusion proof for a specific chunk
@@ -184,41 +195,61 @@ impl TreeHasher {
         let mut current_level = self.hashes.clone();
 
         while current_level.len() > 1 {
-            let sibling_index = if current_index.is_multiple_of(2) {
-                // Right sibling
-                if current_index + 1 < current_level.len() {
-                    Some(current_index + 1)
-                } else {
-                    None
-                }
-            } else {
-                // Left sibling
-                Some(current_index - 1)
-            };
-
-            if let Some(sib_idx) = sibling_index {
-                proof.push(current_level[sib_idx].clone());
+            // A promoted node has no sibling at this level
+            if !is_promoted(current_index, current_level.len()) {
+                proof.push(current_level[current_index ^ 1].clone());
             }
 
             // Move to parent level
             current_index /= 2;
-            current_level = self.compute_parent_level(&current_level);
+            current_level = Self::compute_parent_level(&current_level);
         }
 
-        let root = if current_level.is_empty() {
-            // Should not happen if loop logic is correct
-            String::new()
-        } else {
-            current_level[0].clone()
-        };
-
         Some(MerkleProof {
             leaf_index: chunk_index,
+            leaf_count: self.hashes.len(),
             leaf_hash: self.hashes[chunk_index].clone(),
             sibling_hashes: proof,
-            root_hash: root,
+            root_hash: current_level[0].clone(),
         })
     }
+
+    /// Check that `chunk` is leaf `leaf_index` of the `leaf_count`-leaf tree
+    /// with `root_hash`. Needs only the sibling path, not the other chunks;
+    /// the path must have exactly the length the tree's shape implies.
+    pub fn verify_proof(
+        root_hash: &str,
+        chunk: &[u8],
+        leaf_index: usize,
+        leaf_count: usize,
+        sibling_hashes: &[String],
+    ) -> bool {
+        if leaf_index >= leaf_count {
+            return false;
+        }
+
+        let mut current = merkle_leaf_hash(chunk);
+        let mut index = leaf_index;
+        let mut width = leaf_count;
+        let mut siblings = sibling_hashes.iter();
+
+        while width > 1 {
+            if !is_promoted(index, width) {
+                let Some(sibling) = siblings.next() else {
+                    return false;
+                };
+                current = if index.is_multiple_of(2) {
+                    merkle_node_hash(&current, sibling)
+                } else {
+                    merkle_node_hash(sibling, &current)
+                };
+            }
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+
+        siblings.next().is_none() && current == root_hash
+    }
 }
 
 /// Production Hasher with Strategy Selection
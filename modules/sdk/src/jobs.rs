@@ -0,0 +1,318 @@
+//! Shared JobRequest/JobResult encoding for the compute capsule
+//!
+//! Every module that talks to the kernel over the SAB inbox/outbox should go
+//! through these helpers so the framing stays identical on both ends.
+//!
+//! Convention: jobs are written with standard (unpacked) Cap'n Proto framing.
+//! Readers also accept packed framing so results from older producers that
+//! used `serialize_packed` still decode.
+
+use crate::capsule_capnp::compute::{
+    execution_metrics, job_params, job_request, job_result, Status,
+};
+use capnp::message::{Builder, Reader, ReaderOptions};
+use capnp::serialize::{self, OwnedSegments};
+use capnp::serialize_packed;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum JobCodecError {
+    #[error("Cap'n Proto error: {0}")]
+    Capnp(#[from] capnp::Error),
+    #[error("Field {0} is not valid UTF-8")]
+    Utf8(&'static str),
+    #[error("Unknown status value: {0}")]
+    UnknownStatus(u16),
+}
+
+/// JobRequest with its bytes copied out of the message buffer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OwnedJobRequest {
+    pub job_id: String,
+    pub library: String,
+    pub method: String,
+    pub input: Vec<u8>,
+    /// Binary params (JSON by convention); shader source for custom params
+    pub params: Vec<u8>,
+    pub budget: u64,
+    pub priority: u8,
+    pub timeout: u64,
+}
+
+/// JobResult with its bytes copied out of the message buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedJobResult {
+    pub job_id: String,
+    pub status: Status,
+    pub output: Vec<u8>,
+    pub cost: u64,
+    pub execution_time_ns: u64,
+    pub error_message: String,
+    pub retryable: bool,
+    pub metrics: Option<OwnedExecutionMetrics>,
+}
+
+/// ExecutionMetrics with its text copied out of the message buffer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OwnedExecutionMetrics {
+    pub cpu_time_ns: u64,
+    pub gpu_time_ns: u64,
+    pub memory_peak_bytes: u64,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+    pub wall_time_ns: u64,
+    pub service: String,
+    pub action: String,
+}
+
+impl OwnedJobResult {
+    pub fn success(output: Vec<u8>) -> Self {
+        Self {
+            job_id: String::new(),
+            status: Status::Success,
+            output,
+            cost: 0,
+            execution_time_ns: 0,
+            error_message: String::new(),
+            retryable: false,
+            metrics: None,
+        }
+    }
+
+    pub fn failure(error_message: impl Into<String>) -> Self {
+        Self {
+            status: Status::Failed,
+            error_message: error_message.into(),
+            ..Self::success(Vec::new())
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == Status::Success
+    }
+}
+
+/// Read a job message in the canonical unpacked framing, falling back to packed
+pub fn read_job_message(bytes: &[u8]) -> Result<Reader<OwnedSegments>, JobCodecError> {
+    let mut slice = bytes;
+    match serialize::read_message(&mut slice, ReaderOptions::new()) {
+        // A packed stream can parse as a bogus segment table; only trust a full read
+        Ok(message) if slice.is_empty() => Ok(message),
+        unpacked => {
+            let mut slice = bytes;
+            serialize_packed::read_message(&mut slice, ReaderOptions::new())
+                .or_else(|packed_err| unpacked.map_err(|_| packed_err))
+                .map_err(JobCodecError::from)
+        }
+    }
+}
+
+/// Encode a JobRequest with binary params
+pub fn encode_job_request(
+    library: &str,
+    method: &str,
+    input: &[u8],
+    params: &[u8],
+) -> Result<Vec<u8>, JobCodecError> {
+    encode_owned_job_request(&OwnedJobRequest {
+        library: library.to_string(),
+        method: method.to_string(),
+        input: input.to_vec(),
+        params: params.to_vec(),
+        ..Default::default()
+    })
+}
+
+/// Encode every field of an owned JobRequest
+pub fn encode_owned_job_request(request: &OwnedJobRequest) -> Result<Vec<u8>, JobCodecError> {
+    let mut message = Builder::new_default();
+    {
+        let mut root = message.init_root::<job_request::Builder>();
+        root.set_job_id(request.job_id.as_str());
+        root.set_library(request.library.as_str());
+        root.set_method(request.method.as_str());
+        root.set_input(&request.input);
+        root.set_budget(request.budget);
+        root.set_priority(request.priority);
+        root.set_timeout(request.timeout);
+        root.init_params().set_binary(&request.params);
+    }
+
+    let mut bytes = Vec::new();
+    serialize::write_message(&mut bytes, &message)?;
+    Ok(bytes)
+}
+
+/// Decode a JobRequest, copying all fields out of `bytes`
+pub fn decode_job_request(bytes: &[u8]) -> Result<OwnedJobRequest, JobCodecError> {
+    let message = read_job_message(bytes)?;
+    let job = message.get_root::<job_request::Reader>()?;
+
+    let params = match job.get_params()?.which() {
+        Ok(job_params::Which::Binary(data)) => data?.to_vec(),
+        Ok(job_params::Which::CustomParams(custom)) => custom?
+            .get_shader_source()?
+            .to_str()
+            .map_err(|_| JobCodecError::Utf8("shaderSource"))?
+            .as_bytes()
+            .to_vec(),
+        // Other structured params are interpreted by specialised units
+        _ => Vec::new(),
+    };
+
+    Ok(OwnedJobRequest {
+        job_id: text(job.get_job_id()?, "jobId")?,
+        library: text(job.get_library()?, "library")?,
+        method: text(job.get_method()?, "method")?,
+        input: job.get_input()?.to_vec(),
+        params,
+        budget: job.get_budget(),
+        priority: job.get_priority(),
+        timeout: job.get_timeout(),
+    })
+}
+
+/// Encode a JobResult
+pub fn encode_job_result(result: &OwnedJobResult) -> Result<Vec<u8>, JobCodecError> {
+    let mut message = Builder::new_default();
+    {
+        let mut root = message.init_root::<job_result::Builder>();
+        root.set_job_id(result.job_id.as_str());
+        root.set_status(result.status);
+        root.set_output(&result.output);
+        root.set_cost(result.cost);
+        root.set_execution_time_ns(result.execution_time_ns);
+        root.set_error_message(result.error_message.as_str());
+        root.set_retryable(result.retryable);
+        if let Some(metrics) = &result.metrics {
+            encode_metrics(root.init_metrics(), metrics);
+        }
+    }
+
+    let mut bytes = Vec::new();
+    serialize::write_message(&mut bytes, &message)?;
+    Ok(bytes)
+}
+
+/// Decode a JobResult, copying all fields out of `bytes`
+pub fn decode_job_result(bytes: &[u8]) -> Result<OwnedJobResult, JobCodecError> {
+    let message = read_job_message(bytes)?;
+    let result = message.get_root::<job_result::Reader>()?;
+
+    Ok(OwnedJobResult {
+        job_id: text(result.get_job_id()?, "jobId")?,
+        status: result
+            .get_status()
+            .map_err(|capnp::NotInSchema(v)| JobCodecError::UnknownStatus(v))?,
+        output: result.get_output()?.to_vec(),
+        cost: result.get_cost(),
+        execution_time_ns: result.get_execution_time_ns(),
+        error_message: text(result.get_error_message()?, "errorMessage")?,
+        retryable: result.get_retryable(),
+        metrics: if result.has_metrics() {
+            Some(decode_metrics(result.get_metrics()?)?)
+        } else {
+            None
+        },
+    })
+}
+
+fn encode_metrics(mut builder: execution_metrics::Builder<'_>, metrics: &OwnedExecutionMetrics) {
+    builder.set_cpu_time_ns(metrics.cpu_time_ns);
+    builder.set_gpu_time_ns(metrics.gpu_time_ns);
+    builder.set_memory_peak_bytes(metrics.memory_peak_bytes);
+    builder.set_input_bytes(metrics.input_bytes);
+    builder.set_output_bytes(metrics.output_bytes);
+    builder.set_wall_time_ns(metrics.wall_time_ns);
+    builder.set_service(metrics.service.as_str());
+    builder.set_action(metrics.action.as_str());
+}
+
+fn decode_metrics(
+    reader: execution_metrics::Reader<'_>,
+) -> Result<OwnedExecutionMetrics, JobCodecError> {
+    Ok(OwnedExecutionMetrics {
+        cpu_time_ns: reader.get_cpu_time_ns(),
+        gpu_time_ns: reader.get_gpu_time_ns(),
+        memory_peak_bytes: reader.get_memory_peak_bytes(),
+        input_bytes: reader.get_input_bytes(),
+        output_bytes: reader.get_output_bytes(),
+        wall_time_ns: reader.get_wall_time_ns(),
+        service: text(reader.get_service()?, "service")?,
+        action: text(reader.get_action()?, "action")?,
+    })
+}
+
+fn text(reader: capnp::text::Reader<'_>, field: &'static str) -> Result<String, JobCodecError> {
+    reader
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| JobCodecError::Utf8(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_request_roundtrip() {
+        let bytes = encode_job_request("data", "json_read", b"[{\"a\":1}]", b"{}").unwrap();
+        let decoded = decode_job_request(&bytes).unwrap();
+
+        assert_eq!(decoded.library, "data");
+        assert_eq!(decoded.method, "json_read");
+        assert_eq!(decoded.input, b"[{\"a\":1}]");
+        assert_eq!(decoded.params, b"{}");
+    }
+
+    #[test]
+    fn test_job_result_roundtrip() {
+        let mut result = OwnedJobResult::success(vec![1, 2, 3]);
+        result.job_id = "job-7".to_string();
+        result.cost = 42;
+
+        let decoded = decode_job_result(&encode_job_result(&result).unwrap()).unwrap();
+        assert_eq!(decoded, result);
+
+        assert_eq!(decoded.metrics, None);
+
+        let failed = OwnedJobResult::failure("boom");
+        let decoded = decode_job_result(&encode_job_result(&failed).unwrap()).unwrap();
+        assert!(!decoded.is_success());
+        assert_eq!(decoded.error_message, "boom");
+    }
+
+    #[test]
+    fn test_job_result_metrics_roundtrip() {
+        let mut result = OwnedJobResult::success(vec![0u8; 16]);
+        result.execution_time_ns = 1_500;
+        result.metrics = Some(OwnedExecutionMetrics {
+            input_bytes: 64,
+            output_bytes: 16,
+            wall_time_ns: 1_500,
+            service: "data".to_string(),
+            action: "json_read".to_string(),
+            ..Default::default()
+        });
+
+        let decoded = decode_job_result(&encode_job_result(&result).unwrap()).unwrap();
+        assert_eq!(decoded, result);
+    }
+
+    #[test]
+    fn test_packed_result_decodes_with_canonical_reader() {
+        let mut message = Builder::new_default();
+        {
+            let mut root = message.init_root::<job_result::Builder>();
+            root.set_status(Status::Success);
+            root.set_output(&[0u8, 0, 0, 9, 9, 9, 0, 0]);
+            root.set_error_message("");
+        }
+        let mut packed = Vec::new();
+        serialize_packed::write_message(&mut packed, &message).unwrap();
+
+        let decoded = decode_job_result(&packed).unwrap();
+        assert!(decoded.is_success());
+        assert_eq!(decoded.output, vec![0u8, 0, 0, 9, 9, 9, 0, 0]);
+    }
+}
@@ -339,6 +339,8 @@ pub fn get_now() -> f64 {
     }
 }
 
+/// Monotonic milliseconds: `performance.now()` in the browser, time since first
+/// call on native hosts so tests and tools still measure real durations
 pub fn get_performance_now() -> f64 {
     #[cfg(target_arch = "wasm32")]
     unsafe {
@@ -346,7 +348,12 @@ pub fn get_performance_now() -> f64 {
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        0.0
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        EPOCH
+            .get_or_init(std::time::Instant::now)
+            .elapsed()
+            .as_secs_f64()
+            * 1000.0
     }
 }
 
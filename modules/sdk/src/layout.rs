@@ -88,9 +88,19 @@ pub const OFFSET_ARENA: usize = sab::OFFSET_ARENA as usize;
 pub const OFFSET_DIAGNOSTICS: usize = sab::OFFSET_DIAGNOSTICS as usize;
 pub const SIZE_DIAGNOSTICS: usize = sab::SIZE_DIAGNOSTICS as usize;
 
+/// Module heartbeats: 8-byte slots of [last pulse epoch u32 | pulse count u32]
+pub const OFFSET_HEARTBEATS: usize = OFFSET_DIAGNOSTICS;
+pub const HEARTBEAT_SLOT_SIZE: usize = 8;
+pub const MAX_HEARTBEAT_SLOTS: usize =
+    (OFFSET_BRIDGE_METRICS - OFFSET_HEARTBEATS) / HEARTBEAT_SLOT_SIZE;
+
 pub const OFFSET_BRIDGE_METRICS: usize = OFFSET_DIAGNOSTICS + 0x800;
 pub const SIZE_BRIDGE_METRICS: usize = 0x100;
 
+/// Syscall trace ring (see sdk::syscall_trace); fills the rest of the diagnostics region
+pub const OFFSET_SYSCALL_TRACE: usize = OFFSET_BRIDGE_METRICS + SIZE_BRIDGE_METRICS;
+pub const SIZE_SYSCALL_TRACE: usize = OFFSET_DIAGNOSTICS + SIZE_DIAGNOSTICS - OFFSET_SYSCALL_TRACE;
+
 /// Async Request/Response Queues
 pub const OFFSET_ARENA_REQUEST_QUEUE: usize = sab::OFFSET_ARENA_REQUEST_QUEUE as usize;
 pub const OFFSET_ARENA_RESPONSE_QUEUE: usize = sab::OFFSET_ARENA_RESPONSE_QUEUE as usize;
@@ -113,6 +123,10 @@ pub const SIZE_BIRD_STATE: usize = sab::SIZE_BIRD_STATE as usize;
 pub const OFFSET_PINGPONG_CONTROL: usize = sab::OFFSET_PINGPONG_CONTROL as usize;
 pub const SIZE_PINGPONG_CONTROL: usize = sab::SIZE_PINGPONG_CONTROL as usize;
 
+/// Drone telemetry published by the drivers MAVLink socket (see drivers::mavlink)
+pub const OFFSET_DRONE_TELEMETRY: usize = sab::OFFSET_DRONE_TELEMETRY as usize;
+pub const SIZE_DRONE_TELEMETRY: usize = sab::SIZE_DRONE_TELEMETRY as usize;
+
 /// Bird Population Data (Dual Buffers)
 pub const OFFSET_BIRD_BUFFER_A: usize = sab::OFFSET_BIRD_BUFFER_A as usize;
 pub const OFFSET_BIRD_BUFFER_B: usize = sab::OFFSET_BIRD_BUFFER_B as usize;
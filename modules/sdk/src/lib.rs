@@ -3,6 +3,7 @@
 
 pub mod credits;
 pub mod identity;
+pub mod jobs;
 mod logging;
 pub mod signal;
 pub mod social_graph;
@@ -19,6 +20,7 @@ pub mod registry;
 pub mod ringbuffer;
 pub mod sab;
 pub mod shader_registry;
+pub mod syscall_trace;
 pub mod syscalls;
 
 #[cfg(test)]
@@ -83,7 +85,14 @@ pub mod diagnostics_capnp {
 pub mod protocols {
     pub use crate::actor_capnp as actor;
     pub use crate::base_capnp as base;
-    pub use crate::capsule_capnp as compute;
+    pub mod compute {
+        pub use crate::capsule_capnp::*;
+        pub use crate::jobs::{
+            decode_job_request, decode_job_result, encode_job_request, encode_job_result,
+            encode_owned_job_request, read_job_message, JobCodecError, OwnedExecutionMetrics,
+            OwnedJobRequest, OwnedJobResult,
+        };
+    }
     pub use crate::diagnostics_capnp as diagnostics;
     pub use crate::identity_capnp as identity;
     pub use crate::ledger_capnp as economy;
@@ -101,14 +110,15 @@ pub use identity::{
 };
 pub use logging::init_logging;
 pub use shader_registry::{
-    BindingProfile, GpuRequirements, ShaderManifest, ShaderMeta, ShaderRegistry, ValidationMetadata,
+    BindingProfile, DeviceProfile, GpuRequirements, ShaderError, ShaderManifest, ShaderMeta,
+    ShaderPipeline, ShaderRegistry, ValidationMetadata,
 };
 pub use signal::{
     Epoch, Reactor, IDX_ACTOR_EPOCH, IDX_INBOX_DIRTY, IDX_KERNEL_READY, IDX_OUTBOX_HOST_DIRTY,
     IDX_OUTBOX_KERNEL_DIRTY, IDX_PANIC_STATE, IDX_SENSOR_EPOCH, IDX_STORAGE_EPOCH,
     IDX_SYSTEM_EPOCH,
 };
-pub use social_graph::{SocialEntry, SocialGraph};
+pub use social_graph::{EdgeKind, SocialEdge, SocialEntry, SocialGraph};
 
 // Re-export js-sys and JsValue for modules that need JavaScript interop
 pub use crate::js_interop::JsValue;
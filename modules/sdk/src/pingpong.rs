@@ -19,7 +19,12 @@ use crate::layout::{
     OFFSET_BIRD_BUFFER_A, OFFSET_BIRD_BUFFER_B, OFFSET_MATRIX_BUFFER_A, OFFSET_MATRIX_BUFFER_B,
     SIZE_BIRD_BUFFER, SIZE_MATRIX_BUFFER,
 };
+use crate::ringbuffer::{RingBuffer, RingError};
 use crate::sab::SafeSAB;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use thiserror::Error;
 
 /// Ping-pong buffer for zero-allocation data exchange
 #[derive(Clone)]
@@ -203,6 +208,163 @@ impl PingPongBuffer {
     }
 }
 
+/// Errors from a `PingPongRpc` call
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RpcError {
+    #[error("RPC {id} timed out after {timeout_ms}ms")]
+    Timeout { id: u32, timeout_ms: u32 },
+    /// The call was never sent or has already resolved / timed out
+    #[error("No call in flight with id {0}")]
+    UnknownCall(u32),
+    #[error("Malformed RPC frame of {0} bytes")]
+    MalformedFrame(usize),
+    #[error("Ring error: {0}")]
+    Ring(#[from] RingError),
+}
+
+/// Bytes of request id leading every RPC frame
+const RPC_ID_SIZE: usize = 4;
+
+fn encode_rpc_frame(id: u32, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(RPC_ID_SIZE + payload.len());
+    frame.extend_from_slice(&id.to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn decode_rpc_frame(mut frame: Vec<u8>) -> Result<(u32, Vec<u8>), RpcError> {
+    if frame.len() < RPC_ID_SIZE {
+        return Err(RpcError::MalformedFrame(frame.len()));
+    }
+    let id = u32::from_le_bytes(frame[..RPC_ID_SIZE].try_into().unwrap());
+    frame.drain(..RPC_ID_SIZE);
+    Ok((id, frame))
+}
+
+/// Caller side of a request/response channel over two SAB ring buffers.
+///
+/// Every frame is `[request id: u32 LE][payload]`. Responses are matched to
+/// calls by id, so the server may answer in any order and callers never touch
+/// the inbox/outbox dirty flags themselves.
+pub struct PingPongRpc {
+    requests: RingBuffer,
+    responses: RingBuffer,
+    next_id: AtomicU32,
+    /// In-flight calls; `Some` once a response has been drained for that id
+    pending: Mutex<HashMap<u32, Option<Vec<u8>>>>,
+}
+
+impl PingPongRpc {
+    pub fn new(sab: SafeSAB, request_offset: u32, response_offset: u32, ring_size: u32) -> Self {
+        Self {
+            requests: RingBuffer::new(sab.clone(), request_offset, ring_size),
+            responses: RingBuffer::new(sab, response_offset, ring_size),
+            next_id: AtomicU32::new(1),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Send a request and wait for its response
+    pub async fn call(&self, payload: &[u8], timeout_ms: u32) -> Result<Vec<u8>, RpcError> {
+        let id = self.send(payload)?;
+        self.wait(id, timeout_ms).await
+    }
+
+    /// Send a request without waiting; returns the id to `wait` on
+    pub fn send(&self, payload: &[u8]) -> Result<u32, RpcError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock_pending().insert(id, None);
+        if let Err(e) = self
+            .requests
+            .try_write_message(&encode_rpc_frame(id, payload))
+        {
+            self.lock_pending().remove(&id);
+            return Err(e.into());
+        }
+        Ok(id)
+    }
+
+    /// Wait for the response to call `id`, polling with exponential backoff
+    /// On timeout the call is abandoned and a late response is discarded.
+    pub async fn wait(&self, id: u32, timeout_ms: u32) -> Result<Vec<u8>, RpcError> {
+        let start = web_time::Instant::now();
+        let mut backoff = 1u32;
+
+        loop {
+            if let Some(response) = self.try_take(id)? {
+                return Ok(response);
+            }
+
+            if start.elapsed().as_millis() as u32 > timeout_ms {
+                self.lock_pending().remove(&id);
+                return Err(RpcError::Timeout { id, timeout_ms });
+            }
+
+            let delay = backoff.min(16) * 1000;
+            let _ = futures_timer::Delay::new(std::time::Duration::from_micros(delay as u64)).await;
+            backoff = (backoff * 2).min(16);
+        }
+    }
+
+    /// Drain arrived responses and take the one for `id` if it is there
+    pub fn try_take(&self, id: u32) -> Result<Option<Vec<u8>>, RpcError> {
+        let mut pending = self.lock_pending();
+        while let Some(frame) = self.responses.read_message()? {
+            let (response_id, payload) = decode_rpc_frame(frame)?;
+            // Responses to abandoned or unknown calls are dropped
+            if let Some(slot) = pending.get_mut(&response_id) {
+                *slot = Some(payload);
+            }
+        }
+
+        match pending.get(&id) {
+            None => Err(RpcError::UnknownCall(id)),
+            Some(None) => Ok(None),
+            Some(Some(_)) => Ok(pending.remove(&id).flatten()),
+        }
+    }
+
+    /// Number of calls still waiting for a response
+    pub fn in_flight(&self) -> usize {
+        self.lock_pending().len()
+    }
+
+    fn lock_pending(&self) -> std::sync::MutexGuard<'_, HashMap<u32, Option<Vec<u8>>>> {
+        // A poisoned map is still consistent: every update is a single insert/remove
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Serving side of a `PingPongRpc` channel (same offsets, rings swapped)
+pub struct RpcServer {
+    requests: RingBuffer,
+    responses: RingBuffer,
+}
+
+impl RpcServer {
+    pub fn new(sab: SafeSAB, request_offset: u32, response_offset: u32, ring_size: u32) -> Self {
+        Self {
+            requests: RingBuffer::new(sab.clone(), request_offset, ring_size),
+            responses: RingBuffer::new(sab, response_offset, ring_size),
+        }
+    }
+
+    /// Next pending request as (id, payload)
+    pub fn next_request(&self) -> Result<Option<(u32, Vec<u8>)>, RpcError> {
+        match self.requests.read_message()? {
+            Some(frame) => decode_rpc_frame(frame).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Answer request `id`; responses may be sent in any order
+    pub fn respond(&self, id: u32, payload: &[u8]) -> Result<(), RpcError> {
+        self.responses
+            .try_write_message(&encode_rpc_frame(id, payload))?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,4 +402,56 @@ mod tests {
         assert_eq!(bird_buffer.max_items(), 10000);
         assert_eq!(matrix_buffer.max_items(), 80000); // 10k birds * 8 parts
     }
+
+    #[test]
+    fn test_rpc_out_of_order_responses() {
+        let sab = SafeSAB::with_size(64 * 1024);
+        let client = PingPongRpc::new(sab.clone(), 0, 8192, 4096);
+        let server = RpcServer::new(sab, 0, 8192, 4096);
+
+        let first = client.send(b"first").unwrap();
+        let second = client.send(b"second").unwrap();
+        assert_ne!(first, second);
+
+        let mut received = Vec::new();
+        while let Some(request) = server.next_request().unwrap() {
+            received.push(request);
+        }
+        assert_eq!(received.len(), 2);
+
+        // Answer in reverse order of arrival
+        for (id, payload) in received.into_iter().rev() {
+            let mut reply = b"re:".to_vec();
+            reply.extend_from_slice(&payload);
+            server.respond(id, &reply).unwrap();
+        }
+
+        let (a, b) = futures::executor::block_on(futures::future::join(
+            client.wait(first, 1000),
+            client.wait(second, 1000),
+        ));
+        assert_eq!(a.unwrap(), b"re:first");
+        assert_eq!(b.unwrap(), b"re:second");
+        assert_eq!(client.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_rpc_timeout_discards_late_response() {
+        let sab = SafeSAB::with_size(64 * 1024);
+        let client = PingPongRpc::new(sab.clone(), 0, 8192, 4096);
+        let server = RpcServer::new(sab, 0, 8192, 4096);
+
+        let result = futures::executor::block_on(client.call(b"slow", 5));
+        let id = match result {
+            Err(RpcError::Timeout { id, timeout_ms: 5 }) => id,
+            other => panic!("expected timeout, got {:?}", other),
+        };
+
+        let (request_id, _) = server.next_request().unwrap().unwrap();
+        assert_eq!(request_id, id);
+        server.respond(id, b"too late").unwrap();
+
+        assert_eq!(client.try_take(id), Err(RpcError::UnknownCall(id)));
+        assert_eq!(client.in_flight(), 0);
+    }
 }
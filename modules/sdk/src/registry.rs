@@ -1,5 +1,6 @@
 use crate::layout::*;
 use crate::sab::SafeSAB;
+use thiserror::Error;
 
 /// Enhanced Module Registry Entry (96 bytes) - Phase 2
 /// Production-grade with collision handling and extended metadata
@@ -156,6 +157,12 @@ pub struct CapabilityEntry {
 }
 
 pub const CAP_FLAG_REQUIRES_GPU: u8 = 0b0001;
+pub const CAP_FLAG_VERSIONED: u8 = 0b0010;
+
+/// Versioned entries keep [major, minor, patch] in the last bytes of `id`,
+/// behind the null terminator, so readers that stop at the null are unaffected
+const CAP_VERSION_OFFSET: usize = 29;
+pub const MAX_VERSIONED_CAPABILITY_ID: usize = CAP_VERSION_OFFSET - 1;
 
 impl CapabilityEntry {
     pub fn new(id: &str, requires_gpu: bool, min_memory_mb: u16) -> Self {
@@ -177,6 +184,44 @@ impl CapabilityEntry {
 
         entry
     }
+
+    /// Stamp the capability's own protocol version (id truncated to 28 chars)
+    pub fn with_version(mut self, version: (u8, u8, u8)) -> Self {
+        self.id[MAX_VERSIONED_CAPABILITY_ID..].fill(0);
+        self.id[CAP_VERSION_OFFSET..].copy_from_slice(&[version.0, version.1, version.2]);
+        self.flags |= CAP_FLAG_VERSIONED;
+        self
+    }
+
+    /// None for entries written without `with_version`
+    pub fn version(&self) -> Option<(u8, u8, u8)> {
+        if (self.flags & CAP_FLAG_VERSIONED) == 0 {
+            return None;
+        }
+        let v = &self.id[CAP_VERSION_OFFSET..];
+        Some((v[0], v[1], v[2]))
+    }
+
+    pub fn name(&self) -> String {
+        let null_pos = self.id.iter().position(|&b| b == 0).unwrap_or(32);
+        String::from_utf8_lossy(&self.id[..null_pos]).to_string()
+    }
+
+    pub fn requires_gpu(&self) -> bool {
+        (self.flags & CAP_FLAG_REQUIRES_GPU) != 0
+    }
+}
+
+/// One module's offer of a capability, as returned by `find_capability`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityInfo {
+    pub name: String,
+    pub requires_gpu: bool,
+    pub min_memory_mb: u16,
+    /// GPU memory floor from the providing module's resource profile
+    pub min_gpu_memory_mb: u16,
+    /// Capability version, or the module version for unversioned entries
+    pub version: (u8, u8, u8),
 }
 
 // ========== HASHING FUNCTIONS ==========
@@ -247,16 +292,35 @@ pub fn calculate_secondary_hash(module_id: &str) -> usize {
     }
 }
 
+/// Why a module could not be given a registry slot
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// Every inline slot holds a registered module; `compact_registry` can
+    /// reclaim the slots of dead ones
+    #[error("Inline registry full ({0} modules), compact or use arena overflow")]
+    RegistryFull(usize),
+    /// Reading or writing a slot failed; the registry itself may have room
+    #[error("Registry probe failed: {0}")]
+    Probe(String),
+}
+
+/// Slot visited on the given probe attempt (attempt 0 is the primary slot)
+fn probe_slot(primary_slot: usize, secondary_hash: usize, attempt: usize) -> usize {
+    (primary_slot + attempt * secondary_hash) % MAX_MODULES_INLINE
+}
+
 /// Find slot for module using double hashing
-pub fn find_slot_double_hashing(sab: &SafeSAB, module_id: &str) -> Result<(usize, bool), String> {
+pub fn find_slot_double_hashing(
+    sab: &SafeSAB,
+    module_id: &str,
+) -> Result<(usize, bool), RegistryError> {
     let primary_slot = calculate_primary_slot(module_id);
     let secondary_hash = calculate_secondary_hash(module_id);
     let module_hash = crc32c_hash(module_id.as_bytes());
 
-    let mut slot = primary_slot;
-
     for attempt in 0..MAX_PROBE_ATTEMPTS {
-        let entry = read_enhanced_entry(sab, slot)?;
+        let slot = probe_slot(primary_slot, secondary_hash, attempt);
+        let entry = read_enhanced_entry(sab, slot).map_err(RegistryError::Probe)?;
 
         if !entry.is_valid() {
             return Ok((slot, true)); // New registration
@@ -268,11 +332,56 @@ pub fn find_slot_double_hashing(sab: &SafeSAB, module_id: &str) -> Result<(usize
                 return Ok((slot, false)); // Re-registration
             }
         }
+    }
+
+    // The odd step is coprime with the table size, so every slot was visited
+    Err(RegistryError::RegistryFull(MAX_MODULES_INLINE))
+}
+
+/// Drop entries for which `is_dead` holds (e.g. modules that
+/// `Diagnostics::check_liveness` reports dead) and rehash the survivors.
+///
+/// Open addressing cannot simply blank a slot: a later module's probe chain
+/// may run through it. The table is therefore rebuilt in memory and written
+/// back whole. Returns the number of entries removed; callers should
+/// `signal_registry_change` when it is non-zero.
+pub fn compact_registry<F>(sab: &SafeSAB, is_dead: F) -> Result<usize, RegistryError>
+where
+    F: Fn(&EnhancedModuleEntry) -> bool,
+{
+    let mut survivors = Vec::new();
+    let mut removed = 0;
+    for slot in 0..MAX_MODULES_INLINE {
+        let entry = read_enhanced_entry(sab, slot).map_err(RegistryError::Probe)?;
+        if !entry.is_valid() {
+            continue;
+        }
+        if is_dead(&entry) {
+            removed += 1;
+        } else {
+            survivors.push(entry);
+        }
+    }
+    if removed == 0 {
+        return Ok(0);
+    }
 
-        slot = (primary_slot + (attempt + 1) * secondary_hash) % MAX_MODULES_INLINE;
+    let mut table = vec![EnhancedModuleEntry::new(); MAX_MODULES_INLINE];
+    for entry in survivors {
+        let module_id = entry.get_module_id();
+        let primary_slot = calculate_primary_slot(&module_id);
+        let secondary_hash = calculate_secondary_hash(&module_id);
+        let slot = (0..MAX_PROBE_ATTEMPTS)
+            .map(|attempt| probe_slot(primary_slot, secondary_hash, attempt))
+            .find(|&slot| !table[slot].is_valid())
+            .ok_or(RegistryError::RegistryFull(MAX_MODULES_INLINE))?;
+        table[slot] = entry;
     }
 
-    Err("Inline registry full, need arena overflow".to_string())
+    for (slot, entry) in table.iter().enumerate() {
+        write_enhanced_entry(sab, slot, entry).map_err(RegistryError::Probe)?;
+    }
+    Ok(removed)
 }
 
 /// Read enhanced entry from SAB
@@ -391,6 +500,72 @@ pub fn write_capability_table(sab: &SafeSAB, caps: &[CapabilityEntry]) -> Result
     Ok(offset)
 }
 
+/// Read `count` capability entries written by `write_capability_table`
+pub fn read_capability_table(
+    sab: &SafeSAB,
+    offset: u32,
+    count: u16,
+) -> Result<Vec<CapabilityEntry>, String> {
+    if offset == 0 || count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let entry_size = std::mem::size_of::<CapabilityEntry>();
+    let bytes = sab.read(offset as usize, entry_size * count as usize)?;
+
+    Ok(bytes
+        .chunks_exact(entry_size)
+        .map(|chunk| unsafe { std::ptr::read_unaligned(chunk.as_ptr() as *const CapabilityEntry) })
+        .collect())
+}
+
+/// Every active module advertising capability `name`, in slot order
+/// Modules whose capability table cannot be read are skipped.
+pub fn find_capability(sab: &SafeSAB, name: &str) -> Vec<(String, CapabilityInfo)> {
+    let mut providers = Vec::new();
+    for slot in 0..MAX_MODULES_INLINE {
+        let entry = match read_enhanced_entry(sab, slot) {
+            Ok(entry) if entry.is_valid() && entry.is_active() => entry,
+            _ => continue,
+        };
+        let caps = match read_capability_table(sab, entry.cap_table_offset, entry.cap_count) {
+            Ok(caps) => caps,
+            Err(_) => continue,
+        };
+
+        for cap in caps.iter().filter(|cap| cap.name() == name) {
+            providers.push((
+                entry.get_module_id(),
+                CapabilityInfo {
+                    name: cap.name(),
+                    requires_gpu: cap.requires_gpu(),
+                    min_memory_mb: cap.min_memory_mb,
+                    min_gpu_memory_mb: entry.min_gpu_memory_mb,
+                    version: cap.version().unwrap_or((
+                        entry.version_major,
+                        entry.version_minor,
+                        entry.version_patch,
+                    )),
+                },
+            ));
+        }
+    }
+    providers
+}
+
+/// Providers of `name` at `min_version` or newer, so a client is never routed
+/// to a module speaking an older protocol generation
+pub fn require_capability(
+    sab: &SafeSAB,
+    name: &str,
+    min_version: (u8, u8, u8),
+) -> Vec<(String, CapabilityInfo)> {
+    find_capability(sab, name)
+        .into_iter()
+        .filter(|(_, info)| info.version >= min_version)
+        .collect()
+}
+
 // ========== DEPENDENCY TABLE ==========
 
 /// Dependency entry stored in arena (16 bytes)
@@ -534,6 +709,26 @@ impl ModuleEntryBuilder {
         self
     }
 
+    /// Capability whose protocol version differs from the module version
+    pub fn versioned_capability(
+        mut self,
+        id: &str,
+        requires_gpu: bool,
+        min_memory_mb: u16,
+        version: (u8, u8, u8),
+    ) -> Self {
+        if id.len() > MAX_VERSIONED_CAPABILITY_ID {
+            self.validation_errors.push(format!(
+                "Versioned capability '{}' too long (max {} chars)",
+                id, MAX_VERSIONED_CAPABILITY_ID
+            ));
+            return self;
+        }
+        self.capabilities
+            .push(CapabilityEntry::new(id, requires_gpu, min_memory_mb).with_version(version));
+        self
+    }
+
     pub fn validate(&self) -> Result<(), Vec<String>> {
         let mut errors = self.validation_errors.clone();
 
@@ -668,4 +863,164 @@ mod tests {
         assert!(entry.is_active());
         assert_eq!(entry.get_module_id(), "ml");
     }
+
+    fn register(sab: &SafeSAB, id: &str) -> Result<usize, RegistryError> {
+        let (entry, _, _) = ModuleEntryBuilder::new(id).build().unwrap();
+        let (slot, _) = find_slot_double_hashing(sab, id)?;
+        write_enhanced_entry(sab, slot, &entry).map_err(RegistryError::Probe)?;
+        Ok(slot)
+    }
+
+    #[test]
+    fn test_full_registry_reclaims_dead_module_slot() {
+        let sab = SafeSAB::with_size(64 * 1024);
+        let ids: Vec<String> = (0..MAX_MODULES_INLINE)
+            .map(|i| format!("mod{}", i))
+            .collect();
+        for id in &ids {
+            register(&sab, id).unwrap();
+        }
+
+        assert_eq!(
+            register(&sab, "late"),
+            Err(RegistryError::RegistryFull(MAX_MODULES_INLINE))
+        );
+
+        let removed = compact_registry(&sab, |entry| entry.get_module_id() == "mod7").unwrap();
+        assert_eq!(removed, 1);
+        register(&sab, "late").unwrap();
+
+        // Survivors are still reachable along their rehashed probe chains
+        for id in ids.iter().filter(|id| id.as_str() != "mod7") {
+            let (slot, is_new) = find_slot_double_hashing(&sab, id).unwrap();
+            assert!(!is_new, "{} lost by compaction", id);
+            assert_eq!(
+                read_enhanced_entry(&sab, slot).unwrap().get_module_id(),
+                *id
+            );
+        }
+    }
+
+    #[test]
+    fn test_compact_without_dead_modules_is_noop() {
+        let sab = SafeSAB::with_size(64 * 1024);
+        let slot = register(&sab, "gpu").unwrap();
+
+        assert_eq!(compact_registry(&sab, |_| false).unwrap(), 0);
+        assert_eq!(
+            find_slot_double_hashing(&sab, "gpu").unwrap(),
+            (slot, false)
+        );
+    }
+
+    /// Registers `builder` with its capability table at `table_offset`
+    /// (the native arena allocator mock always hands out the same offset)
+    fn register_with_caps(sab: &SafeSAB, builder: ModuleEntryBuilder, table_offset: u32) {
+        let id = builder.id.clone();
+        let (mut entry, _, caps) = builder.build().unwrap();
+        let bytes = unsafe {
+            std::slice::from_raw_parts(caps.as_ptr() as *const u8, std::mem::size_of_val(&caps[..]))
+        };
+        sab.write(table_offset as usize, bytes).unwrap();
+        entry.cap_table_offset = table_offset;
+
+        let (slot, _) = find_slot_double_hashing(sab, &id).unwrap();
+        write_enhanced_entry(sab, slot, &entry).unwrap();
+    }
+
+    #[test]
+    fn test_find_capability_across_modules() {
+        let sab = SafeSAB::with_size(16 * 1024 * 1024);
+        register_with_caps(
+            &sab,
+            ModuleEntryBuilder::new("ml")
+                .capability("inference", false, 512)
+                .capability("tensor", false, 256),
+            OFFSET_ARENA as u32,
+        );
+        register_with_caps(
+            &sab,
+            ModuleEntryBuilder::new("gpu")
+                .resource_profile(ResourceProfile {
+                    min_gpu_memory_mb: 1024,
+                    ..Default::default()
+                })
+                .capability("tensor", true, 128)
+                .capability("render", true, 64),
+            OFFSET_ARENA as u32 + 1024,
+        );
+
+        let mut tensor = find_capability(&sab, "tensor");
+        tensor.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            tensor,
+            vec![
+                (
+                    "gpu".to_string(),
+                    CapabilityInfo {
+                        name: "tensor".to_string(),
+                        requires_gpu: true,
+                        min_memory_mb: 128,
+                        min_gpu_memory_mb: 1024,
+                        version: (1, 0, 0),
+                    }
+                ),
+                (
+                    "ml".to_string(),
+                    CapabilityInfo {
+                        name: "tensor".to_string(),
+                        requires_gpu: false,
+                        min_memory_mb: 256,
+                        min_gpu_memory_mb: 0,
+                        version: (1, 0, 0),
+                    }
+                ),
+            ]
+        );
+
+        let render = find_capability(&sab, "render");
+        assert_eq!(render.len(), 1);
+        assert_eq!(render[0].0, "gpu");
+        assert!(find_capability(&sab, "pow_sha256").is_empty());
+    }
+
+    #[test]
+    fn test_require_capability_filters_old_providers() {
+        let sab = SafeSAB::with_size(16 * 1024 * 1024);
+        register_with_caps(
+            &sab,
+            ModuleEntryBuilder::new("legacy")
+                .version(2, 0, 0)
+                .versioned_capability("simulation", false, 256, (1, 0, 0)),
+            OFFSET_ARENA as u32,
+        );
+        register_with_caps(
+            &sab,
+            ModuleEntryBuilder::new("physics")
+                .version(1, 9, 2)
+                .capability("simulation", false, 256),
+            OFFSET_ARENA as u32 + 1024,
+        );
+
+        assert_eq!(find_capability(&sab, "simulation").len(), 2);
+
+        let providers = require_capability(&sab, "simulation", (1, 9, 0));
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].0, "physics");
+        assert_eq!(providers[0].1.version, (1, 9, 2));
+
+        let legacy = require_capability(&sab, "simulation", (1, 0, 0));
+        let legacy = legacy.iter().find(|(id, _)| id == "legacy").unwrap();
+        assert_eq!(legacy.1.name, "simulation");
+        assert_eq!(legacy.1.version, (1, 0, 0));
+    }
+
+    #[test]
+    fn test_versioned_capability_id_limit() {
+        let long = "a".repeat(MAX_VERSIONED_CAPABILITY_ID + 1);
+        let result = ModuleEntryBuilder::new("ml")
+            .versioned_capability(&long, false, 0, (1, 0, 0))
+            .build();
+        assert!(result.is_err());
+    }
 }
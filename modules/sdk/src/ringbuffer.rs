@@ -1,4 +1,24 @@
 use crate::sab::SafeSAB;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RingError {
+    /// The frame fits the buffer but would lap unread data; `needed` bytes must be free
+    #[error("Write would overwrite unread data: need {needed} bytes, {free} free")]
+    WouldOverwrite { needed: u32, free: u32 },
+    /// The frame can never fit, even in an empty buffer
+    #[error("Frame of {needed} bytes exceeds ring capacity {capacity}")]
+    TooLarge { needed: u32, capacity: u32 },
+    /// The frame was written by a producer speaking another framing version
+    /// The frame is consumed so the reader can move on to the next one.
+    #[error("Unsupported frame version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("SAB error: {0}")]
+    Sab(String),
+}
+
+/// Version byte that leads the body of every `write_message` frame
+pub const FRAME_VERSION: u8 = 1;
 
 /// Generic Ring Buffer backed by SharedArrayBuffer
 /// Layout: [Head (4 bytes) | Tail (4 bytes) | Data (Capacity - 8 bytes)]
@@ -23,32 +43,89 @@ impl RingBuffer {
         }
     }
 
-    /// Write a framed message [Length: u32][Data...]
+    /// Write a framed message [Length: u32][Version: u8][Data...]
     /// Multi-Producer Safe: Uses atomic reservation and commitment.
+    /// Returns `Ok(false)` when there is no room; see `try_write_message` for why.
     pub fn write_message(&self, data: &[u8]) -> Result<bool, String> {
-        let msg_len = data.len() as u32;
+        match self.try_write_message(data) {
+            Ok(_) => Ok(true),
+            Err(RingError::Sab(e)) => Err(e),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Write a framed message, returning the frame size on success
+    /// On failure the error says how many bytes the frame needs so producers
+    /// can decide to wait for the consumer or chunk the payload.
+    pub fn try_write_message(&self, data: &[u8]) -> Result<u32, RingError> {
+        self.write_frame(Some(FRAME_VERSION), data)
+    }
+
+    /// Write an unversioned [Length: u32][Data...] frame for peers that predate `FRAME_VERSION`
+    pub fn write_message_legacy(&self, data: &[u8]) -> Result<u32, RingError> {
+        self.write_frame(None, data)
+    }
+
+    fn write_frame(&self, version: Option<u8>, data: &[u8]) -> Result<u32, RingError> {
+        let prefix_len = version.is_some() as u32;
+        let msg_len = prefix_len + data.len() as u32;
         let total_len = 4 + msg_len;
 
+        if total_len > self.capacity() {
+            return Err(RingError::TooLarge {
+                needed: total_len,
+                capacity: self.capacity(),
+            });
+        }
+
         // 1. Reserve space atomically
-        let start_tail = self.reserve_space(total_len)?;
+        let start_tail = self.reserve_space(total_len).map_err(RingError::Sab)?;
         if start_tail == 0xFFFFFFFF {
-            return Ok(false); // No space
+            return Err(RingError::WouldOverwrite {
+                needed: total_len,
+                free: self.free_bytes(),
+            });
         }
 
-        // 2. Write Data first (skipping the 4-byte length header)
+        // 2. Write Version + Data first (skipping the 4-byte length header)
         let data_start = (start_tail + 4) % self.data_capacity;
-        self.write_raw_at(data_start, data)?;
+        if let Some(version) = version {
+            self.write_raw_at(data_start, &[version])
+                .map_err(RingError::Sab)?;
+        }
+        self.write_raw_at((data_start + prefix_len) % self.data_capacity, data)
+            .map_err(RingError::Sab)?;
 
         // 3. Commit: Write Length Header LAST
         let len_bytes = msg_len.to_le_bytes();
-        self.write_raw_at(start_tail, &len_bytes)?;
+        self.write_raw_at(start_tail, &len_bytes)
+            .map_err(RingError::Sab)?;
 
-        Ok(true)
+        Ok(total_len)
     }
 
-    /// Read next framed message
+    /// Read next framed message, checking its version byte
     /// Multi-Producer Safe: Only reads if length header is non-zero (committed).
-    pub fn read_message(&self) -> Result<Option<Vec<u8>>, String> {
+    pub fn read_message(&self) -> Result<Option<Vec<u8>>, RingError> {
+        let Some(mut body) = self.read_frame()? else {
+            return Ok(None);
+        };
+
+        match body[0] {
+            FRAME_VERSION => {
+                body.remove(0);
+                Ok(Some(body))
+            }
+            version => Err(RingError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Read next unversioned frame written by `write_message_legacy`
+    pub fn read_message_legacy(&self) -> Result<Option<Vec<u8>>, RingError> {
+        self.read_frame()
+    }
+
+    fn read_frame(&self) -> Result<Option<Vec<u8>>, RingError> {
         let head = self.load_head();
         let tail = self.load_tail();
 
@@ -58,7 +135,8 @@ impl RingBuffer {
 
         // Peek length (without moving head)
         let mut len_bytes = [0u8; 4];
-        self.peek_raw_at(head, &mut len_bytes)?;
+        self.peek_raw_at(head, &mut len_bytes)
+            .map_err(RingError::Sab)?;
         let msg_len = u32::from_le_bytes(len_bytes);
 
         if msg_len == 0 {
@@ -67,14 +145,21 @@ impl RingBuffer {
             return Ok(None);
         }
 
+        if self.available() < 4 + msg_len {
+            // Truncated frame: never advance past a header whose body isn't there.
+            return Ok(None);
+        }
+
         // Consume Length + Data
         let mut msg_data = vec![0u8; msg_len as usize];
         let data_start = (head + 4) % self.data_capacity;
-        self.read_raw_at(data_start, &mut msg_data)?;
+        self.read_raw_at(data_start, &mut msg_data)
+            .map_err(RingError::Sab)?;
 
         // CLEAR HEADER to 0 to prevent stale reads on wrap-around
         let zero_bytes = [0u8; 4];
-        self.write_raw_at(head, &zero_bytes)?;
+        self.write_raw_at(head, &zero_bytes)
+            .map_err(RingError::Sab)?;
 
         // Advance Head
         self.store_head((head + 4 + msg_len) % self.data_capacity);
@@ -82,6 +167,23 @@ impl RingBuffer {
         Ok(Some(msg_data))
     }
 
+    /// Drain every committed message currently in the buffer
+    /// Stops at the first empty, uncommitted, or unreadable frame.
+    pub fn drain(&self) -> Drain<'_> {
+        Drain {
+            rb: self,
+            legacy: false,
+        }
+    }
+
+    /// Drain every committed unversioned frame currently in the buffer
+    pub fn drain_legacy(&self) -> Drain<'_> {
+        Drain {
+            rb: self,
+            legacy: true,
+        }
+    }
+
     /// Read raw bytes (stream mode)
     /// Returns bytes read
     pub fn read(&self, buf: &mut [u8]) -> Result<usize, String> {
@@ -138,11 +240,10 @@ impl RingBuffer {
             }
 
             let new_tail = (tail + amount) % self.data_capacity;
-            let (view_val, _) = self.get_sab_view();
             let idx = (self.base_offset + Self::TAIL_OFFSET) / 4;
 
             let actual_old = crate::js_interop::atomic_compare_exchange(
-                &view_val,
+                self.sab.barrier_view(),
                 idx,
                 tail as i32,
                 new_tail as i32,
@@ -204,6 +305,17 @@ impl RingBuffer {
         self.read_raw_at(offset, buf) // Peek in ring buffer is just read without moving head
     }
 
+    /// Largest frame (length header included) the buffer can ever hold
+    /// One byte stays reserved so a full buffer is distinguishable from an empty one.
+    pub fn capacity(&self) -> u32 {
+        self.data_capacity - 1
+    }
+
+    /// Bytes that can be written right now without lapping the reader
+    pub fn free_bytes(&self) -> u32 {
+        self.capacity().saturating_sub(self.available())
+    }
+
     /// Available bytes to read
     pub fn available(&self) -> u32 {
         let head = self.load_head();
@@ -216,36 +328,44 @@ impl RingBuffer {
         }
     }
 
-    fn get_sab_view(&self) -> (crate::js_interop::Int32Array, u32) {
-        let buffer = self.sab.inner();
-        let length = crate::js_interop::get_byte_length(buffer);
-        let view = crate::js_interop::create_i32_view(buffer, 0, length / 4);
-        (view, length / 4)
-    }
-
     fn load_head(&self) -> u32 {
-        let (view_val, _) = self.get_sab_view();
         let idx = (self.base_offset + Self::HEAD_OFFSET) / 4;
-        let val = crate::js_interop::atomic_load(&view_val, idx);
+        let val = crate::js_interop::atomic_load(self.sab.barrier_view(), idx);
         val as u32
     }
 
     fn store_head(&self, val: u32) {
-        let (view_val, _) = self.get_sab_view();
         let idx = (self.base_offset + Self::HEAD_OFFSET) / 4;
-        crate::js_interop::atomic_store(&view_val, idx, val as i32);
+        crate::js_interop::atomic_store(self.sab.barrier_view(), idx, val as i32);
     }
 
     fn load_tail(&self) -> u32 {
-        let (view_val, _) = self.get_sab_view();
         let idx = (self.base_offset + Self::TAIL_OFFSET) / 4;
-        let val = crate::js_interop::atomic_load(&view_val, idx);
+        let val = crate::js_interop::atomic_load(self.sab.barrier_view(), idx);
         val as u32
     }
 
     fn _store_tail(&self, val: u32) {
-        let (view_val, _) = self.get_sab_view();
         let idx = (self.base_offset + Self::TAIL_OFFSET) / 4;
-        crate::js_interop::atomic_store(&view_val, idx, val as i32);
+        crate::js_interop::atomic_store(self.sab.barrier_view(), idx, val as i32);
+    }
+}
+
+/// Iterator returned by [`RingBuffer::drain`]
+pub struct Drain<'a> {
+    rb: &'a RingBuffer,
+    legacy: bool,
+}
+
+impl Iterator for Drain<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = if self.legacy {
+            self.rb.read_message_legacy()
+        } else {
+            self.rb.read_message()
+        };
+        next.ok().flatten()
     }
 }
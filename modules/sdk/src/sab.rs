@@ -152,6 +152,46 @@ impl SafeSAB {
         self.base_offset
     }
 
+    /// True for views created by `new_shared_view` that start past byte 0
+    pub fn is_scoped(&self) -> bool {
+        self.base_offset != 0
+    }
+
+    /// Translate a view-relative offset into an absolute SAB layout offset
+    pub fn to_absolute(&self, offset: usize) -> Result<usize, String> {
+        if offset >= self.capacity {
+            return Err(format!(
+                "Relative offset 0x{:x} outside view of {} bytes",
+                offset, self.capacity
+            ));
+        }
+        Ok(self.base_offset + offset)
+    }
+
+    /// Translate an absolute SAB layout offset into this view's coordinates.
+    /// Fails when the offset lies outside the window, which is how writing
+    /// a global layout constant through the wrong view is caught.
+    pub fn to_relative(&self, abs_offset: usize) -> Result<usize, String> {
+        let window_end = self.base_offset + self.capacity;
+        if abs_offset < self.base_offset || abs_offset >= window_end {
+            return Err(format!(
+                "Absolute offset 0x{:x} outside view window 0x{:x}..0x{:x}",
+                abs_offset, self.base_offset, window_end
+            ));
+        }
+        Ok(abs_offset - self.base_offset)
+    }
+
+    /// Read using an absolute layout offset (e.g. a `layout::OFFSET_*` constant)
+    pub fn read_absolute(&self, abs_offset: usize, length: usize) -> Result<Vec<u8>, String> {
+        self.read(self.to_relative(abs_offset)?, length)
+    }
+
+    /// Write using an absolute layout offset (e.g. a `layout::OFFSET_*` constant)
+    pub fn write_absolute(&self, abs_offset: usize, data: &[u8]) -> Result<usize, String> {
+        self.write(self.to_relative(abs_offset)?, data)
+    }
+
     /// Safe read from buffer with memory barriers
     pub fn read(&self, offset: usize, length: usize) -> Result<Vec<u8>, String> {
         self.bounds_check(offset, length)?;
@@ -235,13 +275,17 @@ impl SafeSAB {
     }
 
     fn bounds_check(&self, offset: usize, length: usize) -> Result<(), String> {
-        if offset + length > self.capacity {
-            return Err(format!(
+        match offset.checked_add(length) {
+            Some(end) if end <= self.capacity => Ok(()),
+            _ if self.is_scoped() => Err(format!(
+                "Out of bounds: {} + {} > {} (view at 0x{:x}; absolute offsets need to_relative)",
+                offset, length, self.capacity, self.base_offset
+            )),
+            _ => Err(format!(
                 "Out of bounds: {} + {} > {}",
                 offset, length, self.capacity
-            ));
+            )),
         }
-        Ok(())
     }
 
     fn memory_barrier_acquire(&self, offset: usize) {
@@ -295,6 +339,72 @@ impl SafeSAB {
         ))
     }
 
+    /// Copy a plain-old-data struct out of the buffer
+    /// Errors instead of reading past the end if `offset` is too close to it.
+    pub fn read_struct<T: bytemuck::Pod>(&self, offset: usize) -> Result<T, String> {
+        self.struct_check::<T>(offset)?;
+        let bytes = self.read(offset, std::mem::size_of::<T>())?;
+        Ok(bytemuck::pod_read_unaligned(&bytes))
+    }
+
+    /// Copy a plain-old-data struct into the buffer
+    pub fn write_struct<T: bytemuck::Pod>(&self, offset: usize, value: &T) -> Result<(), String> {
+        self.struct_check::<T>(offset)?;
+        self.write_raw(offset, bytemuck::bytes_of(value))
+    }
+
+    /// Size and alignment checks shared by the struct accessors
+    fn struct_check<T>(&self, offset: usize) -> Result<(), String> {
+        let size = std::mem::size_of::<T>();
+        let align = std::mem::align_of::<T>();
+        match offset.checked_add(size) {
+            Some(end) if end <= self.capacity => {}
+            _ => {
+                return Err(format!(
+                    "Struct of {} bytes at offset {} exceeds capacity {}",
+                    size, offset, self.capacity
+                ))
+            }
+        }
+        if !(self.base_offset + offset).is_multiple_of(align) {
+            return Err(format!(
+                "Offset {} is not {}-byte aligned for struct access",
+                offset, align
+            ));
+        }
+        Ok(())
+    }
+
+    /// Atomically replace the i32 at `offset` with `new` if it equals `expected`
+    /// Returns true if the swap happened, false if another writer got there first.
+    pub fn compare_exchange_i32(
+        &self,
+        offset: usize,
+        expected: i32,
+        new: i32,
+    ) -> Result<bool, String> {
+        let index = self.atomic_index(offset)?;
+        let observed =
+            crate::js_interop::atomic_compare_exchange(&self.barrier_view, index, expected, new);
+        Ok(observed == expected)
+    }
+
+    /// Atomically load the i32 at `offset`
+    pub fn load_i32(&self, offset: usize) -> Result<i32, String> {
+        let index = self.atomic_index(offset)?;
+        Ok(crate::js_interop::atomic_load(&self.barrier_view, index))
+    }
+
+    /// Index into the full-buffer barrier view for a 4-byte-aligned view offset
+    fn atomic_index(&self, offset: usize) -> Result<u32, String> {
+        self.bounds_check(offset, 4)?;
+        let abs_offset = self.base_offset + offset;
+        if (abs_offset & 3) != 0 {
+            return Err("Offset must be 4-byte aligned for atomic access".to_string());
+        }
+        Ok((abs_offset / 4) as u32)
+    }
+
     // ========== SAB REGION CONSTANTS ==========
     // Delegated to crate::layout for single source of truth
     pub const OFFSET_ECONOMICS: usize = crate::layout::OFFSET_ECONOMICS;
@@ -623,4 +733,80 @@ mod tests {
         let read_data = tensor.read_tensor(4).unwrap();
         assert_eq!(read_data, data);
     }
+
+    #[test]
+    fn test_compare_exchange_i32() {
+        let sab = SafeSAB::with_size(1024);
+        sab.write(64, &7i32.to_le_bytes()).unwrap();
+
+        // Stale expectation leaves the value alone
+        assert!(!sab.compare_exchange_i32(64, 0, 1).unwrap());
+        assert_eq!(sab.load_i32(64).unwrap(), 7);
+
+        assert!(sab.compare_exchange_i32(64, 7, 42).unwrap());
+        assert_eq!(sab.load_i32(64).unwrap(), 42);
+
+        // A second claimant observing the old value loses
+        assert!(!sab.compare_exchange_i32(64, 7, 99).unwrap());
+        assert_eq!(sab.load_i32(64).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_compare_exchange_i32_rejects_bad_offsets() {
+        let sab = SafeSAB::with_size(1024);
+        assert!(sab.compare_exchange_i32(66, 0, 1).is_err());
+        assert!(sab.compare_exchange_i32(1024, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_struct_round_trip() {
+        let sab = SafeSAB::with_size(256);
+        let state: [f32; 4] = [1.0, -2.5, 3.25, 0.0];
+        sab.write_struct(64, &state).unwrap();
+        assert_eq!(sab.read_struct::<[f32; 4]>(64).unwrap(), state);
+    }
+
+    #[test]
+    fn test_read_struct_near_end_errors() {
+        let sab = SafeSAB::with_size(256);
+        // 16-byte struct starting 8 bytes before the end must not alias past it
+        assert!(sab.read_struct::<[f32; 4]>(248).is_err());
+        assert!(sab.write_struct(248, &[0f32; 4]).is_err());
+        assert!(sab.read_struct::<[f32; 4]>(usize::MAX - 4).is_err());
+        // Misaligned for f32
+        assert!(sab.read_struct::<[f32; 4]>(66).is_err());
+        assert!(sab.read_struct::<[f32; 2]>(248).is_ok());
+    }
+
+    #[test]
+    fn test_scoped_view_offset_translation() {
+        let base = SafeSAB::with_size(4096);
+        let view = SafeSAB::new_shared_view(base.inner(), 1024, 256);
+        assert!(view.is_scoped());
+        assert!(!base.is_scoped());
+
+        assert_eq!(view.to_absolute(10).unwrap(), 1034);
+        assert_eq!(view.to_relative(1034).unwrap(), 10);
+        assert!(view.to_absolute(256).is_err());
+
+        view.write_absolute(1040, b"scoped").unwrap();
+        assert_eq!(base.read(1040, 6).unwrap(), b"scoped");
+        assert_eq!(view.read(16, 6).unwrap(), b"scoped");
+    }
+
+    #[test]
+    fn test_scoped_view_rejects_out_of_window_access() {
+        let base = SafeSAB::with_size(4096);
+        let view = SafeSAB::new_shared_view(base.inner(), 1024, 256);
+
+        // Absolute offsets before or after the window
+        assert!(view.to_relative(512).is_err());
+        assert!(view.to_relative(1024 + 256).is_err());
+        assert!(view.write_absolute(2048, b"x").is_err());
+        assert!(view.read_absolute(0, 4).is_err());
+
+        // An absolute offset mistakenly used as a relative one
+        assert!(view.write(1040, b"x").is_err());
+        assert!(view.read(usize::MAX, 2).is_err());
+    }
 }
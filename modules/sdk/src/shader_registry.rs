@@ -1,5 +1,7 @@
 use crate::js_interop;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
 
 /// Machine-readable manifest for a decentralized shader
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -17,6 +19,9 @@ pub struct ShaderMeta {
     pub author: String,
     pub description: String,
     pub license: String,
+    /// Filled in by `ShaderRegistry::validate_wgsl`
+    #[serde(default)]
+    pub entry_points: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -40,12 +45,220 @@ pub struct BindingProfile {
     pub access: String,        // "read", "write", "read_write"
 }
 
+/// What a device offers, matched against a shader's `GpuRequirements`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeviceProfile {
+    pub architecture: String,
+    pub max_workgroup_size: [u32; 3],
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ShaderError {
+    #[error("Shader manifest has no name")]
+    MissingName,
+    #[error("Shader hash mismatch: manifest {expected}, source {actual}")]
+    HashMismatch { expected: String, actual: String },
+    #[error("Binding profile mismatch: {0}")]
+    BindingMismatch(String),
+    /// Parse or validation failure, prefixed with `line L:C` when known
+    #[error("Invalid WGSL: {0}")]
+    InvalidWgsl(String),
+    #[error("Unknown shader: {0}")]
+    NotFound(String),
+    #[error("Device does not meet shader requirements: {0}")]
+    Unsupported(String),
+}
+
+/// A registered shader, ready for the host to build a pipeline from
+#[derive(Debug, Clone)]
+pub struct ShaderPipeline<'a> {
+    pub manifest: &'a ShaderManifest,
+    pub wgsl: &'a str,
+}
+
 /// Registry for managing decentralized shader manifests
-pub struct ShaderRegistry;
+#[derive(Default)]
+pub struct ShaderRegistry {
+    shaders: HashMap<String, (ShaderManifest, String)>,
+}
 
 impl ShaderRegistry {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Validate `wgsl` against its manifest and store it under `manifest.meta.name`.
+    /// Re-registering a name hot-swaps the shader; a rejected shader leaves
+    /// the previous one in place.
+    ///
+    /// An empty `validation.hash` is filled in; a non-empty one must match the
+    /// BLAKE3 of the source. `bindings` must list exactly the source's
+    /// `@group/@binding` declarations with the same access. With the `wgsl`
+    /// feature the source must also pass `validate_wgsl`, whose parsed layout
+    /// is what the bindings are checked against.
+    pub fn register_shader(
+        &mut self,
+        mut manifest: ShaderManifest,
+        wgsl: &str,
+    ) -> Result<(), ShaderError> {
+        if manifest.meta.name.is_empty() {
+            return Err(ShaderError::MissingName);
+        }
+
+        #[cfg(feature = "wgsl")]
+        let mut declared: Vec<_> = self
+            .validate_wgsl(wgsl)?
+            .bindings
+            .into_iter()
+            .map(|b| (b.group, b.binding, b.access))
+            .collect();
+        #[cfg(not(feature = "wgsl"))]
+        let mut declared = declared_bindings(wgsl);
+
+        let actual = blake3::hash(wgsl.as_bytes()).to_string();
+        if manifest.validation.hash.is_empty() {
+            manifest.validation.hash = actual;
+        } else if manifest.validation.hash != actual {
+            return Err(ShaderError::HashMismatch {
+                expected: manifest.validation.hash,
+                actual,
+            });
+        }
+
+        let mut profiled: Vec<_> = manifest
+            .bindings
+            .iter()
+            .map(|b| (b.group, b.binding, b.access.clone()))
+            .collect();
+        declared.sort();
+        profiled.sort();
+        if declared != profiled {
+            return Err(ShaderError::BindingMismatch(format!(
+                "manifest declares {:?}, shader declares {:?}",
+                profiled, declared
+            )));
+        }
+
+        self.shaders
+            .insert(manifest.meta.name.clone(), (manifest, wgsl.to_string()));
+        Ok(())
+    }
+
+    /// Parse and validate `source` with naga before any pipeline is created,
+    /// so a typo surfaces here with its line instead of as a GPU-side panic.
+    ///
+    /// Returns a manifest derived from the source: entry points, binding
+    /// layout, the first compute workgroup size and the source hash.
+    #[cfg(feature = "wgsl")]
+    pub fn validate_wgsl(&self, source: &str) -> Result<ShaderManifest, ShaderError> {
+        use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+        let module = naga::front::wgsl::parse_str(source)
+            .map_err(|e| invalid_wgsl(e.location(source), e.message()))?;
+        Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .map_err(|e| invalid_wgsl(e.location(source), &e.as_inner().to_string()))?;
+
+        let bindings = module
+            .global_variables
+            .iter()
+            .filter_map(|(_, var)| {
+                let binding = var.binding.as_ref()?;
+                let resource_type = match (var.space, &module.types[var.ty].inner) {
+                    (naga::AddressSpace::Uniform | naga::AddressSpace::Storage { .. }, _) => {
+                        "buffer"
+                    }
+                    (_, naga::TypeInner::Image { .. }) => "texture",
+                    (_, naga::TypeInner::Sampler { .. }) => "sampler",
+                    _ => "unknown",
+                };
+                let access = match var.space {
+                    naga::AddressSpace::Storage { access }
+                        if access.contains(naga::StorageAccess::STORE) =>
+                    {
+                        if access.contains(naga::StorageAccess::LOAD) {
+                            "read_write"
+                        } else {
+                            "write"
+                        }
+                    }
+                    _ => "read",
+                };
+                Some(BindingProfile {
+                    group: binding.group,
+                    binding: binding.binding,
+                    resource_type: resource_type.to_string(),
+                    access: access.to_string(),
+                })
+            })
+            .collect();
+
+        let min_workgroup_size = module
+            .entry_points
+            .iter()
+            .find(|ep| ep.stage == naga::ShaderStage::Compute)
+            .map_or([1, 1, 1], |ep| ep.workgroup_size);
+
+        Ok(ShaderManifest {
+            meta: ShaderMeta {
+                entry_points: module
+                    .entry_points
+                    .iter()
+                    .map(|ep| ep.name.clone())
+                    .collect(),
+                ..Default::default()
+            },
+            requirements: GpuRequirements {
+                architectures: vec!["webgpu".to_string()],
+                min_workgroup_size,
+            },
+            validation: ValidationMetadata {
+                hash: blake3::hash(source.as_bytes()).to_string(),
+                signature: String::new(),
+                timestamp: js_interop::get_now() as u64,
+            },
+            bindings,
+        })
+    }
+
+    /// Look up a shader for pipeline creation on `device`
+    pub fn get_pipeline(
+        &self,
+        device: &DeviceProfile,
+        name: &str,
+    ) -> Result<ShaderPipeline<'_>, ShaderError> {
+        let (manifest, wgsl) = self
+            .shaders
+            .get(name)
+            .ok_or_else(|| ShaderError::NotFound(name.to_string()))?;
+
+        let requirements = &manifest.requirements;
+        if !requirements.architectures.is_empty()
+            && !requirements.architectures.contains(&device.architecture)
+        {
+            return Err(ShaderError::Unsupported(format!(
+                "architecture {} not in {:?}",
+                device.architecture, requirements.architectures
+            )));
+        }
+        let fits = requirements
+            .min_workgroup_size
+            .iter()
+            .zip(device.max_workgroup_size.iter())
+            .all(|(needed, max)| needed <= max);
+        if !fits {
+            return Err(ShaderError::Unsupported(format!(
+                "workgroup {:?} exceeds device limit {:?}",
+                requirements.min_workgroup_size, device.max_workgroup_size
+            )));
+        }
+
+        Ok(ShaderPipeline { manifest, wgsl })
+    }
+
+    /// Names of all registered shaders
+    pub fn shader_names(&self) -> impl Iterator<Item = &str> {
+        self.shaders.keys().map(String::as_str)
     }
 
     /// Sign a manifest with a private key (placeholder for actual crypto integration)
@@ -66,3 +279,302 @@ impl ShaderRegistry {
         !manifest.validation.signature.is_empty()
     }
 }
+
+#[cfg(feature = "wgsl")]
+fn invalid_wgsl(location: Option<naga::SourceLocation>, message: &str) -> ShaderError {
+    ShaderError::InvalidWgsl(match location {
+        Some(loc) => format!(
+            "line {}:{}: {}",
+            loc.line_number, loc.line_position, message
+        ),
+        None => message.to_string(),
+    })
+}
+
+/// `@group(g) @binding(b) var<space, access>` declarations as (group, binding, access)
+/// Access uses the `BindingProfile` vocabulary; non-storage bindings are "read".
+/// Text scan for builds without naga; the attributes may come in either order.
+#[cfg(any(not(feature = "wgsl"), test))]
+fn declared_bindings(wgsl: &str) -> Vec<(u32, u32, String)> {
+    let source = strip_comments(wgsl);
+
+    let mut bindings = Vec::new();
+    let mut rest = source.as_str();
+    while let Some(pos) = rest.find('@') {
+        // Walk the run of attributes in front of one declaration
+        rest = &rest[pos..];
+        let (mut group, mut binding) = (None, None);
+        while let Some(attr) = rest.strip_prefix('@') {
+            let name_len = attr
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(attr.len());
+            let (name, after) = attr.split_at(name_len);
+            rest = after.trim_start();
+            if let Some(args) = rest.strip_prefix('(') {
+                let end = args.find(')').unwrap_or(args.len());
+                let value = args[..end].trim().parse().ok();
+                match name {
+                    "group" => group = value,
+                    "binding" => binding = value,
+                    _ => {}
+                }
+                rest = args.get(end + 1..).unwrap_or("").trim_start();
+            }
+        }
+        let (Some(group), Some(binding)) = (group, binding) else {
+            continue;
+        };
+
+        let access = match rest.strip_prefix("var<") {
+            Some(decl) => {
+                let params = decl.split('>').next().unwrap_or("");
+                let mut parts = params.split(',').map(str::trim);
+                match (parts.next(), parts.next()) {
+                    (Some("storage"), Some("read_write")) => "read_write",
+                    (Some("storage"), Some("write")) => "write",
+                    _ => "read",
+                }
+            }
+            None => "read",
+        };
+        bindings.push((group, binding, access.to_string()));
+    }
+    bindings
+}
+
+/// `wgsl` with `//` and (nested) `/* */` comments replaced by spaces
+#[cfg(any(not(feature = "wgsl"), test))]
+fn strip_comments(wgsl: &str) -> String {
+    let mut out = String::with_capacity(wgsl.len());
+    let mut chars = wgsl.chars().peekable();
+    let mut depth = 0usize;
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('*')) => {
+                chars.next();
+                depth += 1;
+                out.push(' ');
+            }
+            ('*', Some('/')) if depth > 0 => {
+                chars.next();
+                depth -= 1;
+            }
+            ('/', Some('/')) if depth == 0 => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+                out.push(' ');
+            }
+            _ if depth > 0 => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHADER: &str = "
+        struct Params { scale: f32 }
+        @group(0) @binding(0) var<uniform> params: Params; // sizes
+        @group(0) @binding(1) var<storage, read> input: array<f32>;
+        @group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            output[id.x] = input[id.x] * params.scale;
+        }
+    ";
+
+    fn binding(binding: u32, access: &str) -> BindingProfile {
+        BindingProfile {
+            group: 0,
+            binding,
+            resource_type: "buffer".to_string(),
+            access: access.to_string(),
+        }
+    }
+
+    fn manifest(name: &str, bindings: Vec<BindingProfile>) -> ShaderManifest {
+        ShaderManifest {
+            meta: ShaderMeta {
+                name: name.to_string(),
+                ..Default::default()
+            },
+            requirements: GpuRequirements {
+                architectures: vec!["webgpu".to_string()],
+                min_workgroup_size: [64, 1, 1],
+            },
+            validation: ValidationMetadata::default(),
+            bindings,
+        }
+    }
+
+    fn webgpu() -> DeviceProfile {
+        DeviceProfile {
+            architecture: "webgpu".to_string(),
+            max_workgroup_size: [256, 256, 64],
+        }
+    }
+
+    #[test]
+    fn test_register_and_get_pipeline() {
+        let mut registry = ShaderRegistry::new();
+        let bindings = vec![
+            binding(0, "read"),
+            binding(1, "read"),
+            binding(2, "read_write"),
+        ];
+        registry
+            .register_shader(manifest("scale", bindings), SHADER)
+            .unwrap();
+
+        let pipeline = registry.get_pipeline(&webgpu(), "scale").unwrap();
+        assert_eq!(pipeline.wgsl, SHADER);
+        assert_eq!(
+            pipeline.manifest.validation.hash,
+            blake3::hash(SHADER.as_bytes()).to_string()
+        );
+        assert!(matches!(
+            registry.get_pipeline(&webgpu(), "missing"),
+            Err(ShaderError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_mismatched_binding_profile_rejected() {
+        let mut registry = ShaderRegistry::new();
+
+        // Output declared read-only in the manifest
+        let wrong_access = vec![binding(0, "read"), binding(1, "read"), binding(2, "read")];
+        assert!(matches!(
+            registry.register_shader(manifest("scale", wrong_access), SHADER),
+            Err(ShaderError::BindingMismatch(_))
+        ));
+
+        // Binding missing from the manifest
+        let missing = vec![binding(0, "read"), binding(2, "read_write")];
+        assert!(matches!(
+            registry.register_shader(manifest("scale", missing), SHADER),
+            Err(ShaderError::BindingMismatch(_))
+        ));
+        assert_eq!(registry.shader_names().count(), 0);
+    }
+
+    #[test]
+    fn test_bindings_in_either_order_outside_comments() {
+        let source = "
+            /* @group(0) @binding(7) var<storage, read_write> old: array<f32>;
+               /* nested */ @group(0) @binding(8) var<uniform> older: f32; */
+            @binding(1) @group(0) var<storage, read> input: array<f32>;
+            @group(0) // output
+            @binding(2) var<storage, read_write> output: array<f32>;
+            @group(0) /* params */ @binding(0) var<uniform> params: Params;
+            // @group(1) @binding(0) var<uniform> unused: f32;
+            struct Params { scale: f32 }
+            @compute @workgroup_size(64, 1, 1)
+            fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                output[id.x] = input[id.x] * params.scale;
+            }
+        ";
+        let mut declared = declared_bindings(source);
+        declared.sort();
+        assert_eq!(
+            declared,
+            [
+                (0, 0, "read".to_string()),
+                (0, 1, "read".to_string()),
+                (0, 2, "read_write".to_string()),
+            ]
+        );
+
+        let mut registry = ShaderRegistry::new();
+        let bindings = vec![
+            binding(0, "read"),
+            binding(1, "read"),
+            binding(2, "read_write"),
+        ];
+        registry
+            .register_shader(manifest("reordered", bindings), source)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_hash_and_device_requirements() {
+        let mut registry = ShaderRegistry::new();
+        let bindings = vec![
+            binding(0, "read"),
+            binding(1, "read"),
+            binding(2, "read_write"),
+        ];
+
+        let mut tampered = manifest("scale", bindings.clone());
+        tampered.validation.hash = blake3::hash(b"other source").to_string();
+        assert!(matches!(
+            registry.register_shader(tampered, SHADER),
+            Err(ShaderError::HashMismatch { .. })
+        ));
+
+        registry
+            .register_shader(manifest("scale", bindings), SHADER)
+            .unwrap();
+        let small = DeviceProfile {
+            architecture: "webgpu".to_string(),
+            max_workgroup_size: [32, 32, 1],
+        };
+        assert!(matches!(
+            registry.get_pipeline(&small, "scale"),
+            Err(ShaderError::Unsupported(_))
+        ));
+    }
+
+    #[cfg(feature = "wgsl")]
+    #[test]
+    fn test_validate_wgsl_extracts_layout() {
+        let registry = ShaderRegistry::new();
+        let manifest = registry.validate_wgsl(SHADER).unwrap();
+
+        assert_eq!(manifest.meta.entry_points, vec!["main".to_string()]);
+        assert_eq!(manifest.requirements.min_workgroup_size, [64, 1, 1]);
+        let mut layout: Vec<_> = manifest
+            .bindings
+            .iter()
+            .map(|b| (b.binding, b.resource_type.as_str(), b.access.as_str()))
+            .collect();
+        layout.sort();
+        assert_eq!(
+            layout,
+            vec![
+                (0, "buffer", "read"),
+                (1, "buffer", "read"),
+                (2, "buffer", "read_write")
+            ]
+        );
+        assert_eq!(
+            manifest.validation.hash,
+            blake3::hash(SHADER.as_bytes()).to_string()
+        );
+    }
+
+    #[cfg(feature = "wgsl")]
+    #[test]
+    fn test_validate_wgsl_reports_undefined_identifier() {
+        let registry = ShaderRegistry::new();
+        let source =
+            "@compute @workgroup_size(1)\nfn main() {\n    let x = undefined_value + 1.0;\n}\n";
+
+        let err = match registry.validate_wgsl(source) {
+            Err(ShaderError::InvalidWgsl(message)) => message,
+            other => panic!("expected InvalidWgsl, got {:?}", other.map(|_| ())),
+        };
+        assert!(err.starts_with("line 3:"), "{}", err);
+        assert!(err.contains("undefined_value"), "{}", err);
+
+        // Registration rejects it too, before any binding checks
+        let mut registry = ShaderRegistry::new();
+        assert!(matches!(
+            registry.register_shader(manifest("broken", Vec::new()), source),
+            Err(ShaderError::InvalidWgsl(_))
+        ));
+    }
+}
@@ -43,14 +43,27 @@ impl Reactor {
         crate::js_interop::atomic_add(self.flags.barrier_view(), IDX_OUTBOX_KERNEL_DIRTY, 1);
     }
 
+    // Inbox/Outbox stay on unversioned frames until the kernel bridge
+    // (sab_bridge.go, bridge-state.ts) reads and writes FRAME_VERSION.
+
     /// Read next message from Inbox (Ring Buffer)
     pub fn read_request(&self) -> Option<Vec<u8>> {
-        self.inbox.read_message().unwrap_or(None)
+        self.inbox.read_message_legacy().unwrap_or(None)
+    }
+
+    /// Read every pending message from Inbox (Ring Buffer)
+    pub fn drain_requests(&self) -> crate::ringbuffer::Drain<'_> {
+        self.inbox.drain_legacy()
     }
 
     /// Write message to Outbox (Ring Buffer)
     pub fn write_result(&self, data: &[u8]) -> bool {
-        self.outbox.write_message(data).unwrap_or(false)
+        self.outbox.write_message_legacy(data).is_ok()
+    }
+
+    /// Write message to Outbox, reporting why it didn't fit
+    pub fn try_write_result(&self, data: &[u8]) -> Result<u32, crate::ringbuffer::RingError> {
+        self.outbox.write_message_legacy(data)
     }
 }
 
@@ -89,6 +102,16 @@ impl Epoch {
         crate::js_interop::signal_epoch(self.flags.barrier_view(), self.index)
     }
 
+    /// Signal a mutation we produced ourselves, so our own `has_changed` does
+    /// not fire for it (a bump from another writer is still reported)
+    pub fn increment_own(&mut self) -> i32 {
+        let previous = self.increment();
+        if previous == self.last_seen {
+            self.last_seen = previous.wrapping_add(1);
+        }
+        previous
+    }
+
     pub fn current(&self) -> i32 {
         crate::js_interop::atomic_load(self.flags.barrier_view(), self.index)
     }
@@ -111,6 +134,22 @@ mod tests {
         assert!(!epoch.has_changed()); // Second check should be false
     }
 
+    #[test]
+    fn test_epoch_increment_own_does_not_self_wake() {
+        let sab = SafeSAB::with_size(1024);
+        let mut epoch = Epoch::new(sab.clone(), IDX_SYSTEM_EPOCH);
+
+        epoch.increment_own();
+        assert_eq!(epoch.current(), 1);
+        assert!(!epoch.has_changed());
+
+        // Another writer bumping first is still observed
+        let mut other = Epoch::new(sab, IDX_SYSTEM_EPOCH);
+        other.increment();
+        epoch.increment_own();
+        assert!(epoch.has_changed());
+    }
+
     #[test]
     fn test_reactor_signals() {
         let sab = SafeSAB::with_size(16 * 1024 * 1024);
@@ -1,20 +1,83 @@
+use crate::crdt::LwwMap;
 use crate::sab::SafeSAB;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+/// Social graph: SAB account entries plus a replicated, weighted edge set.
+///
+/// Edges are held in an `LwwMap`, so two partitions that gossip divergent
+/// graphs converge to the same edges whatever order they merge in.
 pub struct SocialGraph {
     sab: SafeSAB,
+    edges: LwwMap<SocialEdge, f32>,
+    /// Bumped on every visible edge change (local or merged)
+    version: u64,
+    /// (source, max_hops) -> trust in every reachable node, valid for `trust_cache_version`
+    trust_cache: HashMap<(String, u32), HashMap<String, f32>>,
+    trust_cache_version: u64,
 }
 
 const SOCIAL_ACCOUNT_SIZE: usize = 1248;
 
+/// Accounts that fit in the SAB social graph region
+pub const MAX_SOCIAL_ACCOUNTS: usize = SafeSAB::SIZE_SOCIAL_GRAPH / SOCIAL_ACCOUNT_SIZE;
+
+/// Weights given to edges imported from SAB entries
+pub const DEFAULT_REFERRAL_WEIGHT: f32 = 0.9;
+pub const DEFAULT_CLOSE_WEIGHT: f32 = 0.7;
+
+/// Attenuation applied for every hop after the first
+pub const TRUST_HOP_DECAY: f32 = 0.5;
+
 pub struct SocialEntry {
     pub owner_did: String,
     pub referrer_did: String,
     pub close_ids: Vec<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum EdgeKind {
+    Referral,
+    Close,
+}
+
+/// Directed relationship: `from` vouches for `to`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SocialEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+impl SocialEdge {
+    pub fn new(from: &str, to: &str, kind: EdgeKind) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        }
+    }
+}
+
 impl SocialGraph {
-    pub fn new(sab: SafeSAB) -> Self {
-        Self { sab }
+    /// Replica id is this node's id; fails until the node identity is set,
+    /// since replicas sharing an id can't break stamp ties
+    pub fn new(sab: SafeSAB) -> Result<Self, String> {
+        match crate::identity::get_node_id() {
+            Some(node_id) if !node_id.is_empty() => Ok(Self::with_replica(sab, node_id)),
+            _ => Err("Node id not set; SocialGraph needs a unique replica id".to_string()),
+        }
+    }
+
+    /// `replica_id` must be unique among the replicas that gossip together
+    pub fn with_replica(sab: SafeSAB, replica_id: &str) -> Self {
+        Self {
+            sab,
+            edges: LwwMap::new(replica_id),
+            version: 0,
+            trust_cache: HashMap::new(),
+            trust_cache_version: 0,
+        }
     }
 
     pub fn get_entry(&self, index: usize) -> Result<SocialEntry, String> {
@@ -41,6 +104,182 @@ impl SocialGraph {
         })
     }
 
+    /// Seed edges from the first `count` SAB entries. Edges this replica has
+    /// already seen (including removals) are left alone. Returns edges added.
+    pub fn load_entries(&mut self, count: usize) -> Result<usize, String> {
+        let mut added = 0;
+        for index in 0..count.min(MAX_SOCIAL_ACCOUNTS) {
+            let entry = self.get_entry(index)?;
+            if entry.owner_did.is_empty() {
+                continue;
+            }
+
+            let mut candidates = Vec::new();
+            if !entry.referrer_did.is_empty() {
+                candidates.push((
+                    SocialEdge::new(&entry.owner_did, &entry.referrer_did, EdgeKind::Referral),
+                    DEFAULT_REFERRAL_WEIGHT,
+                ));
+            }
+            for close in &entry.close_ids {
+                candidates.push((
+                    SocialEdge::new(&entry.owner_did, close, EdgeKind::Close),
+                    DEFAULT_CLOSE_WEIGHT,
+                ));
+            }
+
+            for (edge, weight) in candidates {
+                if edge.from != edge.to && self.edges.stamp(&edge).is_none() {
+                    self.edges.insert(edge, weight);
+                    added += 1;
+                }
+            }
+        }
+        if added > 0 {
+            self.version += 1;
+        }
+        Ok(added)
+    }
+
+    /// Add or re-weight an edge; `weight` is the trust `from` places in `to` (0..=1)
+    pub fn add_edge(
+        &mut self,
+        from: &str,
+        to: &str,
+        kind: EdgeKind,
+        weight: f32,
+    ) -> Result<(), String> {
+        if from == to {
+            return Err("Self edges are not allowed".to_string());
+        }
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(format!("Edge weight must be in [0, 1], got {}", weight));
+        }
+        self.edges.insert(SocialEdge::new(from, to, kind), weight);
+        self.version += 1;
+        Ok(())
+    }
+
+    pub fn remove_edge(&mut self, from: &str, to: &str, kind: EdgeKind) {
+        self.edges.remove(&SocialEdge::new(from, to, kind));
+        self.version += 1;
+    }
+
+    pub fn edge_weight(&self, from: &str, to: &str, kind: EdgeKind) -> Option<f32> {
+        self.edges.get(&SocialEdge::new(from, to, kind)).copied()
+    }
+
+    /// Live edges in deterministic (sorted) order
+    pub fn edges(&self) -> impl Iterator<Item = (&SocialEdge, f32)> {
+        self.edges.iter().map(|(edge, weight)| (edge, *weight))
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Reconcile with another replica's edges
+    pub fn merge(&mut self, other: &SocialGraph) {
+        if self.edges.merge(&other.edges) {
+            self.version += 1;
+        }
+    }
+
+    /// Edge state for gossip to peers
+    pub fn export_edges(&self) -> Vec<u8> {
+        self.edges.to_bytes()
+    }
+
+    /// Merge edge state gossiped by a peer (see `export_edges`). State with a
+    /// non-finite weight is rejected; other weights are clamped to [0, 1].
+    pub fn merge_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let mut remote: LwwMap<SocialEdge, f32> = LwwMap::from_bytes(bytes, "remote")?;
+        if let Some(bad) = remote.iter().find(|(_, w)| !w.is_finite()) {
+            return Err(format!(
+                "Edge {} -> {} has non-finite weight {}",
+                bad.0.from, bad.0.to, bad.1
+            ));
+        }
+        for weight in remote.values_mut() {
+            *weight = weight.clamp(0.0, 1.0);
+        }
+
+        if self.edges.merge(&remote) {
+            self.version += 1;
+        }
+        Ok(())
+    }
+
+    /// Transitive trust `from` places in `to` within `max_hops`: the best path
+    /// score, where a path scores the product of its edge weights times
+    /// `TRUST_HOP_DECAY` per hop after the first. 0.0 if unreachable.
+    ///
+    /// Results are cached per source until the graph version changes.
+    pub fn trust_score(&mut self, from: &str, to: &str, max_hops: u32) -> f32 {
+        if from == to {
+            return 1.0;
+        }
+        if self.trust_cache_version != self.version {
+            self.trust_cache.clear();
+            self.trust_cache_version = self.version;
+        }
+
+        let key = (from.to_string(), max_hops);
+        if let Some(scores) = self.trust_cache.get(&key) {
+            return scores.get(to).copied().unwrap_or(0.0);
+        }
+
+        let scores = self.propagate_trust(from, max_hops);
+        let score = scores.get(to).copied().unwrap_or(0.0);
+        self.trust_cache.insert(key, scores);
+        score
+    }
+
+    /// Bounded weighted BFS. A node is only expanded again when reached with a
+    /// strictly better score, so cycles cannot loop and work stays bounded.
+    fn propagate_trust(&self, from: &str, max_hops: u32) -> HashMap<String, f32> {
+        let mut adjacency: HashMap<&str, HashMap<&str, f32>> = HashMap::new();
+        for (edge, weight) in self.edges() {
+            let best = adjacency
+                .entry(edge.from.as_str())
+                .or_default()
+                .entry(edge.to.as_str())
+                .or_insert(0.0);
+            *best = best.max(weight);
+        }
+
+        let mut best: HashMap<&str, f32> = HashMap::new();
+        best.insert(from, 1.0);
+        let mut frontier = vec![(from, 1.0f32)];
+
+        for hop in 1..=max_hops {
+            let decay = if hop == 1 { 1.0 } else { TRUST_HOP_DECAY };
+            let mut next: HashMap<&str, f32> = HashMap::new();
+            for (node, score) in frontier {
+                let neighbours = match adjacency.get(node) {
+                    Some(n) => n,
+                    None => continue,
+                };
+                for (&peer, &weight) in neighbours {
+                    let candidate = score * weight * decay;
+                    if candidate > best.get(peer).copied().unwrap_or(0.0) {
+                        best.insert(peer, candidate);
+                        next.insert(peer, candidate);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next.into_iter().collect();
+        }
+
+        best.remove(from);
+        best.into_iter()
+            .map(|(node, score)| (node.to_string(), score))
+            .collect()
+    }
+
     fn parse_did(data: &[u8]) -> String {
         let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
         String::from_utf8_lossy(&data[..len]).to_string()
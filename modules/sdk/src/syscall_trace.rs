@@ -0,0 +1,116 @@
+//! Syscall latency tracing
+//!
+//! `SyscallClient` records an enter event once a request is in the outbox and
+//! an exit event when its response (or failure) comes back. Events land in a
+//! small ring in the diagnostics region, where the watchdog pairs them by
+//! module and call id.
+//!
+//! Ring layout at `OFFSET_SYSCALL_TRACE`:
+//! `[write sequence u32 | reserved 12B]` followed by 16-byte events
+//! `[call_id u32 | module_id u32 | opcode u16 | kind u8 | reserved u8 | timestamp_us u32]`
+
+use crate::layout::{OFFSET_SYSCALL_TRACE, SIZE_SYSCALL_TRACE};
+use crate::sab::SafeSAB;
+
+const HEADER_SIZE: usize = 16;
+const EVENT_SIZE: usize = 16;
+
+/// Events held before the oldest is overwritten
+pub const SYSCALL_TRACE_CAPACITY: usize = (SIZE_SYSCALL_TRACE - HEADER_SIZE) / EVENT_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TraceKind {
+    Enter = 1,
+    Exit = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyscallTraceEvent {
+    pub call_id: u32,
+    pub module_id: u32,
+    pub opcode: u16,
+    pub kind: TraceKind,
+    /// Microsecond clock; wraps, so only differences are meaningful
+    pub timestamp_us: u32,
+}
+
+impl SyscallTraceEvent {
+    fn to_bytes(self) -> [u8; EVENT_SIZE] {
+        let mut bytes = [0u8; EVENT_SIZE];
+        bytes[0..4].copy_from_slice(&self.call_id.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.module_id.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.opcode.to_le_bytes());
+        bytes[10] = self.kind as u8;
+        bytes[12..16].copy_from_slice(&self.timestamp_us.to_le_bytes());
+        bytes
+    }
+
+    /// Empty (never written) slots decode to None
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let kind = match bytes[10] {
+            1 => TraceKind::Enter,
+            2 => TraceKind::Exit,
+            _ => return None,
+        };
+        Some(Self {
+            call_id: u32::from_le_bytes(bytes[0..4].try_into().ok()?),
+            module_id: u32::from_le_bytes(bytes[4..8].try_into().ok()?),
+            opcode: u16::from_le_bytes(bytes[8..10].try_into().ok()?),
+            kind,
+            timestamp_us: u32::from_le_bytes(bytes[12..16].try_into().ok()?),
+        })
+    }
+}
+
+/// Current time on the trace clock: the monotonic, sub-millisecond
+/// `performance.now()` of the calling worker
+pub fn now_us() -> u32 {
+    (crate::js_interop::get_performance_now() * 1000.0) as u64 as u32
+}
+
+/// Record a syscall boundary for the calling module
+pub fn record(sab: &SafeSAB, call_id: u64, opcode: u16, kind: TraceKind) {
+    record_event(
+        sab,
+        SyscallTraceEvent {
+            call_id: call_id as u32,
+            module_id: crate::identity::get_module_id(),
+            opcode,
+            kind,
+            timestamp_us: now_us(),
+        },
+    );
+}
+
+/// Append an event, overwriting the oldest once the ring is full
+pub fn record_event(sab: &SafeSAB, event: SyscallTraceEvent) {
+    let seq_index = (OFFSET_SYSCALL_TRACE / 4) as u32;
+    let seq = crate::js_interop::atomic_add(sab.barrier_view(), seq_index, 1) as u32;
+    let slot = seq as usize % SYSCALL_TRACE_CAPACITY;
+    let offset = OFFSET_SYSCALL_TRACE + HEADER_SIZE + slot * EVENT_SIZE;
+    let _ = sab.write(offset, &event.to_bytes());
+}
+
+/// All retained events, oldest first
+pub fn read_events(sab: &SafeSAB) -> Result<Vec<SyscallTraceEvent>, String> {
+    let seq_index = (OFFSET_SYSCALL_TRACE / 4) as u32;
+    let seq = crate::js_interop::atomic_load(sab.barrier_view(), seq_index) as u32 as usize;
+    let bytes = sab.read(
+        OFFSET_SYSCALL_TRACE + HEADER_SIZE,
+        SYSCALL_TRACE_CAPACITY * EVENT_SIZE,
+    )?;
+
+    // Once wrapped, the next slot to be written holds the oldest event
+    let start = if seq > SYSCALL_TRACE_CAPACITY {
+        seq % SYSCALL_TRACE_CAPACITY
+    } else {
+        0
+    };
+    Ok((0..SYSCALL_TRACE_CAPACITY)
+        .map(|i| (start + i) % SYSCALL_TRACE_CAPACITY)
+        .filter_map(|slot| {
+            SyscallTraceEvent::from_bytes(&bytes[slot * EVENT_SIZE..(slot + 1) * EVENT_SIZE])
+        })
+        .collect())
+}
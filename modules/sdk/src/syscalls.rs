@@ -1,6 +1,7 @@
 use crate::protocols::resource;
 use crate::protocols::syscall;
 use crate::sab::SafeSAB;
+use crate::syscall_trace::{self, TraceKind};
 
 use capnp::message::{Builder, ReaderOptions};
 use capnp::serialize_packed;
@@ -78,12 +79,16 @@ impl SyscallClient {
         serialize_packed::write_message(&mut request_bytes, &message).map_err(|e| e.to_string())?;
 
         // 3. Send to Outbox & Signal
-        Self::send_raw(sab, &request_bytes)?;
-
         // 4. Await Response (Poll Inbox)
         // In a real reactor model, we would register a waker.
         // For v2.0, we use an efficient async sleep-poll loop.
-        Self::poll_response(sab, call_id).await
+        Self::round_trip(
+            sab,
+            call_id,
+            syscall::syscall::Opcode::FetchChunk,
+            &request_bytes,
+        )
+        .await
     }
 
     /// Send a store_chunk request and await response
@@ -127,10 +132,14 @@ impl SyscallClient {
         let mut request_bytes = Vec::new();
         serialize_packed::write_message(&mut request_bytes, &message).map_err(|e| e.to_string())?;
 
-        Self::send_raw(sab, &request_bytes)?;
-
         // Response should contain StoreChunkResult with replicas count
-        let response_bytes = Self::poll_response(sab, call_id).await?;
+        let response_bytes = Self::round_trip(
+            sab,
+            call_id,
+            syscall::syscall::Opcode::StoreChunk,
+            &request_bytes,
+        )
+        .await?;
 
         // Parse Response
         let reader = serialize_packed::read_message(&mut &response_bytes[..], ReaderOptions::new())
@@ -194,9 +203,13 @@ impl SyscallClient {
         let mut request_bytes = Vec::new();
         serialize_packed::write_message(&mut request_bytes, &message).map_err(|e| e.to_string())?;
 
-        Self::send_raw(sab, &request_bytes)?;
-
-        let response_bytes = Self::poll_response(sab, call_id).await?;
+        let response_bytes = Self::round_trip(
+            sab,
+            call_id,
+            syscall::syscall::Opcode::SendMessage,
+            &request_bytes,
+        )
+        .await?;
 
         // Parse Response
         let reader = serialize_packed::read_message(&mut &response_bytes[..], ReaderOptions::new())
@@ -272,6 +285,21 @@ impl SyscallClient {
         Ok(())
     }
 
+    /// Internal: Send a request and await its response, tracing enter/exit for diagnostics
+    async fn round_trip(
+        sab: &SafeSAB,
+        call_id: u64,
+        opcode: syscall::syscall::Opcode,
+        request_bytes: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        Self::send_raw(sab, request_bytes)?;
+        syscall_trace::record(sab, call_id, opcode as u16, TraceKind::Enter);
+
+        let response = Self::poll_response(sab, call_id).await;
+        syscall_trace::record(sab, call_id, opcode as u16, TraceKind::Exit);
+        response
+    }
+
     /// Internal: Poll SAB Inbox for matching Call ID
     /// Uses exponential backoff to be friendly to the CPU/Runtime.
     async fn poll_response(sab: &SafeSAB, expected_call_id: u64) -> Result<Vec<u8>, String> {
@@ -390,9 +418,13 @@ impl SyscallClient {
         let mut request_bytes = Vec::new();
         serialize_packed::write_message(&mut request_bytes, &message).map_err(|e| e.to_string())?;
 
-        Self::send_raw(sab, &request_bytes)?;
-
-        let response_bytes = Self::poll_response(sab, call_id).await?;
+        let response_bytes = Self::round_trip(
+            sab,
+            call_id,
+            syscall::syscall::Opcode::HostCall,
+            &request_bytes,
+        )
+        .await?;
 
         let reader = serialize_packed::read_message(&mut &response_bytes[..], ReaderOptions::new())
             .map_err(|e| format!("Invalid response format: {}", e))?;
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod cas_tests {
-    use super::super::StorageEngine;
+    use super::super::{NonceMode, StorageEngine};
 
     // ========== CAS (Content-Addressable Storage) TESTS ==========
 
@@ -110,6 +110,24 @@ mod cas_tests {
         assert_eq!(retrieved, data, "Data should match");
     }
 
+    #[test]
+    fn test_cas_streamed_chunk_hashes_plaintext() {
+        let key = [5u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+
+        // Larger than one stream segment, so the hash is built incrementally
+        let data: Vec<u8> = (0..3 * 1024 * 1024 + 17).map(|i| (i % 253) as u8).collect();
+        let (hash, blob) = engine
+            .store_cas_chunk(&data)
+            .expect("Failed to store streamed CAS chunk");
+
+        assert_eq!(hash, hex::encode(sdk::compression::hash_blake3(&data)));
+        let retrieved = engine
+            .retrieve_cas_chunk(&blob, &hash)
+            .expect("Failed to retrieve streamed CAS chunk");
+        assert!(retrieved == data, "Data should match");
+    }
+
     #[test]
     fn test_cas_empty_data() {
         let key = [6u8; 32];
@@ -224,4 +242,114 @@ mod cas_tests {
             .expect("Failed to retrieve binary data");
         assert_eq!(retrieved, data, "Binary data should roundtrip correctly");
     }
+
+    #[test]
+    fn test_convergent_mode_identical_blobs() {
+        let key = [11u8; 32];
+        let engine = StorageEngine::new(&key)
+            .expect("Failed to create engine")
+            .with_nonce_mode(NonceMode::Convergent);
+        let data = b"Convergent content, stored twice";
+
+        let blob1 = engine.store_chunk(data).expect("Failed to store 1");
+        let blob2 = engine.store_chunk(data).expect("Failed to store 2");
+        assert_eq!(blob1, blob2, "Convergent mode should be deterministic");
+
+        let other = engine.store_chunk(b"Different content").unwrap();
+        assert_ne!(blob1[1..13], other[1..13], "Nonces must differ by content");
+        assert_eq!(engine.retrieve_chunk(&blob1).unwrap(), data);
+    }
+
+    #[test]
+    fn test_convergent_nonce_is_keyed() {
+        use sdk::compression::CompressionAlgorithm;
+        let data = b"Guessable content";
+        let engine = StorageEngine::new(&[15u8; 32])
+            .unwrap()
+            .with_nonce_mode(NonceMode::Convergent);
+        let other_key = StorageEngine::new(&[16u8; 32])
+            .unwrap()
+            .with_nonce_mode(NonceMode::Convergent);
+
+        let none = CompressionAlgorithm::None;
+        let blob = engine.store_chunk_with(data, none).unwrap();
+        let other = other_key.store_chunk_with(data, none).unwrap();
+        assert_ne!(blob[1..13], other[1..13], "Nonces must depend on the key");
+
+        // The clear nonce must not be recomputable from a guessed plaintext alone
+        let mut payload = vec![none as u8];
+        payload.extend_from_slice(data);
+        let unkeyed = sdk::compression::hash_blake3(&payload);
+        assert_ne!(blob[1..13], unkeyed[..12]);
+        assert_eq!(engine.retrieve_chunk(&blob).unwrap(), data);
+    }
+
+    #[test]
+    fn test_random_mode_is_default() {
+        let key = [12u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        assert_eq!(engine.nonce_mode(), NonceMode::Random);
+
+        let data = b"Private content";
+        let blob1 = engine.store_chunk(data).unwrap();
+        let blob2 = engine.store_chunk(data).unwrap();
+        assert_ne!(blob1, blob2, "Random nonces should differ per store");
+    }
+
+    #[test]
+    fn test_convergent_cas_dedup_across_engines() {
+        let key = [13u8; 32];
+        let node_a = StorageEngine::new(&key)
+            .unwrap()
+            .with_nonce_mode(NonceMode::Convergent);
+        let node_b = StorageEngine::new(&key)
+            .unwrap()
+            .with_nonce_mode(NonceMode::Convergent);
+        let data = b"Shared chunk replicated by two nodes";
+
+        let (hash_a, blob_a) = node_a.store_cas_chunk(data).unwrap();
+        let (hash_b, blob_b) = node_b.store_cas_chunk(data).unwrap();
+        assert_eq!(hash_a, hash_b);
+        assert_eq!(blob_a, blob_b, "Same key and content should dedup");
+
+        let retrieved = node_b
+            .retrieve_cas_chunk(&blob_a, &hash_a)
+            .expect("Failed to retrieve peer blob");
+        assert_eq!(retrieved, data);
+    }
+
+    #[test]
+    fn test_convergent_streamed_chunk_is_deterministic() {
+        use super::super::STREAM_SEGMENT_SIZE;
+        let key = [14u8; 32];
+        let engine = StorageEngine::new(&key)
+            .unwrap()
+            .with_nonce_mode(NonceMode::Convergent);
+        let data: Vec<u8> = (0..STREAM_SEGMENT_SIZE * 2 + 17)
+            .map(|i| (i * 31 % 253) as u8)
+            .collect();
+
+        let (hash1, blob1) = engine.store_cas_chunk(&data).unwrap();
+        let (hash2, blob2) = engine.store_cas_chunk(&data).unwrap();
+        assert_eq!(hash1, hash2);
+        assert_eq!(
+            blob1, blob2,
+            "Streamed chunks should dedup in convergent mode"
+        );
+        assert_eq!(engine.retrieve_cas_chunk(&blob1, &hash1).unwrap(), data);
+
+        let mut other = data.clone();
+        other[STREAM_SEGMENT_SIZE + 1] ^= 1;
+        let (_, other_blob) = engine.store_cas_chunk(&other).unwrap();
+        assert_ne!(
+            blob1[2..18],
+            other_blob[2..18],
+            "Stream ids must differ by content"
+        );
+
+        let random = StorageEngine::new(&key).unwrap();
+        let (_, random1) = random.store_cas_chunk(&data).unwrap();
+        let (_, random2) = random.store_cas_chunk(&data).unwrap();
+        assert_ne!(random1, random2);
+    }
 }
@@ -1,11 +1,21 @@
+use aes_gcm::Aes256Gcm;
 use chacha20poly1305::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
     ChaCha20Poly1305, Key, Nonce,
 };
 use rand_core::{CryptoRng, RngCore};
+use sdk::compression::CompressionAlgorithm;
+use sdk::credits::ReplicationTier;
+use std::io::{Read, Write};
+use std::sync::Mutex;
 
 use log::{error, info};
 
+pub mod merkle;
+pub use merkle::{verify_chunk_proof, MerkleCas};
+pub mod replication;
+pub use replication::{ReplicationLedger, ReplicationRecord};
+
 // Storage module bare-metal (no wasm-bindgen macros)
 
 #[cfg(target_arch = "wasm32")]
@@ -37,18 +47,79 @@ impl RngCore for HostRng {
 }
 
 impl CryptoRng for HostRng {}
+/// AEAD used to seal stored blobs
+/// The discriminant is written as the first byte of every blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CipherSuite {
+    ChaCha20Poly1305 = 1,
+    Aes256Gcm = 2,
+}
+
+impl CipherSuite {
+    pub fn from_tag(tag: u8) -> Result<Self, String> {
+        match tag {
+            1 => Ok(CipherSuite::ChaCha20Poly1305),
+            2 => Ok(CipherSuite::Aes256Gcm),
+            _ => Err(format!("Unknown cipher tag: {}", tag)),
+        }
+    }
+}
+
+/// How the 12-byte AEAD nonce of a single-segment blob is chosen
+///
+/// `Convergent` derives the nonce from a BLAKE3 hash of the sealed payload,
+/// keyed with the encryption key, so identical plaintext under the same key
+/// yields identical blobs that the DHT can deduplicate across nodes. Since the
+/// nonce is stored in the clear, the trade-off is that equal blobs reveal equal
+/// content, and anyone holding the key can confirm a guessed plaintext; without
+/// the key the nonce says nothing about the payload. Keep `Random` for
+/// privacy-sensitive data.
+/// Streamed blobs derive each segment nonce from the stream id, position and
+/// payload. `store_cas_chunk` derives the stream id from the content hash, so
+/// large chunks converge too; a stream read from a `Reader` gets a random id,
+/// since its hash isn't known until the end.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonceMode {
+    #[default]
+    Random,
+    Convergent,
+}
+
+/// Leading bytes sampled when choosing a compression algorithm
+const COMPRESSION_PROBE_SIZE: usize = 64 * 1024;
+/// Below this fractional saving on the probe, data is stored uncompressed
+const MIN_COMPRESSION_SAVINGS: f64 = 0.05;
+
+/// Plaintext bytes per segment in a streamed blob
+pub const STREAM_SEGMENT_SIZE: usize = 1024 * 1024;
+/// First byte of a streamed blob; never a valid CipherSuite tag
+const STREAM_TAG: u8 = 0x53;
+const STREAM_ID_SIZE: usize = 16;
+/// Upper bound on a segment's sealed size, so a corrupt length cannot force a huge read
+const MAX_SEGMENT_FRAME: usize = 2 * STREAM_SEGMENT_SIZE;
+const SEGMENT_FLAG_FINAL: u8 = 1;
+
 #[derive(Debug)]
 pub struct StorageEngine {
     encryption_key: Key,
+    cipher_suite: CipherSuite,
+    nonce_mode: NonceMode,
+    /// Hosting payouts for replicated CAS chunks
+    replication_ledger: Mutex<ReplicationLedger>,
 }
 
 /// Standardized Memory Allocator for WebAssembly
+/// Release with `vault_free(ptr, size)` (see `sdk::arena` for the ABI contract)
 #[no_mangle]
 pub extern "C" fn vault_alloc(size: usize) -> *mut u8 {
-    let mut buf = Vec::with_capacity(size);
-    let ptr = buf.as_mut_ptr();
-    std::mem::forget(buf);
-    ptr
+    sdk::arena::alloc_host_buffer(size)
+}
+
+/// Standardized Memory Deallocator for WebAssembly
+#[no_mangle]
+pub extern "C" fn vault_free(ptr: *mut u8, size: usize) {
+    unsafe { sdk::arena::free_host_buffer(ptr, size) }
 }
 
 /// Standardized Initialization with SharedArrayBuffer
@@ -136,80 +207,550 @@ pub extern "C" fn vault_poll() {
     // High-frequency reactor for Vault
 }
 
+/// Engine behind the vault's chunk exports, installed by `vault_open`
+static VAULT: Mutex<Option<StorageEngine>> = Mutex::new(None);
+
+/// Open the vault with its 32-byte storage key
+///
+/// CAS chunks are sealed with convergent nonces so replicas deduplicate.
+/// Reopening replaces the engine, including its replication ledger.
+/// Returns 1 on success, 0 on a bad key.
+#[no_mangle]
+pub extern "C" fn vault_open(key_ptr: *const u8, key_len: usize) -> i32 {
+    if key_ptr.is_null() || key_len == 0 {
+        error!("[vault_open] FAILED: key_ptr is null or len=0");
+        return 0;
+    }
+    let key = unsafe { std::slice::from_raw_parts(key_ptr, key_len) };
+    let engine = match StorageEngine::new(key) {
+        Ok(engine) => engine.with_nonce_mode(NonceMode::Convergent),
+        Err(e) => {
+            error!("[vault_open] FAILED: {}", e);
+            return 0;
+        }
+    };
+    match VAULT.lock() {
+        Ok(mut vault) => {
+            *vault = Some(engine);
+            1
+        }
+        Err(_) => 0,
+    }
+}
+
+/// Seal a CAS chunk for hosting on `peer` and credit the peer for it
+///
+/// `tier`: 0 = hot, 1 = warm, 2 = cold, 3 = archive. Writes the chunk's
+/// BLAKE3 hash (32 bytes) to `hash_out` and the blob length to `blob_len`,
+/// and returns the blob for the host to send to the peer; release it with
+/// `vault_free(ptr, blob_len)`. A chunk already replicated to `peer` is sealed
+/// again but not credited twice. Returns null on failure.
+#[no_mangle]
+pub extern "C" fn vault_replicate_cas_chunk(
+    data_ptr: *const u8,
+    data_len: usize,
+    peer_ptr: *const u8,
+    peer_len: usize,
+    tier: u32,
+    hash_out: *mut u8,
+    blob_len: *mut usize,
+) -> *mut u8 {
+    if peer_ptr.is_null() || peer_len == 0 {
+        error!("[vault_replicate_cas_chunk] FAILED: peer_ptr is null or len=0");
+        return std::ptr::null_mut();
+    }
+    if hash_out.is_null() || blob_len.is_null() {
+        error!("[vault_replicate_cas_chunk] FAILED: null output pointer");
+        return std::ptr::null_mut();
+    }
+    let data: &[u8] = if data_len == 0 {
+        &[]
+    } else if data_ptr.is_null() {
+        error!("[vault_replicate_cas_chunk] FAILED: data_ptr is null with len>0");
+        return std::ptr::null_mut();
+    } else {
+        unsafe { std::slice::from_raw_parts(data_ptr, data_len) }
+    };
+    let peer = match std::str::from_utf8(unsafe { std::slice::from_raw_parts(peer_ptr, peer_len) })
+    {
+        Ok(peer) => peer,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    let tier = match tier {
+        0 => ReplicationTier::Hot,
+        1 => ReplicationTier::Warm,
+        2 => ReplicationTier::Cold,
+        3 => ReplicationTier::Archive,
+        _ => {
+            error!("[vault_replicate_cas_chunk] FAILED: unknown tier {}", tier);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let vault = match VAULT.lock() {
+        Ok(vault) => vault,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    let Some(engine) = vault.as_ref() else {
+        error!("[vault_replicate_cas_chunk] FAILED: vault not opened");
+        return std::ptr::null_mut();
+    };
+    let (hash, blob, _) = match engine.replicate_cas_chunk(data, peer, tier) {
+        Ok(replicated) => replicated,
+        Err(e) => {
+            error!("[vault_replicate_cas_chunk] FAILED: {}", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let hash = hex::decode(hash).unwrap_or_default();
+    unsafe {
+        std::ptr::copy_nonoverlapping(hash.as_ptr(), hash_out, hash.len().min(32));
+        *blob_len = blob.len();
+    }
+    let out = sdk::arena::alloc_host_buffer(blob.len());
+    unsafe { std::ptr::copy_nonoverlapping(blob.as_ptr(), out, blob.len()) };
+    out
+}
+
+/// Credits `peer` has earned hosting chunks replicated by this vault
+#[no_mangle]
+pub extern "C" fn vault_replication_credits(peer_ptr: *const u8, peer_len: usize) -> u64 {
+    if peer_ptr.is_null() || peer_len == 0 {
+        return 0;
+    }
+    let peer = match std::str::from_utf8(unsafe { std::slice::from_raw_parts(peer_ptr, peer_len) })
+    {
+        Ok(peer) => peer,
+        Err(_) => return 0,
+    };
+    match VAULT.lock() {
+        Ok(vault) => vault
+            .as_ref()
+            .map_or(0, |engine| engine.replication_credits(peer)),
+        Err(_) => 0,
+    }
+}
+
 impl StorageEngine {
+    /// Creates an engine sealing new blobs with ChaCha20-Poly1305
     pub fn new(key_bytes: &[u8]) -> Result<StorageEngine, String> {
+        Self::with_cipher(key_bytes, CipherSuite::ChaCha20Poly1305)
+    }
+
+    /// Creates an engine sealing new blobs with `cipher_suite`
+    /// Retrieval follows each blob's own cipher tag, whichever suite is selected.
+    pub fn with_cipher(
+        key_bytes: &[u8],
+        cipher_suite: CipherSuite,
+    ) -> Result<StorageEngine, String> {
         if key_bytes.len() != 32 {
             return Err("Key must be 32 bytes".to_string());
         }
         let key = Key::from_slice(key_bytes);
         Ok(StorageEngine {
             encryption_key: *key,
+            cipher_suite,
+            nonce_mode: NonceMode::default(),
+            replication_ledger: Mutex::new(ReplicationLedger::default()),
         })
     }
 
-    /// Stores data with Brotli Compression -> ChaCha20 Encryption
-    /// Returns: [Nonce (12B) | Encrypted Data]
-    /// Returns: [Nonce (12B) | Encrypted Data]
-    pub fn store_chunk(&self, data: &[u8]) -> Result<Vec<u8>, String> {
-        // 1. Compress (Brotli)
-        let compressed = sdk::compression::CompressionAlgorithm::Brotli
-            .compress(data)
-            .map_err(|e| e.to_string())?;
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.cipher_suite
+    }
 
-        // 2. Encrypt (ChaCha20-Poly1305)
-        let cipher = ChaCha20Poly1305::new(&self.encryption_key);
+    /// Selects how nonces are chosen for new blobs (see `NonceMode`)
+    /// Retrieval is unaffected: the nonce travels with each blob.
+    pub fn with_nonce_mode(mut self, nonce_mode: NonceMode) -> Self {
+        self.nonce_mode = nonce_mode;
+        self
+    }
 
-        // Generate random nonce
-        let mut nonce_bytes = [0u8; 12];
-        let mut rng = HostRng;
-        rng.fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
+    pub fn nonce_mode(&self) -> NonceMode {
+        self.nonce_mode
+    }
 
-        // Encrypt
-        let ciphertext = cipher
-            .encrypt(nonce, compressed.as_ref())
-            .map_err(|e| e.to_string())?;
+    /// Stores data with automatically chosen compression -> AEAD Encryption
+    /// Returns: [Cipher Tag (1B) | Nonce (12B) | Encrypted([Compression Tag (1B) | Data])]
+    pub fn store_chunk(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        self.store_chunk_with(data, Self::choose_compression(data))
+    }
 
-        // 3. Pack: [Nonce][Ciphertext]
-        let mut result = Vec::with_capacity(12 + ciphertext.len());
+    /// Stores data compressed with an explicit algorithm
+    pub fn store_chunk_with(
+        &self,
+        data: &[u8],
+        compression: CompressionAlgorithm,
+    ) -> Result<Vec<u8>, String> {
+        // 1. Compress, tagging the algorithm inside the sealed payload
+        let compressed = compression.compress(data).map_err(|e| e.to_string())?;
+        let mut payload = Vec::with_capacity(1 + compressed.len());
+        payload.push(compression as u8);
+        payload.extend_from_slice(&compressed);
+
+        // 2. Encrypt with the configured suite
+        let nonce_bytes = self.chunk_nonce(&payload);
+
+        let ciphertext = self.seal(self.cipher_suite, &nonce_bytes, &payload, &[])?;
+
+        // 3. Pack: [Tag][Nonce][Ciphertext]
+        let mut result = Vec::with_capacity(1 + 12 + ciphertext.len());
+        result.push(self.cipher_suite as u8);
         result.extend_from_slice(&nonce_bytes);
         result.extend_from_slice(&ciphertext);
 
         Ok(result)
     }
 
-    /// Retrieves data: Decrypt ChaCha20 -> Decompress Brotli
+    /// Random, or the payload's keyed BLAKE3 hash truncated to 12 bytes when convergent
+    /// Hashing the tagged payload (not just the data) means two different
+    /// payloads never share a nonce, whatever compression was chosen.
+    fn chunk_nonce(&self, payload: &[u8]) -> [u8; 12] {
+        let mut nonce_bytes = [0u8; 12];
+        match self.nonce_mode {
+            NonceMode::Random => HostRng.fill_bytes(&mut nonce_bytes),
+            NonceMode::Convergent => {
+                let hash = blake3::keyed_hash(&self.convergent_key(), payload);
+                nonce_bytes.copy_from_slice(&hash.as_bytes()[..12])
+            }
+        }
+        nonce_bytes
+    }
+
+    /// Key for convergent derivations: the encryption key, so only holders of
+    /// that key can link a nonce or stream id to the content it was derived from
+    fn convergent_key(&self) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&self.encryption_key);
+        key
+    }
+
+    /// Picks Brotli unless a fast LZ4 probe saves less than 5%
+    /// Already-compressed media (JPEG, MP3, ...) then skips the wasted CPU.
+    pub fn choose_compression(data: &[u8]) -> CompressionAlgorithm {
+        let probe = &data[..data.len().min(COMPRESSION_PROBE_SIZE)];
+        if probe.is_empty() {
+            return CompressionAlgorithm::None;
+        }
+
+        let savings = match CompressionAlgorithm::Lz4.compress(probe) {
+            Ok(packed) => 1.0 - packed.len() as f64 / probe.len() as f64,
+            Err(_) => 0.0,
+        };
+
+        if savings < MIN_COMPRESSION_SAVINGS {
+            CompressionAlgorithm::None
+        } else {
+            CompressionAlgorithm::Brotli
+        }
+    }
+
+    /// Retrieves data: Decrypt (per blob cipher tag) -> Decompress (per payload tag)
+    /// Streamed blobs are recognised by their leading tag and reassembled.
+    /// Untagged blobs written before cipher tags existed are still readable.
     pub fn retrieve_chunk(&self, blob: &[u8]) -> Result<Vec<u8>, String> {
-        if blob.len() < 12 {
+        self.retrieve_tagged(blob)
+            .or_else(|err| self.retrieve_legacy(blob).map_err(|_| err))
+    }
+
+    fn retrieve_tagged(&self, blob: &[u8]) -> Result<Vec<u8>, String> {
+        if blob.first() == Some(&STREAM_TAG) {
+            let mut data = Vec::new();
+            self.retrieve_stream(blob, &mut data)?;
+            return Ok(data);
+        }
+        if blob.len() < 1 + 12 {
             return Err("Blob too short".to_string());
         }
 
         // 1. Unpack
-        let nonce_bytes = &blob[0..12];
-        let ciphertext = &blob[12..];
-        let nonce = Nonce::from_slice(nonce_bytes);
+        let cipher_suite = CipherSuite::from_tag(blob[0])?;
+        let nonce_bytes = &blob[1..13];
+        let ciphertext = &blob[13..];
 
         // 2. Decrypt
-        let cipher = ChaCha20Poly1305::new(&self.encryption_key);
-        let compressed = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| e.to_string())?;
+        let payload = self.open(cipher_suite, nonce_bytes, ciphertext, &[])?;
+        Self::decompress_payload(&payload)
+    }
 
-        // 3. Decompress
-        let decompressed = sdk::compression::CompressionAlgorithm::Brotli
+    /// Original layout: [Nonce (12B) | ChaCha20-Poly1305(Brotli(data))]
+    /// Its first nonce byte can collide with a cipher or stream tag, so it is
+    /// tried whenever the tagged layout fails; the AEAD tag keeps the two apart.
+    fn retrieve_legacy(&self, blob: &[u8]) -> Result<Vec<u8>, String> {
+        if blob.len() < 12 {
+            return Err("Blob too short".to_string());
+        }
+        let compressed = self.open(CipherSuite::ChaCha20Poly1305, &blob[..12], &blob[12..], &[])?;
+        CompressionAlgorithm::Brotli
             .decompress(&compressed)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Streams `reader` into `writer` as independently sealed segments
+    /// Memory stays bounded to two segments however large the input is.
+    /// Returns the number of plaintext bytes consumed.
+    ///
+    /// Layout: [Stream Tag (1B) | Cipher Tag (1B) | Stream ID (16B) | Segment...]
+    /// Segment: [Length (4B LE) | Flags (1B) | Nonce (12B) | Encrypted([Compression Tag | Data])]
+    pub fn store_stream<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<u64, String> {
+        self.store_segments(reader, writer, Self::random_stream_id(), |_| {})
+    }
+
+    /// Streams into CAS, hashing the plaintext incrementally as segments are sealed
+    /// Returns the BLAKE3 hash of the plaintext.
+    pub fn store_cas_stream<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<String, String> {
+        let mut hasher = blake3::Hasher::new();
+        self.store_segments(reader, writer, Self::random_stream_id(), |segment| {
+            hasher.update(segment);
+        })?;
+        Ok(hex::encode(hasher.finalize().as_bytes()))
+    }
+
+    /// Decrypts a streamed blob segment by segment into `writer`
+    /// Each segment is authenticated before any of its plaintext is written.
+    /// Returns the number of plaintext bytes written.
+    pub fn retrieve_stream<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<u64, String> {
+        let mut header = [0u8; 2 + STREAM_ID_SIZE];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| "Blob too short".to_string())?;
+        if header[0] != STREAM_TAG {
+            return Err("Not a streamed blob".to_string());
+        }
+        let cipher_suite = CipherSuite::from_tag(header[1])?;
+        let stream_id = &header[2..];
+
+        let mut total = 0u64;
+        for index in 0u64.. {
+            let mut frame_header = [0u8; 4 + 1 + 12];
+            reader
+                .read_exact(&mut frame_header)
+                .map_err(|_| "Stream truncated before final segment".to_string())?;
+            let len = u32::from_le_bytes(frame_header[..4].try_into().unwrap()) as usize;
+            let flags = frame_header[4];
+            if len > MAX_SEGMENT_FRAME {
+                return Err(format!("Segment {} too large: {} bytes", index, len));
+            }
+
+            let mut ciphertext = vec![0u8; len];
+            reader
+                .read_exact(&mut ciphertext)
+                .map_err(|_| "Stream truncated before final segment".to_string())?;
+
+            let aad = Self::segment_aad(stream_id, index, flags);
+            let payload = self
+                .open(cipher_suite, &frame_header[5..], &ciphertext, &aad)
+                .map_err(|e| format!("Segment {} failed authentication: {}", index, e))?;
+            let segment = Self::decompress_payload(&payload)?;
+
+            writer.write_all(&segment).map_err(|e| e.to_string())?;
+            total += segment.len() as u64;
+
+            if flags & SEGMENT_FLAG_FINAL != 0 {
+                break;
+            }
+        }
+
+        if reader.read(&mut [0u8; 1]).map_err(|e| e.to_string())? != 0 {
+            return Err("Trailing data after final segment".to_string());
+        }
+        Ok(total)
+    }
+
+    fn store_segments<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        stream_id: [u8; STREAM_ID_SIZE],
+        mut on_segment: impl FnMut(&[u8]),
+    ) -> Result<u64, String> {
+        writer
+            .write_all(&[STREAM_TAG, self.cipher_suite as u8])
+            .and_then(|_| writer.write_all(&stream_id))
+            .map_err(|e| e.to_string())?;
+
+        // One segment of lookahead tells us which segment is final, so a
+        // truncated stream can never pass as complete.
+        let mut current = Self::read_segment(&mut reader)?;
+        let mut total = 0u64;
+        for index in 0u64.. {
+            let next = if current.len() == STREAM_SEGMENT_SIZE {
+                Self::read_segment(&mut reader)?
+            } else {
+                Vec::new()
+            };
+            let flags = if next.is_empty() {
+                SEGMENT_FLAG_FINAL
+            } else {
+                0
+            };
+
+            on_segment(&current);
+            let compression = Self::choose_compression(&current);
+            let compressed = compression.compress(&current).map_err(|e| e.to_string())?;
+            let mut payload = Vec::with_capacity(1 + compressed.len());
+            payload.push(compression as u8);
+            payload.extend_from_slice(&compressed);
+
+            let aad = Self::segment_aad(&stream_id, index, flags);
+            let nonce_bytes = self.segment_nonce(&aad, &payload);
+            let ciphertext = self.seal(self.cipher_suite, &nonce_bytes, &payload, &aad)?;
+
+            writer
+                .write_all(&(ciphertext.len() as u32).to_le_bytes())
+                .and_then(|_| writer.write_all(&[flags]))
+                .and_then(|_| writer.write_all(&nonce_bytes))
+                .and_then(|_| writer.write_all(&ciphertext))
+                .map_err(|e| e.to_string())?;
+            total += current.len() as u64;
+
+            if flags & SEGMENT_FLAG_FINAL != 0 {
+                break;
+            }
+            current = next;
+        }
+
+        writer.flush().map_err(|e| e.to_string())?;
+        Ok(total)
+    }
+
+    fn random_stream_id() -> [u8; STREAM_ID_SIZE] {
+        let mut stream_id = [0u8; STREAM_ID_SIZE];
+        HostRng.fill_bytes(&mut stream_id);
+        stream_id
+    }
+
+    /// Stream id for content with BLAKE3 hash `hash`: random, or derived from
+    /// the hash when convergent so equal content gets an equal stream header
+    fn content_stream_id(&self, hash: &[u8]) -> [u8; STREAM_ID_SIZE] {
+        match self.nonce_mode {
+            NonceMode::Random => Self::random_stream_id(),
+            NonceMode::Convergent => {
+                let mut hasher = blake3::Hasher::new_keyed(&self.convergent_key());
+                hasher.update(b"inos storage convergent stream id v1");
+                hasher.update(hash);
+                let mut stream_id = [0u8; STREAM_ID_SIZE];
+                stream_id.copy_from_slice(&hasher.finalize().as_bytes()[..STREAM_ID_SIZE]);
+                stream_id
+            }
+        }
+    }
+
+    /// Random, or when convergent a keyed hash of the segment's AAD (stream id,
+    /// index, flags) and tagged payload, so no two distinct segments share a nonce
+    fn segment_nonce(&self, aad: &[u8], payload: &[u8]) -> [u8; 12] {
+        let mut nonce_bytes = [0u8; 12];
+        match self.nonce_mode {
+            NonceMode::Random => HostRng.fill_bytes(&mut nonce_bytes),
+            NonceMode::Convergent => {
+                let mut hasher = blake3::Hasher::new_keyed(&self.convergent_key());
+                hasher.update(aad);
+                hasher.update(payload);
+                nonce_bytes.copy_from_slice(&hasher.finalize().as_bytes()[..12]);
+            }
+        }
+        nonce_bytes
+    }
+
+    fn read_segment<R: Read>(reader: &mut R) -> Result<Vec<u8>, String> {
+        let mut segment = Vec::with_capacity(STREAM_SEGMENT_SIZE);
+        reader
+            .take(STREAM_SEGMENT_SIZE as u64)
+            .read_to_end(&mut segment)
             .map_err(|e| e.to_string())?;
+        Ok(segment)
+    }
+
+    /// Binds each segment to its stream, position and final flag
+    /// Reordering, splicing or truncating segments then fails authentication.
+    fn segment_aad(stream_id: &[u8], index: u64, flags: u8) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(STREAM_ID_SIZE + 8 + 1);
+        aad.extend_from_slice(stream_id);
+        aad.extend_from_slice(&index.to_le_bytes());
+        aad.push(flags);
+        aad
+    }
+
+    fn decompress_payload(payload: &[u8]) -> Result<Vec<u8>, String> {
+        let (&tag, compressed) = payload
+            .split_first()
+            .ok_or_else(|| "Missing compression tag".to_string())?;
 
-        Ok(decompressed)
+        let compression = CompressionAlgorithm::from_tag(tag)
+            .map_err(|_| format!("Unknown compression tag: {}", tag))?;
+        compression
+            .decompress(compressed)
+            .map_err(|e| e.to_string())
+    }
+
+    fn seal(
+        &self,
+        cipher_suite: CipherSuite,
+        nonce_bytes: &[u8],
+        plaintext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let nonce = Nonce::from_slice(nonce_bytes);
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+        match cipher_suite {
+            CipherSuite::ChaCha20Poly1305 => ChaCha20Poly1305::new(&self.encryption_key)
+                .encrypt(nonce, payload)
+                .map_err(|e| e.to_string()),
+            CipherSuite::Aes256Gcm => Aes256Gcm::new(&self.encryption_key)
+                .encrypt(nonce, payload)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    fn open(
+        &self,
+        cipher_suite: CipherSuite,
+        nonce_bytes: &[u8],
+        ciphertext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let nonce = Nonce::from_slice(nonce_bytes);
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        match cipher_suite {
+            CipherSuite::ChaCha20Poly1305 => ChaCha20Poly1305::new(&self.encryption_key)
+                .decrypt(nonce, payload)
+                .map_err(|e| e.to_string()),
+            CipherSuite::Aes256Gcm => Aes256Gcm::new(&self.encryption_key)
+                .decrypt(nonce, payload)
+                .map_err(|e| e.to_string()),
+        }
     }
 
     /// Stores data using Content-Addressable Storage (CAS)
     /// Returns: (BLAKE3 hash, encrypted blob)
+    /// Inputs larger than one segment are streamed, hashing as each segment is sealed.
     pub fn store_cas_chunk(&self, data: &[u8]) -> Result<(String, Vec<u8>), String> {
         // 1. Compute BLAKE3 hash for deduplication
         let hash = sdk::compression::hash_blake3(data);
         let hash_str = hex::encode(&hash);
 
+        if data.len() > STREAM_SEGMENT_SIZE {
+            let mut blob = Vec::new();
+            let stream_id = self.content_stream_id(&hash);
+            self.store_segments(data, &mut blob, stream_id, |_| {})?;
+            return Ok((hash_str, blob));
+        }
+
         // 2. Store using standard encryption pipeline
         let blob = self.store_chunk(data)?;
 
@@ -242,3 +783,9 @@ mod tests;
 
 #[cfg(test)]
 mod cas_tests;
+
+#[cfg(test)]
+mod merkle_tests;
+
+#[cfg(test)]
+mod replication_tests;
@@ -0,0 +1,114 @@
+//! Merkle CAS: data split into independently sealed chunks under one BLAKE3 Merkle root
+//! A peer holding the root and chunk count can verify any single chunk from its
+//! proof path, without downloading the rest of the blob.
+
+use crate::StorageEngine;
+use sdk::hashing::{MerkleProof, TreeHasher};
+
+/// Default leaf size for Merkle CAS
+pub const MERKLE_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Sealed chunks plus the Merkle tree over their plaintext
+pub struct MerkleCas {
+    root: String,
+    chunk_size: usize,
+    blobs: Vec<Vec<u8>>,
+    tree: TreeHasher,
+}
+
+impl MerkleCas {
+    /// Root hash a peer needs to verify individual chunks
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.blobs.len()
+    }
+
+    /// Encrypted blob for one chunk, as stored by `StorageEngine::store_chunk`
+    pub fn chunk_blob(&self, index: usize) -> Option<&[u8]> {
+        self.blobs.get(index).map(Vec::as_slice)
+    }
+
+    /// Inclusion proof for one chunk
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        self.tree.generate_proof(index)
+    }
+}
+
+/// Verifies that `chunk` is leaf `index` of the `chunk_count`-leaf tree with `root`
+pub fn verify_chunk_proof(
+    root: &str,
+    chunk: &[u8],
+    index: usize,
+    chunk_count: usize,
+    proof: &MerkleProof,
+) -> bool {
+    proof.leaf_index == index
+        && proof.leaf_count == chunk_count
+        && TreeHasher::verify_proof(root, chunk, index, chunk_count, &proof.sibling_hashes)
+}
+
+impl StorageEngine {
+    /// Stores data as `chunk_size` leaves, each sealed with the standard pipeline
+    pub fn store_merkle_cas(&self, data: &[u8], chunk_size: usize) -> Result<MerkleCas, String> {
+        if chunk_size == 0 {
+            return Err("Chunk size must be non-zero".to_string());
+        }
+
+        let mut tree = TreeHasher::new(chunk_size);
+        let mut blobs = Vec::with_capacity(data.len().div_ceil(chunk_size));
+        for chunk in data.chunks(chunk_size) {
+            tree.add_chunk(chunk);
+            blobs.push(self.store_chunk(chunk)?);
+        }
+
+        Ok(MerkleCas {
+            root: tree.build_tree(),
+            chunk_size,
+            blobs,
+            tree,
+        })
+    }
+
+    /// Decrypts one chunk blob and checks it against `root` before returning it
+    pub fn retrieve_merkle_chunk(
+        &self,
+        blob: &[u8],
+        root: &str,
+        index: usize,
+        chunk_count: usize,
+        proof: &MerkleProof,
+    ) -> Result<Vec<u8>, String> {
+        let chunk = self.retrieve_chunk(blob)?;
+        if !verify_chunk_proof(root, &chunk, index, chunk_count, proof) {
+            return Err(format!("Merkle proof failed for chunk {}", index));
+        }
+        Ok(chunk)
+    }
+
+    /// Reassembles all chunks, rebuilding the tree to check them against the root
+    pub fn retrieve_merkle_cas(&self, cas: &MerkleCas) -> Result<Vec<u8>, String> {
+        let mut tree = TreeHasher::new(cas.chunk_size);
+        let mut data = Vec::with_capacity(cas.chunk_count() * cas.chunk_size);
+        for blob in &cas.blobs {
+            let chunk = self.retrieve_chunk(blob)?;
+            tree.add_chunk(&chunk);
+            data.extend_from_slice(&chunk);
+        }
+
+        let root = tree.build_tree();
+        if root != cas.root {
+            return Err(format!(
+                "Merkle root mismatch: expected {}, got {}",
+                cas.root, root
+            ));
+        }
+        Ok(data)
+    }
+}
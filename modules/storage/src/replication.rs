@@ -0,0 +1,121 @@
+//! Replication accounting: credits a peer for hosting a CAS chunk
+//! A (chunk hash, peer) pair is credited at most once, so a retried or
+//! duplicated replication cannot be paid twice.
+
+use crate::StorageEngine;
+use sdk::credits::{ReplicationIncentive, ReplicationTier};
+use std::collections::HashMap;
+
+/// One payout for a peer hosting a chunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplicationRecord {
+    pub chunk_hash: String,
+    pub peer: String,
+    pub tier: ReplicationTier,
+    pub bytes: u64,
+    pub credits: u64,
+}
+
+/// Payouts keyed by (chunk hash, peer)
+#[derive(Debug, Default)]
+pub struct ReplicationLedger {
+    incentive: ReplicationIncentive,
+    records: HashMap<(String, String), ReplicationRecord>,
+}
+
+impl ReplicationLedger {
+    pub fn new(incentive: ReplicationIncentive) -> Self {
+        Self {
+            incentive,
+            records: HashMap::new(),
+        }
+    }
+
+    /// Hosting reward: the tier's storage price plus the transfer, scaled by demand
+    pub fn reward(&self, tier: ReplicationTier, bytes: u64) -> u64 {
+        let base = tier.storage_cost() + self.incentive.calculate_bandwidth_reward(bytes);
+        (base as f64 * self.incentive.demand_multiplier).round() as u64
+    }
+
+    /// Credits `peer` for hosting `chunk_hash`.
+    /// Returns the credits awarded, or None if the pair was already credited.
+    pub fn record(
+        &mut self,
+        chunk_hash: &str,
+        peer: &str,
+        tier: ReplicationTier,
+        bytes: u64,
+    ) -> Option<u64> {
+        let key = (chunk_hash.to_string(), peer.to_string());
+        if self.records.contains_key(&key) {
+            return None;
+        }
+
+        let credits = self.reward(tier, bytes);
+        self.records.insert(
+            key,
+            ReplicationRecord {
+                chunk_hash: chunk_hash.to_string(),
+                peer: peer.to_string(),
+                tier,
+                bytes,
+                credits,
+            },
+        );
+        Some(credits)
+    }
+
+    pub fn is_credited(&self, chunk_hash: &str, peer: &str) -> bool {
+        self.records
+            .contains_key(&(chunk_hash.to_string(), peer.to_string()))
+    }
+
+    /// Total credits earned by `peer` across all chunks
+    pub fn credits_for(&self, peer: &str) -> u64 {
+        self.records
+            .values()
+            .filter(|r| r.peer == peer)
+            .map(|r| r.credits)
+            .sum()
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &ReplicationRecord> {
+        self.records.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl StorageEngine {
+    /// Stores a CAS chunk hosted by `peer` and credits the peer for it.
+    /// Returns: (BLAKE3 hash, encrypted blob, credits awarded; 0 if already credited)
+    pub fn replicate_cas_chunk(
+        &self,
+        data: &[u8],
+        peer: &str,
+        tier: ReplicationTier,
+    ) -> Result<(String, Vec<u8>, u64), String> {
+        let (hash, blob) = self.store_cas_chunk(data)?;
+        let credits = self
+            .replication_ledger
+            .lock()
+            .map_err(|_| "Replication ledger lock poisoned".to_string())?
+            .record(&hash, peer, tier, blob.len() as u64)
+            .unwrap_or(0);
+        Ok((hash, blob, credits))
+    }
+
+    /// Total credits `peer` has earned for hosting chunks from this engine
+    pub fn replication_credits(&self, peer: &str) -> u64 {
+        self.replication_ledger
+            .lock()
+            .map(|ledger| ledger.credits_for(peer))
+            .unwrap_or(0)
+    }
+}
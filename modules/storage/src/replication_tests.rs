@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod replication_tests {
+    use super::super::replication::ReplicationLedger;
+    use super::super::{
+        vault_free, vault_open, vault_replicate_cas_chunk, vault_replication_credits, StorageEngine,
+    };
+    use sdk::credits::{ReplicationIncentive, ReplicationTier};
+
+    // ========== REPLICATION ACCOUNTING TESTS ==========
+
+    #[test]
+    fn test_replicating_same_chunk_to_same_peer_credits_once() {
+        let key = [40u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let data = b"replicated chunk";
+
+        let (hash1, _, first) = engine
+            .replicate_cas_chunk(data, "peer-a", ReplicationTier::Hot)
+            .expect("Failed to replicate");
+        let (hash2, _, second) = engine
+            .replicate_cas_chunk(data, "peer-a", ReplicationTier::Hot)
+            .expect("Failed to replicate again");
+
+        assert_eq!(hash1, hash2);
+        assert!(first > 0, "First replication should earn credits");
+        assert_eq!(second, 0, "Duplicate replication must not be credited");
+        assert_eq!(engine.replication_credits("peer-a"), first);
+    }
+
+    #[test]
+    fn test_each_peer_credited_for_same_chunk() {
+        let key = [41u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let data = b"widely replicated";
+
+        let (_, _, a) = engine
+            .replicate_cas_chunk(data, "peer-a", ReplicationTier::Warm)
+            .expect("Failed to replicate to a");
+        let (_, _, b) = engine
+            .replicate_cas_chunk(data, "peer-b", ReplicationTier::Warm)
+            .expect("Failed to replicate to b");
+
+        assert!(a > 0 && b > 0);
+        assert_eq!(engine.replication_credits("peer-a"), a);
+        assert_eq!(engine.replication_credits("peer-b"), b);
+    }
+
+    #[test]
+    fn test_reward_follows_tier() {
+        let mut ledger = ReplicationLedger::new(ReplicationIncentive::new());
+        let hot = ledger
+            .record("hash-1", "peer-a", ReplicationTier::Hot, 1024)
+            .unwrap();
+        let archive = ledger
+            .record("hash-2", "peer-a", ReplicationTier::Archive, 1024)
+            .unwrap();
+
+        assert_eq!(hot, ReplicationTier::Hot.storage_cost());
+        assert_eq!(archive, ReplicationTier::Archive.storage_cost());
+        assert!(hot > archive);
+        assert_eq!(ledger.credits_for("peer-a"), hot + archive);
+        assert!(ledger
+            .record("hash-1", "peer-a", ReplicationTier::Hot, 1024)
+            .is_none());
+        assert_eq!(ledger.len(), 2);
+    }
+
+    #[test]
+    fn test_vault_export_credits_each_peer_once() {
+        let key = [42u8; 32];
+        assert_eq!(vault_open(key.as_ptr(), 0), 0);
+        assert_eq!(vault_open(key.as_ptr(), key.len()), 1);
+
+        let data = b"chunk replicated through the vault export";
+        let peer = b"peer-a";
+        let replicate = |tier: u32| {
+            let mut hash = [0u8; 32];
+            let mut len = 0usize;
+            let ptr = vault_replicate_cas_chunk(
+                data.as_ptr(),
+                data.len(),
+                peer.as_ptr(),
+                peer.len(),
+                tier,
+                hash.as_mut_ptr(),
+                &mut len,
+            );
+            assert!(!ptr.is_null());
+            let blob = unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec();
+            vault_free(ptr, len);
+            (hash, blob)
+        };
+
+        let (hash, blob) = replicate(0);
+        let credits = vault_replication_credits(peer.as_ptr(), peer.len());
+        assert!(credits > 0, "Replication should credit the hosting peer");
+
+        let (again_hash, again_blob) = replicate(0);
+        assert_eq!(hash, again_hash);
+        assert_eq!(blob, again_blob, "Vault chunks are convergent");
+        assert_eq!(
+            vault_replication_credits(peer.as_ptr(), peer.len()),
+            credits,
+            "Replicating the same chunk again must not be credited"
+        );
+
+        // The peer receives a blob the vault's key opens
+        let engine = StorageEngine::new(&key).unwrap();
+        let data_back = engine
+            .retrieve_cas_chunk(&blob, &hex::encode(hash))
+            .unwrap();
+        assert_eq!(data_back, data);
+
+        let mut len = 0usize;
+        let mut hash = [0u8; 32];
+        let unknown_tier = vault_replicate_cas_chunk(
+            data.as_ptr(),
+            data.len(),
+            peer.as_ptr(),
+            peer.len(),
+            9,
+            hash.as_mut_ptr(),
+            &mut len,
+        );
+        assert!(unknown_tier.is_null());
+    }
+}
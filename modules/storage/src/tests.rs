@@ -298,6 +298,198 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aes_gcm_roundtrip_is_tagged() {
+        use super::super::CipherSuite;
+
+        let key = [18u8; 32];
+        let engine = StorageEngine::with_cipher(&key, CipherSuite::Aes256Gcm)
+            .expect("Failed to create engine");
+
+        let data = b"AES sealed data";
+        let blob = engine.store_chunk(data).expect("Failed to encrypt");
+        assert_eq!(blob[0], CipherSuite::Aes256Gcm as u8);
+
+        // Retrieval follows the blob tag, not the engine default
+        let chacha_engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let retrieved = chacha_engine
+            .retrieve_chunk(&blob)
+            .expect("Failed to decrypt AES blob");
+        assert_eq!(retrieved, data);
+    }
+
+    #[test]
+    fn test_cross_cipher_decryption_fails() {
+        use super::super::CipherSuite;
+
+        let key = [19u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let mut blob = engine
+            .store_chunk(b"ChaCha data")
+            .expect("Failed to encrypt");
+
+        blob[0] = CipherSuite::Aes256Gcm as u8;
+        assert!(engine.retrieve_chunk(&blob).is_err());
+
+        blob[0] = 0xEE;
+        assert_eq!(
+            engine.retrieve_chunk(&blob).unwrap_err(),
+            "Unknown cipher tag: 238"
+        );
+    }
+
+    #[test]
+    fn test_each_compression_tag_roundtrips() {
+        use sdk::compression::CompressionAlgorithm;
+
+        let key = [20u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let data = b"tagged tagged tagged tagged payload".repeat(8);
+
+        for algorithm in [
+            CompressionAlgorithm::None,
+            CompressionAlgorithm::Brotli,
+            CompressionAlgorithm::Snappy,
+            CompressionAlgorithm::Lz4,
+        ] {
+            let blob = engine
+                .store_chunk_with(&data, algorithm)
+                .expect("Failed to store chunk");
+            let retrieved = engine
+                .retrieve_chunk(&blob)
+                .expect("Failed to retrieve chunk");
+            assert_eq!(retrieved, data, "{:?} should roundtrip", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_auto_compression_skips_incompressible_data() {
+        use sdk::compression::CompressionAlgorithm;
+
+        // xorshift noise stands in for already-compressed media
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let noise: Vec<u8> = (0..8192)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+
+        assert_eq!(
+            StorageEngine::choose_compression(&noise),
+            CompressionAlgorithm::None
+        );
+        assert_eq!(
+            StorageEngine::choose_compression(&[0u8; 8192]),
+            CompressionAlgorithm::Brotli
+        );
+
+        let engine = StorageEngine::new(&[21u8; 32]).expect("Failed to create engine");
+        let blob = engine.store_chunk(&noise).expect("Failed to store noise");
+        assert_eq!(engine.retrieve_chunk(&blob).unwrap(), noise);
+    }
+
+    fn streamed_test_data() -> Vec<u8> {
+        use super::super::STREAM_SEGMENT_SIZE;
+
+        // Two and a half segments: text that compresses, then bytes that don't
+        let mut data = b"segment ".repeat(STREAM_SEGMENT_SIZE / 8);
+        data.extend((0..STREAM_SEGMENT_SIZE * 3 / 2).map(|i| (i * 7919 % 251) as u8));
+        data
+    }
+
+    #[test]
+    fn test_retrieve_reads_untagged_legacy_blobs() {
+        use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce};
+
+        let key = [12u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let data = b"Written before blobs carried cipher tags".repeat(20);
+
+        // Baseline layout: [Nonce | ChaCha20(Brotli(data))], whatever the first nonce byte
+        for first in [0x00, 0x01, 0x02, 0x53, 0xff] {
+            let nonce = [first, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7];
+            let compressed = sdk::compression::CompressionAlgorithm::Brotli
+                .compress(&data)
+                .unwrap();
+            let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+                .encrypt(Nonce::from_slice(&nonce), compressed.as_ref())
+                .unwrap();
+            let blob = [&nonce[..], &ciphertext].concat();
+
+            let retrieved = engine
+                .retrieve_chunk(&blob)
+                .unwrap_or_else(|e| panic!("first byte {:#x}: {}", first, e));
+            assert_eq!(retrieved, data);
+        }
+    }
+
+    #[test]
+    fn test_stream_roundtrip_is_byte_identical() {
+        let key = [22u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let data = streamed_test_data();
+
+        let mut blob = Vec::new();
+        let stored = engine
+            .store_stream(data.as_slice(), &mut blob)
+            .expect("Failed to store stream");
+        assert_eq!(stored, data.len() as u64);
+
+        let mut retrieved = Vec::new();
+        let written = engine
+            .retrieve_stream(blob.as_slice(), &mut retrieved)
+            .expect("Failed to retrieve stream");
+        assert_eq!(written, data.len() as u64);
+        assert!(retrieved == data, "Streamed data should be byte-identical");
+
+        // retrieve_chunk recognises streamed blobs too
+        assert!(engine.retrieve_chunk(&blob).unwrap() == data);
+    }
+
+    #[test]
+    fn test_stream_corrupted_segment_fails_authentication() {
+        let key = [23u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let data = streamed_test_data();
+
+        let mut blob = Vec::new();
+        engine.store_stream(data.as_slice(), &mut blob).unwrap();
+
+        // Header is 18 bytes; each frame is [len 4][flags 1][nonce 12][ciphertext]
+        let first_len = u32::from_le_bytes(blob[18..22].try_into().unwrap()) as usize;
+        let second_ciphertext = 18 + 17 + first_len + 17;
+        blob[second_ciphertext + 5] ^= 0x01;
+
+        let err = engine
+            .retrieve_stream(blob.as_slice(), &mut Vec::new())
+            .unwrap_err();
+        assert!(
+            err.starts_with("Segment 1 failed authentication"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_stream_truncated_after_segment_fails() {
+        let key = [24u8; 32];
+        let engine = StorageEngine::new(&key).expect("Failed to create engine");
+        let data = streamed_test_data();
+
+        let mut blob = Vec::new();
+        engine.store_stream(data.as_slice(), &mut blob).unwrap();
+
+        // Drop everything after the first complete segment
+        let first_len = u32::from_le_bytes(blob[18..22].try_into().unwrap()) as usize;
+        blob.truncate(18 + 17 + first_len);
+
+        let result = engine.retrieve_stream(blob.as_slice(), &mut Vec::new());
+        assert_eq!(result.unwrap_err(), "Stream truncated before final segment");
+    }
+
     // ========== PERFORMANCE TESTS ==========
 
     #[test]